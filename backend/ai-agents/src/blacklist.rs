@@ -0,0 +1,77 @@
+use alloy::primitives::Address;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::{collections::HashMap, str::FromStr};
+
+// Sanctioned or known-malicious addresses the Security Agent refuses to pay.
+// Keys are parsed `Address`es, so checksummed, lowercase and mixed-case input
+// all match the same entry.
+pub struct Blacklist {
+    entries: HashMap<Address, String>, // Address -> label shown in the rejection
+}
+
+// BLACKLIST_PATH file entries: a bare address or one with a label
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Address(String),
+    Labeled { address: String, label: String },
+}
+
+impl Blacklist {
+    pub fn new(entries: HashMap<Address, String>) -> Self {
+        Self { entries }
+    }
+
+    // BLACKLIST_PATH (JSON array), plus BLACKLIST_ADDRESSES (comma-separated) as
+    // an override for entries not yet in the file
+    pub fn from_env() -> Result<Self> {
+        let mut entries = HashMap::new();
+
+        if let Some(path) = std::env::var("BLACKLIST_PATH")
+            .ok()
+            .filter(|p| !p.is_empty())
+        {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read blacklist {}", path))?;
+            entries.extend(Self::parse(&contents).with_context(|| path.clone())?);
+        }
+
+        for address in std::env::var("BLACKLIST_ADDRESSES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+        {
+            entries.insert(parse_address(address)?, "BLACKLIST_ADDRESSES".to_string());
+        }
+
+        Ok(Self::new(entries))
+    }
+
+    // `["0xabc..", {"address": "0xdef..", "label": "OFAC SDN"}]`
+    pub fn parse(json: &str) -> Result<HashMap<Address, String>> {
+        let entries: Vec<Entry> = serde_json::from_str(json)?;
+        entries
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Address(address) => Ok((parse_address(&address)?, "blacklist".to_string())),
+                Entry::Labeled { address, label } => Ok((parse_address(&address)?, label)),
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // Label of the matching entry, if the address is blacklisted
+    pub fn check(&self, address: Address) -> Option<&str> {
+        self.entries.get(&address).map(String::as_str)
+    }
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address.trim())
+        .map_err(|_| anyhow!("Invalid blacklist address '{}'", address))
+}
@@ -0,0 +1,339 @@
+use alloy::{
+    primitives::{Address, Bytes, U256},
+    providers::ProviderBuilder,
+    sol,
+    sol_types::SolCall,
+};
+use serde::{Deserialize, Serialize};
+use shared::{observability::time_rpc, tokens::TokenRegistry};
+use std::str::FromStr;
+use tracing::warn;
+
+use crate::TransactionData;
+
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 amount) external returns (bool);
+        function transferFrom(address from, address to, uint256 amount) external returns (bool);
+        function approve(address spender, uint256 amount) external returns (bool);
+    }
+
+    interface ISafeAdmin {
+        function addOwnerWithThreshold(address owner, uint256 threshold) external;
+        function removeOwner(address prevOwner, address owner, uint256 threshold) external;
+        function swapOwner(address prevOwner, address oldOwner, address newOwner) external;
+        function changeThreshold(uint256 threshold) external;
+    }
+}
+
+// Structured view of calldata the agents know how to interpret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DecodedCall {
+    Erc20Transfer {
+        token: String,
+        recipient: String,
+        amount: String,
+        // From the token registry; None if the token couldn't be resolved
+        symbol: Option<String>,
+        formatted_amount: Option<String>,
+    },
+    Erc20TransferFrom {
+        token: String,
+        from: String,
+        recipient: String,
+        amount: String,
+        symbol: Option<String>,
+        formatted_amount: Option<String>,
+    },
+    Erc20Approve {
+        token: String,
+        spender: String,
+        amount: String,
+        unlimited: bool, // approve(spender, type(uint256).max)
+        symbol: Option<String>,
+        formatted_amount: Option<String>,
+    },
+    SafeAddOwner {
+        owner: String,
+        threshold: String,
+    },
+    SafeRemoveOwner {
+        owner: String,
+        threshold: String,
+    },
+    SafeSwapOwner {
+        old_owner: String,
+        new_owner: String,
+    },
+    SafeChangeThreshold {
+        threshold: String,
+    },
+}
+
+// Decodes known calldata so agents reason about the call rather than an opaque
+// blob. Token amounts are scaled by the registry's decimals, resolving tokens it
+// doesn't know from chain.
+pub async fn enrich(transaction: &mut TransactionData, tokens: &TokenRegistry, rpc_url: &str) {
+    transaction.decoded = decode_calldata(&transaction.to, &transaction.data);
+
+    let (token, amount, symbol, formatted_amount) = match &mut transaction.decoded {
+        Some(DecodedCall::Erc20Transfer {
+            token,
+            amount,
+            symbol,
+            formatted_amount,
+            ..
+        })
+        | Some(DecodedCall::Erc20TransferFrom {
+            token,
+            amount,
+            symbol,
+            formatted_amount,
+            ..
+        })
+        | Some(DecodedCall::Erc20Approve {
+            token,
+            amount,
+            symbol,
+            formatted_amount,
+            ..
+        }) => (&*token, &*amount, symbol, formatted_amount),
+        _ => return,
+    };
+    let (Ok(token), Ok(amount)) = (Address::from_str(token), U256::from_str(amount)) else {
+        return;
+    };
+
+    if tokens.get(token).is_none() {
+        let Ok(url) = rpc_url.parse() else {
+            return;
+        };
+        let provider = ProviderBuilder::new().connect_http(url);
+        if let Err(e) = time_rpc("token_metadata", tokens.resolve(&provider, token)).await {
+            warn!("Could not resolve token {}: {}", token, e);
+            return;
+        }
+    }
+
+    *symbol = tokens.get(token).map(|info| info.symbol);
+    *formatted_amount = tokens.format_amount(token, amount);
+}
+
+pub fn decode_calldata(to: &str, data: &str) -> Option<DecodedCall> {
+    let data = Bytes::from_str(data).ok()?;
+    let selector: [u8; 4] = data.get(..4)?.try_into().ok()?;
+    let token = to.to_string();
+
+    match selector {
+        IERC20::transferCall::SELECTOR => {
+            let call = IERC20::transferCall::abi_decode(&data).ok()?;
+            Some(DecodedCall::Erc20Transfer {
+                token,
+                recipient: call.to.to_string(),
+                amount: call.amount.to_string(),
+                symbol: None,
+                formatted_amount: None,
+            })
+        }
+        IERC20::transferFromCall::SELECTOR => {
+            let call = IERC20::transferFromCall::abi_decode(&data).ok()?;
+            Some(DecodedCall::Erc20TransferFrom {
+                token,
+                from: call.from.to_string(),
+                recipient: call.to.to_string(),
+                amount: call.amount.to_string(),
+                symbol: None,
+                formatted_amount: None,
+            })
+        }
+        IERC20::approveCall::SELECTOR => {
+            let call = IERC20::approveCall::abi_decode(&data).ok()?;
+            Some(DecodedCall::Erc20Approve {
+                token,
+                spender: call.spender.to_string(),
+                amount: call.amount.to_string(),
+                unlimited: call.amount == U256::MAX,
+                symbol: None,
+                formatted_amount: None,
+            })
+        }
+        ISafeAdmin::addOwnerWithThresholdCall::SELECTOR => {
+            let call = ISafeAdmin::addOwnerWithThresholdCall::abi_decode(&data).ok()?;
+            Some(DecodedCall::SafeAddOwner {
+                owner: call.owner.to_string(),
+                threshold: call.threshold.to_string(),
+            })
+        }
+        ISafeAdmin::removeOwnerCall::SELECTOR => {
+            let call = ISafeAdmin::removeOwnerCall::abi_decode(&data).ok()?;
+            Some(DecodedCall::SafeRemoveOwner {
+                owner: call.owner.to_string(),
+                threshold: call.threshold.to_string(),
+            })
+        }
+        ISafeAdmin::swapOwnerCall::SELECTOR => {
+            let call = ISafeAdmin::swapOwnerCall::abi_decode(&data).ok()?;
+            Some(DecodedCall::SafeSwapOwner {
+                old_owner: call.oldOwner.to_string(),
+                new_owner: call.newOwner.to_string(),
+            })
+        }
+        ISafeAdmin::changeThresholdCall::SELECTOR => {
+            let call = ISafeAdmin::changeThresholdCall::abi_decode(&data).ok()?;
+            Some(DecodedCall::SafeChangeThreshold {
+                threshold: call.threshold.to_string(),
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::tokens::TokenInfo;
+    use std::collections::HashMap;
+
+    const USDT: Address = Address::repeat_byte(0x7c);
+
+    fn call_to(token: Address, data: Vec<u8>) -> TransactionData {
+        TransactionData {
+            safe: String::new(),
+            to: token.to_string(),
+            value: "0".to_string(),
+            data: Bytes::from(data).to_string(),
+            nonce: 0,
+            existing_signers: Vec::new(),
+            decoded: None,
+        }
+    }
+
+    fn registry() -> TokenRegistry {
+        TokenRegistry::new(HashMap::from([(
+            USDT,
+            TokenInfo {
+                symbol: "USDT".to_string(),
+                decimals: 6,
+            },
+        )]))
+    }
+
+    #[tokio::test]
+    async fn known_token_transfer_is_scaled_by_its_decimals() {
+        let recipient = Address::repeat_byte(0x11);
+        let data = IERC20::transferCall {
+            to: recipient,
+            amount: U256::from(1_500_000u64),
+        }
+        .abi_encode();
+        let mut tx = call_to(USDT, data);
+
+        // Nothing listens here, so the registry alone has to answer
+        enrich(&mut tx, &registry(), "http://127.0.0.1:9").await;
+
+        match tx.decoded {
+            Some(DecodedCall::Erc20Transfer {
+                token,
+                recipient: to,
+                amount,
+                symbol,
+                formatted_amount,
+            }) => {
+                assert_eq!(token, USDT.to_string());
+                assert_eq!(to, recipient.to_string());
+                assert_eq!(amount, "1500000");
+                assert_eq!(symbol.as_deref(), Some("USDT"));
+                assert_eq!(formatted_amount.as_deref(), Some("1.500000"));
+            }
+            other => panic!("expected an ERC20 transfer, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn unresolvable_token_is_decoded_without_metadata() {
+        let unknown = Address::repeat_byte(0x99);
+        let data = IERC20::transferCall {
+            to: Address::repeat_byte(0x11),
+            amount: U256::from(42u64),
+        }
+        .abi_encode();
+        let mut tx = call_to(unknown, data);
+
+        enrich(&mut tx, &registry(), "http://127.0.0.1:9").await;
+
+        match tx.decoded {
+            Some(DecodedCall::Erc20Transfer {
+                amount,
+                symbol,
+                formatted_amount,
+                ..
+            }) => {
+                assert_eq!(amount, "42");
+                assert_eq!(symbol, None);
+                assert_eq!(formatted_amount, None);
+            }
+            other => panic!("expected an ERC20 transfer, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn transfer_calldata_is_decoded() {
+        let recipient = Address::repeat_byte(0x11);
+        let data = IERC20::transferCall {
+            to: recipient,
+            amount: U256::from(7u64),
+        }
+        .abi_encode();
+
+        match decode_calldata(&USDT.to_string(), &Bytes::from(data.clone()).to_string()) {
+            Some(DecodedCall::Erc20Transfer {
+                token,
+                recipient: to,
+                amount,
+                ..
+            }) => {
+                assert_eq!(token, USDT.to_string());
+                assert_eq!(to, recipient.to_string());
+                assert_eq!(amount, "7");
+            }
+            other => panic!("expected an ERC20 transfer, got {:?}", other),
+        }
+        // Cut short of the amount word
+        let truncated = Bytes::from(data[..36].to_vec()).to_string();
+        assert!(decode_calldata(&USDT.to_string(), &truncated).is_none());
+    }
+
+    #[test]
+    fn only_a_max_approval_is_unlimited() {
+        let approve = |amount: U256| {
+            let data = IERC20::approveCall {
+                spender: Address::repeat_byte(0x22),
+                amount,
+            }
+            .abi_encode();
+            decode_calldata(&USDT.to_string(), &Bytes::from(data).to_string())
+        };
+
+        match approve(U256::MAX) {
+            Some(DecodedCall::Erc20Approve {
+                spender,
+                amount,
+                unlimited,
+                ..
+            }) => {
+                assert_eq!(spender, Address::repeat_byte(0x22).to_string());
+                assert_eq!(amount, U256::MAX.to_string());
+                assert!(unlimited);
+            }
+            other => panic!("expected an ERC20 approval, got {:?}", other),
+        }
+        assert!(matches!(
+            approve(U256::MAX - U256::from(1)),
+            Some(DecodedCall::Erc20Approve {
+                unlimited: false,
+                ..
+            })
+        ));
+    }
+}
@@ -1,3 +1,7 @@
+mod blacklist;
+mod enrichment;
+
+use alloy_primitives::{Address, U256};
 use async_trait::async_trait;
 use axum::{
     extract::State,
@@ -6,10 +10,22 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use blacklist::Blacklist;
+use chrono::{DateTime, Utc};
+use enrichment::DecodedCall;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tower_http::cors::CorsLayer;
-use tracing::info;
+use shared::{
+    constants::KAIA_TESTNET_RPC, deployment::DeploymentMode, observability, tokens::TokenRegistry,
+    types::SignerType, SafeWalletError,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::Semaphore, task::JoinSet};
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Clone)]
@@ -17,31 +33,171 @@ struct AppState {
     cfo_agent: Arc<CfoAgent>,
     security_agent: Arc<SecurityAgent>,
     onchain_analyst: Arc<OnchainAnalyst>,
+    batch_concurrency: usize,
+    agent_timeout: Duration,
+    tokens: Arc<TokenRegistry>,
+    rpc_url: String, // Token metadata lookups for tokens missing from the registry
+    decision_policy: DecisionPolicy,
+}
+
+// How /decision turns the three verdicts into one recommendation
+#[derive(Debug, Clone)]
+struct DecisionPolicy {
+    rule: AggregationRule,
+    max_risk: f64, // Highest individual risk score still allowed to pass
+    // A proposal already signed by one of these passes up to trusted_max_risk instead
+    trusted_signers: Vec<SignerType>,
+    trusted_max_risk: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AggregationRule {
+    All,      // Every agent must approve
+    Majority, // More than half must approve
 }
 
 #[async_trait]
 trait AiAgent: Send + Sync {
-    async fn analyze(&self, transaction: &TransactionData) -> AnalysisResult;
+    fn name(&self) -> &str;
+    async fn analyze(&self, transaction: &TransactionData) -> shared::Result<AnalysisResult>;
+}
+
+// Runs one agent under the configured deadline so a hung backend surfaces as an
+// AgentError instead of stalling the whole consensus. Agents score synchronously,
+// so they run on the blocking pool where the deadline can fire while they work;
+// an abandoned run finishes in the background and its result is dropped.
+async fn run_agent(
+    agent: Arc<dyn AiAgent>,
+    transaction: &TransactionData,
+    timeout: Duration,
+) -> shared::Result<AnalysisResult> {
+    let name = agent.name().to_string();
+    let transaction = transaction.clone();
+    let run = tokio::task::spawn_blocking(move || {
+        tokio::runtime::Handle::current().block_on(agent.analyze(&transaction))
+    });
+
+    match tokio::time::timeout(timeout, run).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(SafeWalletError::AgentError(format!(
+            "{} failed: {}",
+            name, e
+        ))),
+        Err(_) => Err(SafeWalletError::AgentError(format!(
+            "{} timed out after {}s",
+            name,
+            timeout.as_secs()
+        ))),
+    }
+}
+
+// Agent failures are upstream problems (502); validation errors are the caller's
+fn agent_error_status(e: SafeWalletError) -> StatusCode {
+    error!("Analysis failed: {}", e);
+    match e {
+        SafeWalletError::AgentError(_) => StatusCode::BAD_GATEWAY,
+        SafeWalletError::ValidationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
 }
 
 struct CfoAgent {
     name: String,
+    max_data_bytes: usize,
+    max_tx_value: U256,
+    tx_limit: Option<U256>,    // Budget per transaction (CFO_TX_LIMIT_WEI)
+    daily_limit: Option<U256>, // Budget per Safe over any 24 hours (CFO_DAILY_LIMIT_WEI)
+    spend: Mutex<HashMap<Address, RollingSpend>>, // Keyed by Safe address
 }
 
+// Native value approved by the CFO agent for one Safe over the last 24 hours.
+// One entry per Safe nonce: only one proposal can execute at a nonce, so
+// analyzing it again (refresh, batch, decision) replaces rather than adds.
+#[derive(Default)]
+struct RollingSpend {
+    approvals: VecDeque<(DateTime<Utc>, u64, U256)>,
+}
+
+impl RollingSpend {
+    // Total for every nonce except `nonce`, whose entry is about to be replaced
+    fn total_excluding(&mut self, now: DateTime<Utc>, nonce: u64) -> U256 {
+        let window_start = now - chrono::Duration::hours(24);
+        while self
+            .approvals
+            .front()
+            .is_some_and(|(at, _, _)| *at <= window_start)
+        {
+            self.approvals.pop_front();
+        }
+        // Saturates rather than wrapping so a huge day can't roll back to zero
+        self.approvals
+            .iter()
+            .filter(|(_, n, _)| *n != nonce)
+            .fold(U256::ZERO, |sum, (_, _, value)| sum.saturating_add(*value))
+    }
+
+    fn record(&mut self, now: DateTime<Utc>, nonce: u64, value: U256) {
+        self.approvals.retain(|(_, n, _)| *n != nonce);
+        self.approvals.push_back((now, nonce, value));
+    }
+}
+
+// How much of `limit` `amount` uses, from 0.0 to 1.0
+fn limit_usage(amount: U256, limit: U256) -> f64 {
+    if limit.is_zero() {
+        return 1.0;
+    }
+    let bps = amount.saturating_mul(U256::from(10_000)) / limit;
+    bps.min(U256::from(10_000)).to::<u64>() as f64 / 10_000.0
+}
+
+// 1e9 KAIA: far beyond anything a treasury would move in one transaction
+const DEFAULT_CFO_MAX_TX_VALUE_WEI: &str = "1000000000000000000000000000";
+
 struct SecurityAgent {
     name: String,
+    max_data_bytes: usize,
+    blacklist: Blacklist,
 }
 
 struct OnchainAnalyst {
     name: String,
+    max_data_bytes: usize,
+    // Demo mode vouches for calldata it can't decode; production flags it instead
+    deployment_mode: DeploymentMode,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+const DEFAULT_MAX_DATA_BYTES: usize = 64 * 1024;
+
+// Per-agent calldata cap, e.g. CFO_MAX_DATA_BYTES, falling back to MAX_ANALYSIS_DATA_BYTES
+fn max_data_bytes_for(agent_prefix: &str) -> usize {
+    std::env::var(format!("{}_MAX_DATA_BYTES", agent_prefix))
+        .or_else(|_| std::env::var("MAX_ANALYSIS_DATA_BYTES"))
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_MAX_DATA_BYTES)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct TransactionData {
+    #[serde(default)]
+    safe: String, // Safe the proposal is for; keys the CFO daily budget
     to: String,
     value: String,
     data: String,
     nonce: u64,
+    #[serde(default)]
+    existing_signers: Vec<SignerType>, // Who has already signed the proposal
+    // Filled in by the enrichment step, never taken from the request
+    #[serde(skip_deserializing)]
+    decoded: Option<DecodedCall>,
+}
+
+impl TransactionData {
+    fn data_len(&self) -> usize {
+        self.data.trim_start_matches("0x").len() / 2
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,6 +206,149 @@ struct AnalysisResult {
     approved: bool,
     risk_score: f64,
     reasons: Vec<String>,
+    #[serde(default)]
+    reason_codes: Vec<ReasonCode>,
+}
+
+// Machine-readable counterpart to the free-form reasons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReasonCode {
+    PayloadTooLarge,
+    InvalidValue,
+    ValueTooLarge,
+    OverTxLimit,
+    OverDailyBudget,
+    Blacklisted,
+    UnlimitedApproval,
+    UnrecognizedCalldata,
+}
+
+impl AnalysisResult {
+    // Oversized calldata isn't analyzed at all; it gets a conservative rejection instead
+    fn check_payload_size(
+        agent: &str,
+        transaction: &TransactionData,
+        max_data_bytes: usize,
+    ) -> Option<Self> {
+        let size = transaction.data_len();
+        if size <= max_data_bytes {
+            return None;
+        }
+
+        warn!(
+            "{} skipping analysis: calldata is {} bytes (limit {})",
+            agent, size, max_data_bytes
+        );
+        Some(Self {
+            agent: agent.to_string(),
+            approved: false,
+            risk_score: 1.0,
+            reasons: vec![format!(
+                "Calldata of {} bytes exceeds the {} byte analysis limit",
+                size, max_data_bytes
+            )],
+            reason_codes: vec![ReasonCode::PayloadTooLarge],
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConsensusResult {
+    approved: bool,
+    aggregate_risk: f64,
+    results: Vec<AnalysisResult>,
+}
+
+// One per transaction of an /analyze-batch request, in input order. A failed
+// analysis is reported in its own slot instead of failing the whole batch.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum BatchAnalysisItem {
+    Result(ConsensusResult),
+    Error(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SigningRecommendation {
+    Sign,
+    DoNotSign,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DecisionResult {
+    approved: bool,
+    recommendation: SigningRecommendation,
+    rule: AggregationRule,
+    approvals: usize,
+    max_risk: f64,
+    risk_threshold: f64,
+    // Trusted signer whose signature raised risk_threshold, if any
+    relaxed_by: Option<SignerType>,
+    reasons: Vec<String>, // Every agent's reasons, prefixed with the agent name
+    results: Vec<AnalysisResult>,
+}
+
+impl DecisionPolicy {
+    fn decide(
+        &self,
+        results: Vec<AnalysisResult>,
+        existing_signers: &[SignerType],
+    ) -> DecisionResult {
+        let approvals = results.iter().filter(|r| r.approved).count();
+        let max_risk = results.iter().map(|r| r.risk_score).fold(0.0, f64::max);
+        let enough_approvals = match self.rule {
+            AggregationRule::All => approvals == results.len(),
+            AggregationRule::Majority => approvals * 2 > results.len(),
+        };
+        let relaxed_by = existing_signers
+            .iter()
+            .copied()
+            .find(|signer| self.trusted_signers.contains(signer));
+        let risk_threshold = match relaxed_by {
+            Some(_) => self.trusted_max_risk.max(self.max_risk),
+            None => self.max_risk,
+        };
+        let approved = enough_approvals && max_risk < risk_threshold;
+
+        let mut reasons: Vec<String> = results
+            .iter()
+            .flat_map(|r| {
+                r.reasons
+                    .iter()
+                    .map(move |reason| format!("{}: {}", r.agent, reason))
+            })
+            .collect();
+        if let Some(signer) = relaxed_by {
+            reasons.push(format!(
+                "Risk threshold relaxed to {:.2}: already signed by a trusted {:?} signer",
+                risk_threshold, signer
+            ));
+        }
+        if max_risk >= risk_threshold {
+            reasons.push(format!(
+                "Max risk score {:.2} is at or above the {:.2} threshold",
+                max_risk, risk_threshold
+            ));
+        }
+
+        DecisionResult {
+            approved,
+            recommendation: if approved {
+                SigningRecommendation::Sign
+            } else {
+                SigningRecommendation::DoNotSign
+            },
+            rule: self.rule,
+            approvals,
+            max_risk,
+            risk_threshold,
+            relaxed_by,
+            reasons,
+            results,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -61,64 +360,388 @@ struct HealthResponse {
 
 #[async_trait]
 impl AiAgent for CfoAgent {
-    async fn analyze(&self, transaction: &TransactionData) -> AnalysisResult {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn analyze(&self, transaction: &TransactionData) -> shared::Result<AnalysisResult> {
         info!("CFO Agent analyzing transaction to: {}", transaction.to);
 
-        AnalysisResult {
+        if let Some(result) =
+            AnalysisResult::check_payload_size(&self.name, transaction, self.max_data_bytes)
+        {
+            return Ok(result);
+        }
+
+        let value = match U256::from_str(&transaction.value) {
+            Ok(value) => value,
+            Err(_) => {
+                return Ok(AnalysisResult {
+                    agent: self.name.clone(),
+                    approved: false,
+                    risk_score: 1.0,
+                    reasons: vec![format!("Unparseable value: {}", transaction.value)],
+                    reason_codes: vec![ReasonCode::InvalidValue],
+                });
+            }
+        };
+
+        if value > self.max_tx_value {
+            warn!(
+                "CFO Agent rejecting implausible value {} (max {})",
+                value, self.max_tx_value
+            );
+            return Ok(AnalysisResult {
+                agent: self.name.clone(),
+                approved: false,
+                risk_score: 1.0,
+                reasons: vec![format!(
+                    "Value of {} wei exceeds the sane maximum of {} wei",
+                    value, self.max_tx_value
+                )],
+                reason_codes: vec![ReasonCode::ValueTooLarge],
+            });
+        }
+
+        if let Some(limit) = self.tx_limit.filter(|limit| value > *limit) {
+            warn!(
+                "CFO Agent rejecting {} wei above the per-transaction limit {}",
+                value, limit
+            );
+            return Ok(AnalysisResult {
+                agent: self.name.clone(),
+                approved: false,
+                risk_score: 1.0,
+                reasons: vec![format!(
+                    "exceeds per-transaction limit: {} of {} wei",
+                    value, limit
+                )],
+                reason_codes: vec![ReasonCode::OverTxLimit],
+            });
+        }
+
+        // Parsed rather than compared as text so casing can't open a fresh budget
+        let safe = match Address::from_str(&transaction.safe) {
+            Ok(safe) => safe,
+            Err(_) if self.daily_limit.is_none() => Address::ZERO,
+            Err(_) => {
+                return Ok(AnalysisResult {
+                    agent: self.name.clone(),
+                    approved: false,
+                    risk_score: 1.0,
+                    reasons: vec![format!(
+                        "Unparseable Safe address '{}', daily budget can't be checked",
+                        transaction.safe
+                    )],
+                    reason_codes: vec![ReasonCode::InvalidValue],
+                });
+            }
+        };
+
+        // Checked and recorded under one lock so concurrent analyses can't both
+        // squeeze under the daily cap
+        let now = Utc::now();
+        let spent_today = {
+            let mut spend = self.spend.lock().unwrap_or_else(|e| e.into_inner());
+            let rolling = spend.entry(safe).or_default();
+            let used = rolling.total_excluding(now, transaction.nonce);
+            let after = used.saturating_add(value);
+
+            if let Some(limit) = self.daily_limit.filter(|limit| after > *limit) {
+                warn!(
+                    "CFO Agent rejecting {} wei for Safe {}: {} of {} used in the last 24h",
+                    value, transaction.safe, used, limit
+                );
+                return Ok(AnalysisResult {
+                    agent: self.name.clone(),
+                    approved: false,
+                    risk_score: 1.0,
+                    reasons: vec![format!(
+                        "exceeds daily budget: {} of {} wei used, {} wei requested",
+                        used, limit, value
+                    )],
+                    reason_codes: vec![ReasonCode::OverDailyBudget],
+                });
+            }
+
+            rolling.record(now, transaction.nonce, value);
+            after
+        };
+
+        // Baseline risk without limits; closer to either limit means riskier
+        let usage = [
+            self.tx_limit.map(|limit| limit_usage(value, limit)),
+            self.daily_limit
+                .map(|limit| limit_usage(spent_today, limit)),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(0.0, f64::max);
+        let risk_score = if self.tx_limit.is_none() && self.daily_limit.is_none() {
+            0.2
+        } else {
+            0.1 + 0.8 * usage
+        };
+
+        let reason = match &transaction.decoded {
+            Some(DecodedCall::Erc20Transfer {
+                token,
+                amount,
+                symbol,
+                formatted_amount,
+                ..
+            })
+            | Some(DecodedCall::Erc20TransferFrom {
+                token,
+                amount,
+                symbol,
+                formatted_amount,
+                ..
+            }) => match (formatted_amount, symbol) {
+                (Some(formatted), Some(symbol)) => {
+                    format!(
+                        "Token transfer of {} {} within budget limits",
+                        formatted, symbol
+                    )
+                }
+                _ => format!(
+                    "Token transfer of {} (token {}) within budget limits",
+                    amount, token
+                ),
+            },
+            _ => "Within budget limits".to_string(),
+        };
+
+        Ok(AnalysisResult {
             agent: self.name.clone(),
             approved: true,
-            risk_score: 0.2,
-            reasons: vec!["Within budget limits".to_string()],
-        }
+            risk_score,
+            reasons: vec![
+                reason,
+                format!("{} wei approved in the last 24h", spent_today),
+            ],
+            reason_codes: Vec::new(),
+        })
     }
 }
 
 #[async_trait]
 impl AiAgent for SecurityAgent {
-    async fn analyze(&self, transaction: &TransactionData) -> AnalysisResult {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn analyze(&self, transaction: &TransactionData) -> shared::Result<AnalysisResult> {
         info!(
             "Security Agent analyzing transaction to: {}",
             transaction.to
         );
 
-        AnalysisResult {
+        if let Some(result) =
+            AnalysisResult::check_payload_size(&self.name, transaction, self.max_data_bytes)
+        {
+            return Ok(result);
+        }
+
+        let to = Address::from_str(&transaction.to).map_err(|_| {
+            SafeWalletError::ValidationError(format!(
+                "Invalid recipient address '{}'",
+                transaction.to
+            ))
+        })?;
+
+        if let Some(label) = self.blacklist.check(to) {
+            warn!(
+                "Security Agent rejecting blacklisted recipient {} ({})",
+                to, label
+            );
+            return Ok(AnalysisResult {
+                agent: self.name.clone(),
+                approved: false,
+                risk_score: 1.0,
+                reasons: vec![format!("Recipient {} is blacklisted: {}", to, label)],
+                reason_codes: vec![ReasonCode::Blacklisted],
+            });
+        }
+
+        Ok(AnalysisResult {
             agent: self.name.clone(),
             approved: true,
             risk_score: 0.1,
             reasons: vec!["Address not in blacklist".to_string()],
-        }
+            reason_codes: Vec::new(),
+        })
     }
 }
 
 #[async_trait]
 impl AiAgent for OnchainAnalyst {
-    async fn analyze(&self, transaction: &TransactionData) -> AnalysisResult {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn analyze(&self, transaction: &TransactionData) -> shared::Result<AnalysisResult> {
         info!(
             "Onchain Analyst analyzing transaction to: {}",
             transaction.to
         );
 
-        AnalysisResult {
-            agent: self.name.clone(),
-            approved: true,
-            risk_score: 0.3,
-            reasons: vec!["Contract verified on chain".to_string()],
+        if let Some(result) =
+            AnalysisResult::check_payload_size(&self.name, transaction, self.max_data_bytes)
+        {
+            return Ok(result);
         }
+
+        let (risk_score, reason, reason_codes) = match &transaction.decoded {
+            Some(DecodedCall::Erc20Transfer {
+                token,
+                recipient,
+                amount,
+                ..
+            }) => (
+                0.3,
+                format!(
+                    "transfer(to={}, amount={}) on token {}",
+                    recipient, amount, token
+                ),
+                Vec::new(),
+            ),
+            Some(DecodedCall::Erc20TransferFrom {
+                token,
+                from,
+                recipient,
+                amount,
+                ..
+            }) => (
+                0.4,
+                format!(
+                    "transferFrom(from={}, to={}, amount={}) on token {}",
+                    from, recipient, amount, token
+                ),
+                Vec::new(),
+            ),
+            // An unlimited allowance lets the spender drain the token at any later time
+            Some(DecodedCall::Erc20Approve {
+                token,
+                spender,
+                unlimited: true,
+                ..
+            }) => (
+                0.9,
+                format!(
+                    "approve(spender={}, amount=unlimited) on token {}",
+                    spender, token
+                ),
+                vec![ReasonCode::UnlimitedApproval],
+            ),
+            Some(DecodedCall::Erc20Approve {
+                token,
+                spender,
+                amount,
+                ..
+            }) => (
+                0.4,
+                format!(
+                    "approve(spender={}, amount={}) on token {}",
+                    spender, amount, token
+                ),
+                Vec::new(),
+            ),
+            None if self.deployment_mode.is_production() && transaction.data_len() == 0 => (
+                0.2,
+                "Plain value transfer, no contract call".to_string(),
+                Vec::new(),
+            ),
+            _ if self.deployment_mode.is_production() => (
+                0.7,
+                format!("Unrecognized calldata to {}, not verified", transaction.to),
+                vec![ReasonCode::UnrecognizedCalldata],
+            ),
+            _ => (0.3, "Contract verified on chain".to_string(), Vec::new()),
+        };
+
+        Ok(AnalysisResult {
+            agent: self.name.clone(),
+            approved: !reason_codes.contains(&ReasonCode::UnrecognizedCalldata),
+            risk_score,
+            reasons: vec![reason],
+            reason_codes,
+        })
     }
 }
 
 impl AppState {
-    fn new() -> Self {
+    fn new(deployment_mode: DeploymentMode) -> Self {
+        let max_risk = std::env::var("DECISION_MAX_RISK")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0.8);
+
         Self {
             cfo_agent: Arc::new(CfoAgent {
                 name: "CFO Agent".to_string(),
+                max_data_bytes: max_data_bytes_for("CFO"),
+                max_tx_value: std::env::var("CFO_MAX_TX_VALUE_WEI")
+                    .ok()
+                    .and_then(|s| U256::from_str(&s).ok())
+                    .unwrap_or_else(|| U256::from_str(DEFAULT_CFO_MAX_TX_VALUE_WEI).unwrap()),
+                tx_limit: std::env::var("CFO_TX_LIMIT_WEI")
+                    .ok()
+                    .and_then(|s| U256::from_str(&s).ok()),
+                daily_limit: std::env::var("CFO_DAILY_LIMIT_WEI")
+                    .ok()
+                    .and_then(|s| U256::from_str(&s).ok()),
+                spend: Mutex::new(HashMap::new()),
             }),
             security_agent: Arc::new(SecurityAgent {
                 name: "Security Agent".to_string(),
+                max_data_bytes: max_data_bytes_for("SECURITY"),
+                blacklist: Blacklist::from_env().expect("Invalid blacklist configuration"),
             }),
             onchain_analyst: Arc::new(OnchainAnalyst {
                 name: "Onchain Analyst".to_string(),
+                max_data_bytes: max_data_bytes_for("ONCHAIN"),
+                deployment_mode,
             }),
+            // Max transactions analyzed at once by /analyze-batch
+            batch_concurrency: std::env::var("ANALYZE_BATCH_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(4),
+            tokens: Arc::new(TokenRegistry::from_env().expect("Invalid KNOWN_TOKENS")),
+            rpc_url: std::env::var("KAIROS_RPC_URL")
+                .unwrap_or_else(|_| KAIA_TESTNET_RPC.to_string()),
+            decision_policy: DecisionPolicy {
+                rule: match std::env::var("DECISION_RULE").as_deref() {
+                    Ok("majority") => AggregationRule::Majority,
+                    _ => AggregationRule::All,
+                },
+                max_risk,
+                trusted_signers: std::env::var("DECISION_TRUSTED_SIGNERS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .filter_map(|s| {
+                        let signer = serde_json::from_value(serde_json::Value::from(s)).ok();
+                        if signer.is_none() {
+                            warn!("Ignoring unknown DECISION_TRUSTED_SIGNERS entry '{}'", s);
+                        }
+                        signer
+                    })
+                    .collect(),
+                trusted_max_risk: std::env::var("DECISION_TRUSTED_MAX_RISK")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(max_risk),
+            },
+            agent_timeout: Duration::from_secs(
+                std::env::var("AGENT_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(10),
+            ),
         }
     }
 }
@@ -139,22 +762,49 @@ async fn main() {
 
     dotenv::dotenv().ok();
 
-    let state = Arc::new(AppState::new());
+    let deployment_mode = DeploymentMode::from_env();
+    let state = Arc::new(AppState::new(deployment_mode));
+    info!(
+        "Security Agent loaded {} blacklisted addresses",
+        state.security_agent.blacklist.len()
+    );
+
+    // Without limits or a blacklist the agents approve anything
+    let mut unsafe_defaults = Vec::new();
+    if state.cfo_agent.tx_limit.is_none() && state.cfo_agent.daily_limit.is_none() {
+        unsafe_defaults.push("CFO_TX_LIMIT_WEI or CFO_DAILY_LIMIT_WEI must be set".to_string());
+    }
+    if state.security_agent.blacklist.len() == 0 {
+        unsafe_defaults.push("BLACKLIST_PATH or BLACKLIST_ADDRESSES must be set".to_string());
+    }
+    deployment_mode
+        .ensure_production_ready("AI Agents service", &unsafe_defaults)
+        .unwrap();
+    let cors = deployment_mode.cors_layer().unwrap();
 
-    let app = Router::new()
+    let routes = Router::new()
         .route("/health", get(health))
         .route("/api/v1/analyze", post(analyze_transaction))
+        .route("/api/v1/consensus", post(consensus))
+        .route("/api/v1/decision", post(decision))
+        .route("/api/v1/analyze-batch", post(analyze_batch))
         .route("/api/v1/cfo/analyze", post(cfo_analyze))
         .route("/api/v1/security/analyze", post(security_analyze))
-        .route("/api/v1/onchain/analyze", post(onchain_analyze))
-        .layer(CorsLayer::permissive())
+        .route("/api/v1/onchain/analyze", post(onchain_analyze));
+    let app = observability::with_metrics(routes)
+        .layer(cors)
         .with_state(state);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3002").await.unwrap();
 
-    info!("AI Agents service listening on http://0.0.0.0:3002");
+    info!(
+        "AI Agents service listening on http://0.0.0.0:3002 ({} mode)",
+        deployment_mode.as_str()
+    );
 
-    axum::serve(listener, app).await.unwrap();
+    shared::shutdown::serve(listener, app, shared::shutdown::drain_timeout_from_env())
+        .await
+        .unwrap();
 }
 
 async fn health(State(_state): State<Arc<AppState>>) -> Json<HealthResponse> {
@@ -171,32 +821,527 @@ async fn health(State(_state): State<Arc<AppState>>) -> Json<HealthResponse> {
 
 async fn analyze_transaction(
     State(state): State<Arc<AppState>>,
-    Json(transaction): Json<TransactionData>,
+    Json(mut transaction): Json<TransactionData>,
 ) -> Result<Json<Vec<AnalysisResult>>, StatusCode> {
-    let cfo_result = state.cfo_agent.analyze(&transaction).await;
-    let security_result = state.security_agent.analyze(&transaction).await;
-    let onchain_result = state.onchain_analyst.analyze(&transaction).await;
+    enrichment::enrich(&mut transaction, &state.tokens, &state.rpc_url).await;
+
+    let cfo_result = run_agent(state.cfo_agent.clone(), &transaction, state.agent_timeout).await;
+    let security_result = run_agent(
+        state.security_agent.clone(),
+        &transaction,
+        state.agent_timeout,
+    )
+    .await;
+    let onchain_result = run_agent(
+        state.onchain_analyst.clone(),
+        &transaction,
+        state.agent_timeout,
+    )
+    .await;
 
-    Ok(Json(vec![cfo_result, security_result, onchain_result]))
+    Ok(Json(vec![
+        cfo_result.map_err(agent_error_status)?,
+        security_result.map_err(agent_error_status)?,
+        onchain_result.map_err(agent_error_status)?,
+    ]))
 }
 
-async fn cfo_analyze(
+async fn run_consensus(
+    state: &AppState,
+    mut transaction: TransactionData,
+) -> shared::Result<ConsensusResult> {
+    enrichment::enrich(&mut transaction, &state.tokens, &state.rpc_url).await;
+
+    let results = vec![
+        run_agent(state.cfo_agent.clone(), &transaction, state.agent_timeout).await?,
+        run_agent(
+            state.security_agent.clone(),
+            &transaction,
+            state.agent_timeout,
+        )
+        .await?,
+        run_agent(
+            state.onchain_analyst.clone(),
+            &transaction,
+            state.agent_timeout,
+        )
+        .await?,
+    ];
+
+    Ok(ConsensusResult {
+        approved: results.iter().all(|r| r.approved),
+        aggregate_risk: results.iter().map(|r| r.risk_score).fold(0.0, f64::max),
+        results,
+    })
+}
+
+async fn consensus(
     State(state): State<Arc<AppState>>,
     Json(transaction): Json<TransactionData>,
+) -> Result<Json<ConsensusResult>, StatusCode> {
+    run_consensus(&state, transaction)
+        .await
+        .map(Json)
+        .map_err(agent_error_status)
+}
+
+// Go/no-go for signers: the consensus verdicts under the configured policy
+async fn decision(
+    State(state): State<Arc<AppState>>,
+    Json(transaction): Json<TransactionData>,
+) -> Result<Json<DecisionResult>, StatusCode> {
+    let existing_signers = transaction.existing_signers.clone();
+    let consensus = run_consensus(&state, transaction)
+        .await
+        .map_err(agent_error_status)?;
+    Ok(Json(
+        state
+            .decision_policy
+            .decide(consensus.results, &existing_signers),
+    ))
+}
+
+async fn analyze_batch(
+    State(state): State<Arc<AppState>>,
+    Json(transactions): Json<Vec<TransactionData>>,
+) -> Json<Vec<BatchAnalysisItem>> {
+    info!("Analyzing batch of {} transactions", transactions.len());
+
+    // The JoinSet aborts whatever is still running if the request is dropped, so
+    // an abandoned batch stops recording CFO spend for verdicts nobody receives
+    let permits = Arc::new(Semaphore::new(state.batch_concurrency));
+    let mut tasks = JoinSet::new();
+    let mut positions = HashMap::new();
+    for (position, transaction) in transactions.into_iter().enumerate() {
+        let state = state.clone();
+        let permits = permits.clone();
+        let task = tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await.ok();
+            run_consensus(&state, transaction).await
+        });
+        positions.insert(task.id(), position);
+    }
+
+    let mut items: Vec<Option<BatchAnalysisItem>> = (0..positions.len()).map(|_| None).collect();
+    while let Some(joined) = tasks.join_next_with_id().await {
+        let (id, item) = match joined {
+            Ok((id, Ok(result))) => (id, BatchAnalysisItem::Result(result)),
+            Ok((id, Err(e))) => {
+                error!("Batch analysis failed: {}", e);
+                (id, BatchAnalysisItem::Error(e.to_string()))
+            }
+            Err(e) => {
+                error!("Batch analysis task failed: {}", e);
+                (
+                    e.id(),
+                    BatchAnalysisItem::Error(format!("Analysis task failed: {}", e)),
+                )
+            }
+        };
+        items[positions[&id]] = Some(item);
+    }
+
+    Json(items.into_iter().flatten().collect())
+}
+
+async fn cfo_analyze(
+    State(state): State<Arc<AppState>>,
+    Json(mut transaction): Json<TransactionData>,
 ) -> Result<Json<AnalysisResult>, StatusCode> {
-    Ok(Json(state.cfo_agent.analyze(&transaction).await))
+    enrichment::enrich(&mut transaction, &state.tokens, &state.rpc_url).await;
+    run_agent(state.cfo_agent.clone(), &transaction, state.agent_timeout)
+        .await
+        .map(Json)
+        .map_err(agent_error_status)
 }
 
 async fn security_analyze(
     State(state): State<Arc<AppState>>,
-    Json(transaction): Json<TransactionData>,
+    Json(mut transaction): Json<TransactionData>,
 ) -> Result<Json<AnalysisResult>, StatusCode> {
-    Ok(Json(state.security_agent.analyze(&transaction).await))
+    enrichment::enrich(&mut transaction, &state.tokens, &state.rpc_url).await;
+    run_agent(
+        state.security_agent.clone(),
+        &transaction,
+        state.agent_timeout,
+    )
+    .await
+    .map(Json)
+    .map_err(agent_error_status)
 }
 
 async fn onchain_analyze(
     State(state): State<Arc<AppState>>,
-    Json(transaction): Json<TransactionData>,
+    Json(mut transaction): Json<TransactionData>,
 ) -> Result<Json<AnalysisResult>, StatusCode> {
-    Ok(Json(state.onchain_analyst.analyze(&transaction).await))
+    enrichment::enrich(&mut transaction, &state.tokens, &state.rpc_url).await;
+    run_agent(
+        state.onchain_analyst.clone(),
+        &transaction,
+        state.agent_timeout,
+    )
+    .await
+    .map(Json)
+    .map_err(agent_error_status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfo(daily_limit: u64) -> CfoAgent {
+        CfoAgent {
+            name: "CFO Agent".to_string(),
+            max_data_bytes: 1024,
+            max_tx_value: U256::from_str(DEFAULT_CFO_MAX_TX_VALUE_WEI).unwrap(),
+            tx_limit: None,
+            daily_limit: Some(U256::from(daily_limit)),
+            spend: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn transfer(safe: &str, nonce: u64, value: u64) -> TransactionData {
+        TransactionData {
+            safe: safe.to_string(),
+            to: Address::repeat_byte(0x11).to_string(),
+            value: value.to_string(),
+            data: "0x".to_string(),
+            nonce,
+            existing_signers: Vec::new(),
+            decoded: None,
+        }
+    }
+
+    const SAFE: &str = "0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a";
+
+    #[tokio::test]
+    async fn reanalysis_does_not_double_count() {
+        let agent = cfo(100);
+
+        for _ in 0..3 {
+            let result = agent.analyze(&transfer(SAFE, 7, 60)).await.unwrap();
+            assert!(result.approved);
+        }
+
+        // Only the one 60 wei proposal counts, so 40 still fits and 41 doesn't
+        assert!(
+            agent
+                .analyze(&transfer(SAFE, 8, 40))
+                .await
+                .unwrap()
+                .approved
+        );
+        let over = agent.analyze(&transfer(SAFE, 9, 1)).await.unwrap();
+        assert_eq!(over.reason_codes, vec![ReasonCode::OverDailyBudget]);
+    }
+
+    #[tokio::test]
+    async fn safe_address_casing_shares_one_budget() {
+        let agent = cfo(100);
+
+        assert!(
+            agent
+                .analyze(&transfer(SAFE, 1, 80))
+                .await
+                .unwrap()
+                .approved
+        );
+        let upper = format!("0x{}", SAFE[2..].to_uppercase());
+        let result = agent.analyze(&transfer(&upper, 2, 80)).await.unwrap();
+
+        assert_eq!(result.reason_codes, vec![ReasonCode::OverDailyBudget]);
+    }
+
+    #[tokio::test]
+    async fn unparseable_safe_is_rejected_under_a_daily_limit() {
+        let result = cfo(100).analyze(&transfer("", 1, 1)).await.unwrap();
+
+        assert!(!result.approved);
+        assert_eq!(result.reason_codes, vec![ReasonCode::InvalidValue]);
+    }
+
+    #[tokio::test]
+    async fn value_near_u256_max_is_too_large() {
+        let agent = cfo(100);
+        let mut huge = transfer(SAFE, 1, 0);
+        huge.value = (U256::MAX - U256::from(1)).to_string();
+
+        let result = agent.analyze(&huge).await.unwrap();
+
+        assert!(!result.approved);
+        assert_eq!(result.risk_score, 1.0);
+        assert_eq!(result.reason_codes, vec![ReasonCode::ValueTooLarge]);
+        // Rejected before the budget, so none of it was counted
+        assert!(
+            agent
+                .analyze(&transfer(SAFE, 2, 100))
+                .await
+                .unwrap()
+                .approved
+        );
+    }
+
+    #[test]
+    fn rolling_spend_saturates_instead_of_wrapping() {
+        let now = Utc::now();
+        let mut rolling = RollingSpend::default();
+        rolling.record(now, 1, U256::MAX - U256::from(1));
+        rolling.record(now, 2, U256::MAX - U256::from(1));
+
+        assert_eq!(rolling.total_excluding(now, 3), U256::MAX);
+        assert_eq!(rolling.total_excluding(now, 2), U256::MAX - U256::from(1));
+    }
+
+    #[tokio::test]
+    async fn oversized_calldata_is_rejected_unanalyzed() {
+        let agent = cfo(100);
+        let mut call = transfer(SAFE, 1, 1);
+        call.data = format!("0x{}", "ab".repeat(1024));
+        assert!(agent.analyze(&call).await.unwrap().approved);
+
+        call.data.push_str("ab");
+        let result = agent.analyze(&call).await.unwrap();
+
+        assert!(!result.approved);
+        assert_eq!(result.risk_score, 1.0);
+        assert_eq!(result.reason_codes, vec![ReasonCode::PayloadTooLarge]);
+        assert!(result.reasons[0].contains("1025 bytes"));
+    }
+
+    // Scores synchronously for longer than any test deadline
+    struct StalledAgent;
+
+    #[async_trait]
+    impl AiAgent for StalledAgent {
+        fn name(&self) -> &str {
+            "Stalled Agent"
+        }
+
+        async fn analyze(&self, _: &TransactionData) -> shared::Result<AnalysisResult> {
+            std::thread::sleep(Duration::from_secs(1));
+            Err(SafeWalletError::AgentError("finished too late".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn stalled_agent_times_out_as_bad_gateway() {
+        let started = std::time::Instant::now();
+
+        let err = run_agent(
+            Arc::new(StalledAgent),
+            &transfer(SAFE, 1, 1),
+            Duration::from_millis(100),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert!(err.to_string().contains("Stalled Agent timed out"));
+        assert_eq!(agent_error_status(err), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn agent_within_deadline_returns_its_result() {
+        let result = run_agent(
+            Arc::new(cfo(100)),
+            &transfer(SAFE, 1, 1),
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+
+        assert!(result.approved);
+    }
+
+    #[tokio::test]
+    async fn blacklist_matches_regardless_of_address_casing() {
+        let sanctioned = Address::repeat_byte(0xab);
+        let listed = format!(
+            r#"["{}", {{"address": "0x{}", "label": "OFAC SDN"}}]"#,
+            sanctioned.to_checksum(None),
+            Address::repeat_byte(0xcd).to_string()[2..].to_uppercase()
+        );
+        let agent = SecurityAgent {
+            name: "Security Agent".to_string(),
+            max_data_bytes: 1024,
+            blacklist: Blacklist::new(Blacklist::parse(&listed).unwrap()),
+        };
+        let to = |address: String| TransactionData {
+            to: address,
+            ..transfer(SAFE, 1, 1)
+        };
+
+        let hit = agent
+            .analyze(&to(sanctioned.to_string().to_lowercase()))
+            .await
+            .unwrap();
+        assert!(!hit.approved);
+        assert_eq!(hit.risk_score, 1.0);
+        assert_eq!(hit.reason_codes, vec![ReasonCode::Blacklisted]);
+        assert!(hit.reasons[0].contains(": blacklist"));
+        let labeled = agent
+            .analyze(&to(Address::repeat_byte(0xcd).to_checksum(None)))
+            .await
+            .unwrap();
+        assert!(!labeled.approved);
+        assert!(labeled.reasons[0].contains("OFAC SDN"));
+
+        // One byte off the listed address
+        let miss = agent
+            .analyze(&to(format!("0x{}ac", "AB".repeat(19))))
+            .await
+            .unwrap();
+        assert!(miss.approved);
+        assert!(miss.reason_codes.is_empty());
+    }
+
+    // Agents with a 100 wei daily budget and no blacklist; token lookups go nowhere
+    fn test_state() -> AppState {
+        AppState {
+            cfo_agent: Arc::new(cfo(100)),
+            security_agent: Arc::new(SecurityAgent {
+                name: "Security Agent".to_string(),
+                max_data_bytes: 1024,
+                blacklist: Blacklist::new(HashMap::new()),
+            }),
+            onchain_analyst: Arc::new(OnchainAnalyst {
+                name: "Onchain Analyst".to_string(),
+                max_data_bytes: 1024,
+                deployment_mode: DeploymentMode::Demo,
+            }),
+            batch_concurrency: 2,
+            agent_timeout: Duration::from_secs(5),
+            tokens: Arc::new(TokenRegistry::new(HashMap::new())),
+            rpc_url: "http://127.0.0.1:9".to_string(),
+            decision_policy: DecisionPolicy {
+                rule: AggregationRule::All,
+                max_risk: 0.8,
+                trusted_signers: Vec::new(),
+                trusted_max_risk: 0.8,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_results_follow_input_order() {
+        let mut malformed = transfer(SAFE, 2, 1);
+        malformed.to = "not-an-address".to_string();
+        let batch = vec![
+            transfer(SAFE, 1, 10),
+            malformed,
+            // Over the daily budget whether or not nonce 1 was counted first
+            transfer(SAFE, 3, 200),
+        ];
+
+        let Json(items) = analyze_batch(State(Arc::new(test_state())), Json(batch)).await;
+
+        assert_eq!(items.len(), 3);
+        let BatchAnalysisItem::Result(first) = &items[0] else {
+            panic!("expected a verdict, got {:?}", items[0]);
+        };
+        assert!(first.approved);
+        let BatchAnalysisItem::Error(error) = &items[1] else {
+            panic!("expected an error, got {:?}", items[1]);
+        };
+        assert!(error.contains("not-an-address"));
+        let BatchAnalysisItem::Result(third) = &items[2] else {
+            panic!("expected a verdict, got {:?}", items[2]);
+        };
+        assert!(!third.approved);
+        assert_eq!(
+            third.results[0].reason_codes,
+            vec![ReasonCode::OverDailyBudget]
+        );
+    }
+
+    fn verdict(agent: &str, approved: bool, risk_score: f64) -> AnalysisResult {
+        AnalysisResult {
+            agent: agent.to_string(),
+            approved,
+            risk_score,
+            reasons: vec![if approved { "ok" } else { "vetoed" }.to_string()],
+            reason_codes: Vec::new(),
+        }
+    }
+
+    fn policy(rule: AggregationRule) -> DecisionPolicy {
+        DecisionPolicy {
+            rule,
+            ..test_state().decision_policy
+        }
+    }
+
+    #[test]
+    fn one_veto_rejects_under_the_all_rule() {
+        let results = || {
+            vec![
+                verdict("CFO Agent", true, 0.1),
+                verdict("Security Agent", false, 0.3),
+                verdict("Onchain Analyst", true, 0.2),
+            ]
+        };
+
+        let all = policy(AggregationRule::All).decide(results(), &[]);
+        let majority = policy(AggregationRule::Majority).decide(results(), &[]);
+
+        assert!(!all.approved);
+        assert_eq!(all.recommendation, SigningRecommendation::DoNotSign);
+        assert_eq!(all.approvals, 2);
+        assert!(all.reasons.contains(&"Security Agent: vetoed".to_string()));
+        assert_eq!(all.results.len(), 3);
+        // The same lone veto is outvoted under majority
+        assert!(majority.approved);
+        assert_eq!(majority.recommendation, SigningRecommendation::Sign);
+    }
+
+    #[test]
+    fn unanimous_approval_still_needs_risk_below_the_threshold() {
+        let decision = policy(AggregationRule::All).decide(
+            vec![
+                verdict("CFO Agent", true, 0.1),
+                verdict("Security Agent", true, 0.8),
+            ],
+            &[],
+        );
+
+        assert!(!decision.approved);
+        assert_eq!(decision.max_risk, 0.8);
+        assert!(decision.reasons.last().unwrap().contains("at or above"));
+    }
+
+    #[test]
+    fn trusted_signer_relaxes_the_risk_threshold() {
+        let policy = DecisionPolicy {
+            trusted_signers: vec![SignerType::Human],
+            trusted_max_risk: 0.95,
+            ..policy(AggregationRule::All)
+        };
+        let results = || {
+            vec![
+                verdict("CFO Agent", true, 0.1),
+                verdict("Security Agent", true, 0.9),
+            ]
+        };
+
+        let untrusted = policy.decide(results(), &[SignerType::AiCfo]);
+        let trusted = policy.decide(results(), &[SignerType::AiCfo, SignerType::Human]);
+
+        assert!(!untrusted.approved);
+        assert_eq!(untrusted.risk_threshold, 0.8);
+        assert_eq!(untrusted.relaxed_by, None);
+        assert!(trusted.approved);
+        assert_eq!(trusted.risk_threshold, 0.95);
+        assert_eq!(trusted.relaxed_by, Some(SignerType::Human));
+        assert!(trusted
+            .reasons
+            .last()
+            .unwrap()
+            .contains("trusted Human signer"));
+        // A trusted signature relaxes the risk bar, never a veto
+        let vetoed = policy.decide(
+            vec![verdict("Security Agent", false, 0.2)],
+            &[SignerType::Human],
+        );
+        assert!(!vetoed.approved);
+    }
 }
@@ -0,0 +1,261 @@
+use alloy::{
+    primitives::{keccak256, Address, Bytes, Signature, B256, U256},
+    rlp::{Encodable, Header},
+    signers::{local::PrivateKeySigner, SignerSync},
+};
+use anyhow::{anyhow, Result};
+
+// Kaia transaction type tags (fee payer covers everything / a share of the fee)
+const TX_TYPE_FEE_DELEGATED_VALUE_TRANSFER: u8 = 0x09;
+const TX_TYPE_FEE_DELEGATED_VALUE_TRANSFER_WITH_RATIO: u8 = 0x0a;
+const TX_TYPE_FEE_DELEGATED_SMART_CONTRACT_EXECUTION: u8 = 0x31;
+const TX_TYPE_FEE_DELEGATED_SMART_CONTRACT_EXECUTION_WITH_RATIO: u8 = 0x32;
+
+// Fee-delegated value transfer (no calldata) or smart contract execution,
+// signed first by the sender and then by the fee payer
+pub struct FeeDelegatedTx {
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas: u64,
+    pub to: Address,
+    pub value: U256,
+    pub from: Address,
+    pub input: Bytes,
+    pub fee_ratio: Option<u8>, // None = full delegation
+    pub chain_id: u64,
+}
+
+// Kaia's [V, R, S] with an EIP-155 style V
+#[derive(Debug, Clone, Copy)]
+pub struct TxSignature {
+    pub v: u64,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl TxSignature {
+    fn from_ecdsa(sig: &Signature, chain_id: u64) -> Self {
+        Self {
+            v: sig.v() as u64 + chain_id * 2 + 35,
+            r: sig.r(),
+            s: sig.s(),
+        }
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        rlp_list(&[&self.v, &self.r, &self.s], out);
+    }
+}
+
+impl FeeDelegatedTx {
+    fn tx_type(&self) -> u8 {
+        match (self.input.is_empty(), self.fee_ratio.is_some()) {
+            (true, false) => TX_TYPE_FEE_DELEGATED_VALUE_TRANSFER,
+            (true, true) => TX_TYPE_FEE_DELEGATED_VALUE_TRANSFER_WITH_RATIO,
+            (false, false) => TX_TYPE_FEE_DELEGATED_SMART_CONTRACT_EXECUTION,
+            (false, true) => TX_TYPE_FEE_DELEGATED_SMART_CONTRACT_EXECUTION_WITH_RATIO,
+        }
+    }
+
+    // Fields after the type tag, in the order shared by all signing/encoding forms
+    fn fields(&self) -> Vec<&dyn Encodable> {
+        let mut fields: Vec<&dyn Encodable> = vec![
+            &self.nonce,
+            &self.gas_price,
+            &self.gas,
+            &self.to,
+            &self.value,
+            &self.from,
+        ];
+        if !self.input.is_empty() {
+            fields.push(&self.input);
+        }
+        if let Some(ratio) = &self.fee_ratio {
+            fields.push(ratio);
+        }
+        fields
+    }
+
+    fn common_rlp(&self) -> Vec<u8> {
+        let tx_type = self.tx_type();
+        let mut fields: Vec<&dyn Encodable> = vec![&tx_type];
+        fields.extend(self.fields());
+        let mut out = Vec::new();
+        rlp_list(&fields, &mut out);
+        out
+    }
+
+    // keccak(rlp([rlp([type, ...fields]), chainId, 0, 0]))
+    pub fn sender_sig_hash(&self) -> B256 {
+        let common = Bytes::from(self.common_rlp());
+        let mut out = Vec::new();
+        rlp_list(&[&common, &self.chain_id, &0u8, &0u8], &mut out);
+        keccak256(out)
+    }
+
+    // keccak(rlp([rlp([type, ...fields]), feePayer, chainId, 0, 0]))
+    pub fn fee_payer_sig_hash(&self, fee_payer: Address) -> B256 {
+        let common = Bytes::from(self.common_rlp());
+        let mut out = Vec::new();
+        rlp_list(&[&common, &fee_payer, &self.chain_id, &0u8, &0u8], &mut out);
+        keccak256(out)
+    }
+
+    // Accepts a 65-byte r || s || v with v as 0/1, 27/28 or EIP-155, and
+    // checks it was produced by `from` over this exact transaction
+    pub fn verify_sender_signature(&self, signature: &[u8]) -> Result<TxSignature> {
+        if signature.len() != 65 {
+            return Err(anyhow!(
+                "Invalid signature length: expected 65 bytes, got {}",
+                signature.len()
+            ));
+        }
+        let v = signature[64] as u64;
+        let parity = match v {
+            0 | 1 => v == 1,
+            27 | 28 => v == 28,
+            v if v >= 35 && (v - 35) / 2 == self.chain_id => (v - 35) % 2 == 1,
+            _ => return Err(anyhow!("Unsupported signature v value: {}", v)),
+        };
+
+        let sig = Signature::from_bytes_and_parity(&signature[..64], parity);
+        let signer = sig
+            .recover_address_from_prehash(&self.sender_sig_hash())
+            .map_err(|e| anyhow!("Signature recovery failed: {}", e))?;
+        if signer != self.from {
+            return Err(anyhow!(
+                "Signature is from {}, not the sender {}",
+                signer,
+                self.from
+            ));
+        }
+
+        Ok(TxSignature::from_ecdsa(&sig, self.chain_id))
+    }
+
+    pub fn sign_as_fee_payer(&self, fee_payer: &PrivateKeySigner) -> Result<TxSignature> {
+        let sig = fee_payer.sign_hash_sync(&self.fee_payer_sig_hash(fee_payer.address()))?;
+        Ok(TxSignature::from_ecdsa(&sig, self.chain_id))
+    }
+
+    // type || rlp([...fields, [senderSig], feePayer, [feePayerSig]]), as accepted
+    // by kaia_sendRawTransaction
+    pub fn encode_signed(
+        &self,
+        sender: &TxSignature,
+        fee_payer: Address,
+        fee_payer_sig: &TxSignature,
+    ) -> Bytes {
+        let mut sender_sigs = Vec::new();
+        sender.encode(&mut sender_sigs);
+        let mut fee_payer_sigs = Vec::new();
+        fee_payer_sig.encode(&mut fee_payer_sigs);
+
+        let mut payload = Vec::new();
+        for field in self.fields() {
+            field.encode(&mut payload);
+        }
+        Header {
+            list: true,
+            payload_length: sender_sigs.len(),
+        }
+        .encode(&mut payload);
+        payload.extend(sender_sigs);
+        fee_payer.encode(&mut payload);
+        Header {
+            list: true,
+            payload_length: fee_payer_sigs.len(),
+        }
+        .encode(&mut payload);
+        payload.extend(fee_payer_sigs);
+
+        let mut out = vec![self.tx_type()];
+        Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut out);
+        out.extend(payload);
+        out.into()
+    }
+}
+
+fn rlp_list(items: &[&dyn Encodable], out: &mut Vec<u8>) {
+    let mut payload = Vec::new();
+    for item in items {
+        item.encode(&mut payload);
+    }
+    Header {
+        list: true,
+        payload_length: payload.len(),
+    }
+    .encode(out);
+    out.extend(payload);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> PrivateKeySigner {
+        PrivateKeySigner::from_bytes(&B256::with_last_byte(n)).unwrap()
+    }
+
+    fn transfer(from: Address) -> FeeDelegatedTx {
+        FeeDelegatedTx {
+            nonce: 3,
+            gas_price: U256::from(25_000_000_000u64),
+            gas: 21_000,
+            to: Address::repeat_byte(0x11),
+            value: U256::from(1_000u64),
+            from,
+            input: Bytes::new(),
+            fee_ratio: None,
+            chain_id: 1001,
+        }
+    }
+
+    #[test]
+    fn sender_signature_must_come_from_the_sender() {
+        let tx = transfer(key(1).address());
+        let own = key(1).sign_hash_sync(&tx.sender_sig_hash()).unwrap();
+        let other = key(2).sign_hash_sync(&tx.sender_sig_hash()).unwrap();
+
+        assert!(tx.verify_sender_signature(&own.as_bytes()).is_ok());
+        assert!(tx.verify_sender_signature(&other.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn fee_payer_signature_covers_the_fee_payer() {
+        let tx = transfer(key(1).address());
+        let fee_payer = key(2);
+
+        let sig = tx.sign_as_fee_payer(&fee_payer).unwrap();
+
+        // EIP-155 style v for chain 1001
+        assert!(sig.v == 1001 * 2 + 35 || sig.v == 1001 * 2 + 36);
+        let ecdsa = Signature::new(sig.r, sig.s, sig.v == 1001 * 2 + 36);
+        let recovered = ecdsa
+            .recover_address_from_prehash(&tx.fee_payer_sig_hash(fee_payer.address()))
+            .unwrap();
+        assert_eq!(recovered, fee_payer.address());
+    }
+
+    #[test]
+    fn ratio_and_calldata_select_the_tx_type() {
+        let mut tx = transfer(key(1).address());
+        assert_eq!(tx.tx_type(), TX_TYPE_FEE_DELEGATED_VALUE_TRANSFER);
+        tx.fee_ratio = Some(30);
+        assert_eq!(
+            tx.tx_type(),
+            TX_TYPE_FEE_DELEGATED_VALUE_TRANSFER_WITH_RATIO
+        );
+        tx.input = Bytes::from_static(&[0xde, 0xad]);
+        assert_eq!(
+            tx.tx_type(),
+            TX_TYPE_FEE_DELEGATED_SMART_CONTRACT_EXECUTION_WITH_RATIO
+        );
+        tx.fee_ratio = None;
+        assert_eq!(tx.tx_type(), TX_TYPE_FEE_DELEGATED_SMART_CONTRACT_EXECUTION);
+    }
+}
@@ -1,32 +1,213 @@
+mod kaia_tx;
+mod rate_limit;
+mod spend_cap;
+mod subscriptions;
+
+use alloy::{
+    primitives::{Address, Bytes, B256, U256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
 use axum::{
-    extract::State,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, State,
+    },
     http::StatusCode,
-    response::Json,
+    response::{Json, Response},
     routing::{get, post},
     Router,
 };
+use rate_limit::{RateLimiter, DEFAULT_DELEGATE_RATE_LIMIT_PER_MIN};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tower_http::cors::CorsLayer;
-use tracing::info;
+use shared::{
+    constants::{DEFAULT_MIN_CONFIRMATIONS, KAIA_TESTNET_CHAIN_ID, KAIA_TESTNET_RPC},
+    deployment::DeploymentMode,
+    observability::{self, time_rpc},
+};
+use spend_cap::DailySpend;
+use std::{
+    collections::{HashSet, VecDeque},
+    future::IntoFuture,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use subscriptions::{LimitReached, SubscriptionLimiter, SubscriptionSlot};
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+// How often a status subscription re-reads the receipt, and when it gives up
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const STATUS_SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(600);
+// Submitted hashes remembered for status lookups; the oldest are forgotten first
+const MAX_TRACKED_SUBMISSIONS: usize = 100_000;
+
 #[derive(Clone)]
 struct AppState {
     fee_payer_address: String,
+    fee_payer: Option<PrivateKeySigner>, // Signs delegated transactions; demo mode can run without
+    chain_id: u64,
+    rpc_url: String,
+    min_confirmations: u64,
+    fee_ratio: u8, // Percent of the fee the fee payer covers (Kaia partial fee delegation)
+    deployment_mode: DeploymentMode,
+    // Fee-delegated transactions consume the sender's nonce, not the fee payer's,
+    // so collisions are two requests for the same (sender, nonce) in flight at once
+    in_flight: Arc<Mutex<HashSet<(Address, u64)>>>,
+    submitted: Arc<Mutex<SubmittedHashes>>, // Hashes this service handed out; status is only served for these
+    daily_spend: Arc<DailySpend>,
+    status_subscriptions: SubscriptionLimiter,
+}
+
+// Insertion-ordered set that drops its oldest hash once it holds `capacity`
+struct SubmittedHashes {
+    capacity: usize,
+    order: VecDeque<B256>,
+    hashes: HashSet<B256>,
+}
+
+impl SubmittedHashes {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::new(),
+            hashes: HashSet::new(),
+        }
+    }
+
+    fn insert(&mut self, hash: B256) {
+        if !self.hashes.insert(hash) {
+            return;
+        }
+        self.order.push_back(hash);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+    }
+
+    fn contains(&self, hash: &B256) -> bool {
+        self.hashes.contains(hash)
+    }
+}
+
+// Holds a (sender, nonce) slot until the submission finishes, successful or not
+struct NonceReservation {
+    in_flight: Arc<Mutex<HashSet<(Address, u64)>>>,
+    key: (Address, u64),
+}
+
+impl NonceReservation {
+    fn acquire(
+        in_flight: &Arc<Mutex<HashSet<(Address, u64)>>>,
+        key: (Address, u64),
+    ) -> Option<Self> {
+        let inserted = in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key);
+        inserted.then(|| Self {
+            in_flight: in_flight.clone(),
+            key,
+        })
+    }
+}
+
+impl Drop for NonceReservation {
+    fn drop(&mut self) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.key);
+    }
 }
 
 impl AppState {
+    fn record_submitted(&self, hash: B256) {
+        self.submitted
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(hash);
+    }
+
+    fn was_submitted(&self, hash: B256) -> bool {
+        self.submitted
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&hash)
+    }
+
     fn new() -> Self {
-        let fee_payer_address = std::env::var("FEE_PAYER_ADDRESS")
-            .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
+        let fee_payer = std::env::var("FEE_PAYER_PRIVATE_KEY")
+            .ok()
+            .filter(|k| !k.is_empty())
+            .map(|k| PrivateKeySigner::from_str(&k).expect("Invalid FEE_PAYER_PRIVATE_KEY"));
+
+        // The key decides who pays; FEE_PAYER_ADDRESS is only a cross-check
+        let configured_address = std::env::var("FEE_PAYER_ADDRESS").ok();
+        let fee_payer_address = match &fee_payer {
+            Some(signer) => {
+                if let Some(configured) = configured_address
+                    .as_deref()
+                    .and_then(|a| Address::from_str(a).ok())
+                {
+                    assert_eq!(
+                        configured,
+                        signer.address(),
+                        "FEE_PAYER_ADDRESS does not match FEE_PAYER_PRIVATE_KEY"
+                    );
+                }
+                signer.address().to_string()
+            }
+            None => configured_address
+                .unwrap_or_else(|| "0x0000000000000000000000000000000000000000".to_string()),
+        };
+
+        let chain_id = std::env::var("CHAIN_ID")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(KAIA_TESTNET_CHAIN_ID);
+
+        let rpc_url =
+            std::env::var("KAIROS_RPC_URL").unwrap_or_else(|_| KAIA_TESTNET_RPC.to_string());
 
-        Self { fee_payer_address }
+        // Blocks a delegated tx must be buried under before we report it confirmed
+        let min_confirmations = std::env::var("FEE_DELEGATION_MIN_CONFIRMATIONS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MIN_CONFIRMATIONS);
+
+        // 100 = full delegation; 1-99 = partial, the user pays the remainder
+        let fee_ratio = std::env::var("FEE_SPONSORSHIP_RATIO")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|r| (1..=100).contains(r))
+            .unwrap_or(100);
+
+        Self {
+            fee_payer_address,
+            fee_payer,
+            chain_id,
+            rpc_url,
+            min_confirmations,
+            fee_ratio,
+            deployment_mode: DeploymentMode::from_env(),
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            submitted: Arc::new(Mutex::new(SubmittedHashes::new(MAX_TRACKED_SUBMISSIONS))),
+            daily_spend: Arc::new(DailySpend::from_env()),
+            status_subscriptions: SubscriptionLimiter::from_env(),
+        }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct DelegatedTransaction {
+    #[serde(default)]
+    nonce: Option<u64>, // Sender nonce the signature covers; the pending nonce when omitted
     from: String,
     to: String,
     value: String,
@@ -46,6 +227,18 @@ struct DelegationResponse {
     transaction_hash: String,
     fee_payer: String,
     status: String,
+    confirmations: Option<u64>,
+    #[serde(default)]
+    block_number: Option<u64>,
+    #[serde(default)]
+    gas_used: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct StatusEvent {
+    transaction_hash: String,
+    status: String,
+    confirmations: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -53,6 +246,8 @@ struct HealthResponse {
     status: String,
     service: String,
     fee_payer: String,
+    fee_payer_balance: Option<String>, // Wei; None when the RPC can't be reached
+    daily_budget_remaining: Option<String>, // Wei left under FEE_PAYER_DAILY_CAP_WEI; None when uncapped
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -60,6 +255,9 @@ struct FeeEstimate {
     estimated_fee: String,
     gas_price: String,
     gas_limit: String,
+    fee_ratio: u8,
+    fee_payer_share: String,
+    user_share: String,
 }
 
 #[tokio::main]
@@ -80,19 +278,53 @@ async fn main() {
 
     let state = Arc::new(AppState::new());
 
-    let app = Router::new()
+    let mut unsafe_defaults = Vec::new();
+    if state.fee_payer.is_none() {
+        unsafe_defaults.push("FEE_PAYER_PRIVATE_KEY must be set".to_string());
+    }
+    state
+        .deployment_mode
+        .ensure_production_ready("Fee Delegation service", &unsafe_defaults)
+        .unwrap();
+    let cors = state.deployment_mode.cors_layer().unwrap();
+    // Only delegation spends the fee payer's funds; estimates and status reads stay unthrottled
+    let delegate_limiter = RateLimiter::from_env(
+        "DELEGATE_RATE_LIMIT_PER_MIN",
+        DEFAULT_DELEGATE_RATE_LIMIT_PER_MIN,
+    );
+
+    let routes = Router::new()
         .route("/health", get(health))
-        .route("/api/v1/delegate", post(delegate_fee))
+        .route(
+            "/api/v1/delegate",
+            post(delegate_fee).layer(axum::middleware::from_fn_with_state(
+                delegate_limiter,
+                rate_limit::limit,
+            )),
+        )
         .route("/api/v1/estimate", post(estimate_fee))
-        .route("/api/v1/status/:tx_hash", get(get_delegation_status))
-        .layer(CorsLayer::permissive())
+        .route("/api/v1/status/{tx_hash}", get(get_delegation_status))
+        .route(
+            "/api/v1/ws/status/{tx_hash}",
+            get(subscribe_delegation_status),
+        );
+    let app = observability::with_metrics(routes)
+        .layer(cors)
         .with_state(state);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3003").await.unwrap();
 
     info!("Fee Delegation service listening on http://0.0.0.0:3003");
 
-    axum::serve(listener, app).await.unwrap();
+    shared::shutdown::serve(listener, app, shared::shutdown::drain_timeout_from_env())
+        .await
+        .unwrap();
+}
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn api_error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (status, Json(serde_json::json!({ "error": message.into() })))
 }
 
 async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
@@ -100,52 +332,1135 @@ async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
         status: "healthy".to_string(),
         service: "fee-delegation".to_string(),
         fee_payer: state.fee_payer_address.clone(),
+        fee_payer_balance: fee_payer_balance(&state)
+            .await
+            .map_err(|e| warn!("Failed to fetch fee payer balance: {}", e))
+            .ok()
+            .map(|b| b.to_string()),
+        daily_budget_remaining: state.daily_spend.remaining().map(|r| r.to_string()),
     })
 }
 
+async fn fee_payer_balance(state: &AppState) -> anyhow::Result<U256> {
+    let address = Address::from_str(&state.fee_payer_address)?;
+    let provider = ProviderBuilder::new().connect_http(state.rpc_url.parse()?);
+    Ok(time_rpc(
+        "eth_getBalance",
+        provider.get_balance(address).into_future(),
+    )
+    .await?)
+}
+
 async fn delegate_fee(
     State(state): State<Arc<AppState>>,
     Json(request): Json<DelegationRequest>,
-) -> Result<(StatusCode, Json<DelegationResponse>), StatusCode> {
+) -> Result<(StatusCode, Json<DelegationResponse>), ApiError> {
     info!(
         "Delegating fee for transaction from {} to {}",
         request.transaction.from, request.transaction.to
     );
 
-    let tx_hash = format!("0x{}", uuid::Uuid::new_v4().simple());
+    // Production refuses to start without a key, so only demo mode gets here
+    let Some(fee_payer) = &state.fee_payer else {
+        warn!("No FEE_PAYER_PRIVATE_KEY configured, returning a placeholder hash");
+        let placeholder = alloy::primitives::keccak256(uuid::Uuid::new_v4().as_bytes());
+        state.record_submitted(placeholder);
+        return Ok((
+            StatusCode::OK,
+            Json(DelegationResponse {
+                transaction_hash: placeholder.to_string(),
+                fee_payer: state.fee_payer_address.clone(),
+                status: "pending".to_string(),
+                confirmations: None,
+                block_number: None,
+                gas_used: None,
+            }),
+        ));
+    };
+
+    let provider = ProviderBuilder::new().connect_http(state.rpc_url.parse().map_err(|e| {
+        error!("Invalid RPC URL {}: {}", state.rpc_url, e);
+        api_error(StatusCode::INTERNAL_SERVER_ERROR, "Invalid RPC URL")
+    })?);
+
+    let tx = build_fee_delegated_tx(&state, &provider, &request.transaction)
+        .await
+        .map_err(|status| api_error(status, "Invalid delegated transaction"))?;
+
+    let user_signature = Bytes::from_str(&request.user_signature)
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid user_signature"))?;
+    let sender_sig = tx.verify_sender_signature(&user_signature).map_err(|e| {
+        warn!(
+            "Rejecting delegation from {}: {}",
+            request.transaction.from, e
+        );
+        api_error(StatusCode::FORBIDDEN, e.to_string())
+    })?;
+
+    let _reservation = NonceReservation::acquire(&state.in_flight, (tx.from, tx.nonce))
+        .ok_or_else(|| {
+            warn!(
+                "Delegation for {} nonce {} is already being submitted",
+                tx.from, tx.nonce
+            );
+            api_error(
+                StatusCode::CONFLICT,
+                format!(
+                    "A delegation for {} with nonce {} is already in flight",
+                    tx.from, tx.nonce
+                ),
+            )
+        })?;
+
+    // Submitting without funds would only fail later on-chain
+    let fee_payer_share = fee_payer_share(tx.gas_price, U256::from(tx.gas), state.fee_ratio)
+        .ok_or_else(|| {
+            warn!(
+                "Rejecting delegation from {}: gas {} at {} wei overflows",
+                tx.from, tx.gas, tx.gas_price
+            );
+            api_error(
+                StatusCode::BAD_REQUEST,
+                "Gas price times gas limit overflows",
+            )
+        })?;
+    let balance = time_rpc(
+        "eth_getBalance",
+        provider.get_balance(fee_payer.address()).into_future(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch fee payer balance: {}", e);
+        api_error(StatusCode::BAD_GATEWAY, "Could not read fee payer balance")
+    })?;
+    if balance < fee_payer_share {
+        error!(
+            "Fee payer {} has {} wei, needs {} wei",
+            fee_payer.address(),
+            balance,
+            fee_payer_share
+        );
+        return Err(api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "Fee payer balance {} wei is below the {} wei fee for this transaction",
+                balance, fee_payer_share
+            ),
+        ));
+    }
+
+    let spend = state
+        .daily_spend
+        .try_spend(fee_payer_share)
+        .map_err(|remaining| {
+            warn!(
+                "Daily fee cap reached: {} wei needed, {} wei left",
+                fee_payer_share, remaining
+            );
+            api_error(
+                StatusCode::FORBIDDEN,
+                format!(
+                    "Daily fee delegation cap reached: this transaction needs {} wei, {} wei remains in the 24h window",
+                    fee_payer_share, remaining
+                ),
+            )
+        })?;
+
+    let fee_payer_sig = tx.sign_as_fee_payer(fee_payer).map_err(|e| {
+        error!("Fee payer signing failed: {}", e);
+        state.daily_spend.refund(spend);
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Fee payer signing failed",
+        )
+    })?;
+    let raw = tx.encode_signed(&sender_sig, fee_payer.address(), &fee_payer_sig);
+
+    let tx_hash: B256 = time_rpc(
+        "kaia_sendRawTransaction",
+        provider.raw_request("kaia_sendRawTransaction".into(), (raw,)),
+    )
+    .await
+    .map_err(|e| {
+        error!("kaia_sendRawTransaction failed: {}", e);
+        state.daily_spend.refund(spend);
+        api_error(StatusCode::BAD_GATEWAY, format!("Submission failed: {}", e))
+    })?;
+    info!("Submitted fee-delegated transaction {}", tx_hash);
+    state.record_submitted(tx_hash);
 
     Ok((
         StatusCode::OK,
         Json(DelegationResponse {
-            transaction_hash: tx_hash,
+            transaction_hash: tx_hash.to_string(),
             fee_payer: state.fee_payer_address.clone(),
             status: "pending".to_string(),
+            confirmations: None,
+            block_number: None,
+            gas_used: None,
         }),
     ))
 }
 
+// Parses the request into the Kaia transaction the sender signed; a
+// partial FEE_SPONSORSHIP_RATIO selects the *WithRatio transaction types
+async fn build_fee_delegated_tx<P: Provider>(
+    state: &AppState,
+    provider: &P,
+    transaction: &DelegatedTransaction,
+) -> Result<kaia_tx::FeeDelegatedTx, StatusCode> {
+    let from = Address::from_str(&transaction.from).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let nonce = match transaction.nonce {
+        Some(nonce) => nonce,
+        None => time_rpc(
+            "eth_getTransactionCount",
+            provider.get_transaction_count(from).pending().into_future(),
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch nonce for {}: {}", from, e);
+            StatusCode::BAD_GATEWAY
+        })?,
+    };
+
+    Ok(kaia_tx::FeeDelegatedTx {
+        nonce,
+        gas_price: U256::from_str(&transaction.gas_price).map_err(|_| StatusCode::BAD_REQUEST)?,
+        gas: transaction
+            .gas
+            .parse()
+            .map_err(|_| StatusCode::BAD_REQUEST)?,
+        to: Address::from_str(&transaction.to).map_err(|_| StatusCode::BAD_REQUEST)?,
+        value: U256::from_str(&transaction.value).map_err(|_| StatusCode::BAD_REQUEST)?,
+        from,
+        input: Bytes::from_str(if transaction.data.is_empty() {
+            "0x"
+        } else {
+            &transaction.data
+        })
+        .map_err(|_| StatusCode::BAD_REQUEST)?,
+        fee_ratio: (state.fee_ratio < 100).then_some(state.fee_ratio),
+        chain_id: state.chain_id,
+    })
+}
+
 async fn estimate_fee(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(transaction): Json<DelegatedTransaction>,
 ) -> Result<Json<FeeEstimate>, StatusCode> {
     info!("Estimating fee for transaction to: {}", transaction.to);
 
+    let request = TransactionRequest::default()
+        .from(Address::from_str(&transaction.from).map_err(|_| StatusCode::BAD_REQUEST)?)
+        .to(Address::from_str(&transaction.to).map_err(|_| StatusCode::BAD_REQUEST)?)
+        .value(U256::from_str(&transaction.value).map_err(|_| StatusCode::BAD_REQUEST)?)
+        .input(
+            Bytes::from_str(if transaction.data.is_empty() {
+                "0x"
+            } else {
+                &transaction.data
+            })
+            .map_err(|_| StatusCode::BAD_REQUEST)?
+            .into(),
+        );
+
+    // Contract calls cost more than a plain transfer, so ask the node rather than assume 21000
+    let (gas_limit, gas_price) = rpc_fee_quote(&state.rpc_url, request).await.map_err(|e| {
+        error!("Fee estimation failed for {}: {}", transaction.to, e);
+        StatusCode::BAD_GATEWAY
+    })?;
+    let estimated_fee = gas_limit
+        .checked_mul(gas_price)
+        .ok_or(StatusCode::BAD_GATEWAY)?;
+    let fee_payer_share =
+        fee_payer_share(gas_price, gas_limit, state.fee_ratio).ok_or(StatusCode::BAD_GATEWAY)?;
+    let user_share = estimated_fee - fee_payer_share;
+
     Ok(Json(FeeEstimate {
-        estimated_fee: "1000000000000000".to_string(),
-        gas_price: "25000000000".to_string(),
-        gas_limit: "21000".to_string(),
+        estimated_fee: estimated_fee.to_string(),
+        gas_price: gas_price.to_string(),
+        gas_limit: gas_limit.to_string(),
+        fee_ratio: state.fee_ratio,
+        fee_payer_share: fee_payer_share.to_string(),
+        user_share: user_share.to_string(),
     }))
 }
 
+// The part of gas_price * gas the fee payer covers at `fee_ratio` percent;
+// None if the fee doesn't fit in a U256
+fn fee_payer_share(gas_price: U256, gas: U256, fee_ratio: u8) -> Option<U256> {
+    gas_price
+        .checked_mul(gas)?
+        .checked_mul(U256::from(fee_ratio))
+        .map(|share| share / U256::from(100u64))
+}
+
+// (eth_estimateGas, eth_gasPrice) for the transaction
+async fn rpc_fee_quote(rpc_url: &str, request: TransactionRequest) -> anyhow::Result<(U256, U256)> {
+    let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+    let gas_limit = time_rpc(
+        "eth_estimateGas",
+        provider.estimate_gas(request).into_future(),
+    )
+    .await?;
+    let gas_price = time_rpc("eth_gasPrice", provider.get_gas_price().into_future()).await?;
+    Ok((U256::from(gas_limit), U256::from(gas_price)))
+}
+
 async fn get_delegation_status(
     State(state): State<Arc<AppState>>,
     axum::extract::Path(tx_hash): axum::extract::Path<String>,
 ) -> Result<Json<DelegationResponse>, StatusCode> {
     info!("Getting delegation status for: {}", tx_hash);
 
+    let hash = B256::from_str(&tx_hash).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !state.was_submitted(hash) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let (status, receipt) = delegation_status(&state, hash).await.map_err(|e| {
+        error!("Failed to fetch receipt for {}: {}", tx_hash, e);
+        StatusCode::BAD_GATEWAY
+    })?;
+
     Ok(Json(DelegationResponse {
         transaction_hash: tx_hash,
         fee_payer: state.fee_payer_address.clone(),
-        status: "confirmed".to_string(),
+        status: status.to_string(),
+        confirmations: receipt.map(|r| r.confirmations),
+        block_number: receipt.map(|r| r.block_number),
+        gas_used: receipt.map(|r| r.gas_used),
+    }))
+}
+
+// Pushes a StatusEvent on every change (pending -> confirmed/failed) and closes
+// once the transaction reaches a final status
+async fn subscribe_delegation_status(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(tx_hash): axum::extract::Path<String>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    let hash = B256::from_str(&tx_hash).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !state.was_submitted(hash) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let slot = state
+        .status_subscriptions
+        .acquire(client.ip())
+        .map_err(|limit| {
+            match limit {
+                LimitReached::Client => {
+                    warn!("{} has too many status subscriptions open", client.ip())
+                }
+                LimitReached::Service => warn!("Status subscription limit reached"),
+            }
+            StatusCode::TOO_MANY_REQUESTS
+        })?;
+    Ok(ws.on_upgrade(move |socket| stream_status(socket, state, hash, tx_hash, slot)))
+}
+
+// Holds its subscription slot until it returns
+async fn stream_status(
+    mut socket: WebSocket,
+    state: Arc<AppState>,
+    hash: B256,
+    tx_hash: String,
+    _slot: SubscriptionSlot,
+) {
+    let deadline = tokio::time::Instant::now() + STATUS_SUBSCRIPTION_TIMEOUT;
+    let mut last_sent: Option<StatusEvent> = None;
+
+    while tokio::time::Instant::now() < deadline {
+        match delegation_status(&state, hash).await {
+            Ok((status, receipt)) => {
+                let event = StatusEvent {
+                    transaction_hash: tx_hash.clone(),
+                    status: status.to_string(),
+                    confirmations: receipt.map(|r| r.confirmations),
+                };
+                if last_sent.as_ref() != Some(&event) {
+                    let Ok(payload) = serde_json::to_string(&event) else {
+                        break;
+                    };
+                    if socket.send(Message::Text(payload.into())).await.is_err() {
+                        // Client went away
+                        return;
+                    }
+                    last_sent = Some(event);
+                }
+                if status != "pending" {
+                    break;
+                }
+            }
+            Err(e) => warn!("Status poll for {} failed, retrying: {}", tx_hash, e),
+        }
+        if !wait_for_next_poll(&mut socket).await {
+            return;
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+// Sleeps for STATUS_POLL_INTERVAL, but returns false as soon as the client closes
+// the socket so its slot isn't held until the next send fails
+async fn wait_for_next_poll(socket: &mut WebSocket) -> bool {
+    let next_poll = tokio::time::sleep(STATUS_POLL_INTERVAL);
+    tokio::pin!(next_poll);
+    loop {
+        tokio::select! {
+            _ = &mut next_poll => return true,
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return false,
+                // Pings are answered by axum; anything else is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ReceiptInfo {
+    confirmations: u64, // Blocks including the one the tx was mined in
+    success: bool,
+    block_number: u64,
+    gas_used: u64,
+}
+
+// "pending" until the receipt is buried under min_confirmations blocks, then
+// "confirmed" or, if the transaction reverted, "failed"
+async fn delegation_status(
+    state: &AppState,
+    hash: B256,
+) -> anyhow::Result<(&'static str, Option<ReceiptInfo>)> {
+    let receipt = fetch_receipt(&state.rpc_url, hash).await?;
+    let status = match receipt {
+        Some(r) if r.confirmations >= state.min_confirmations => {
+            if r.success {
+                "confirmed"
+            } else {
+                "failed"
+            }
+        }
+        _ => "pending",
+    };
+    Ok((status, receipt))
+}
+
+// None if not yet mined
+async fn fetch_receipt(rpc_url: &str, hash: B256) -> anyhow::Result<Option<ReceiptInfo>> {
+    let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+
+    let Some(receipt) = time_rpc(
+        "eth_getTransactionReceipt",
+        provider.get_transaction_receipt(hash).into_future(),
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+    let Some(mined_in) = receipt.block_number else {
+        return Ok(None);
+    };
+
+    let latest = time_rpc("eth_blockNumber", provider.get_block_number().into_future()).await?;
+    Ok(Some(ReceiptInfo {
+        confirmations: latest.saturating_sub(mined_in) + 1,
+        success: receipt.status(),
+        block_number: mined_in,
+        gas_used: receipt.gas_used,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::SignerSync;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn key(n: u8) -> PrivateKeySigner {
+        PrivateKeySigner::from_bytes(&B256::with_last_byte(n)).unwrap()
+    }
+
+    const MOCK_TX_HASH: B256 = B256::repeat_byte(0x77);
+
+    // JSON-RPC endpoint on a local port that records every call and answers
+    // balance reads and raw submissions
+    async fn mock_rpc() -> (String, Arc<Mutex<Vec<Value>>>) {
+        mock_rpc_with(default_response, Duration::ZERO).await
+    }
+
+    fn default_response(method: &str, _params: &Value) -> Value {
+        match method {
+            "eth_getBalance" => Value::from("0xde0b6b3a7640000"),
+            "kaia_sendRawTransaction" => Value::from(MOCK_TX_HASH.to_string()),
+            _ => Value::Null,
+        }
+    }
+
+    // Same, answering with `respond` after `delay`
+    async fn mock_rpc_with(
+        respond: impl Fn(&str, &Value) -> Value + Send + Sync + 'static,
+        delay: Duration,
+    ) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let respond = Arc::new(respond);
+        let app = Router::new().route(
+            "/",
+            post(move |Json(call): Json<Value>| async move {
+                let result = respond(call["method"].as_str().unwrap_or_default(), &call["params"]);
+                let id = call["id"].clone();
+                recorded.lock().unwrap().push(call);
+                tokio::time::sleep(delay).await;
+                Json(serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, calls)
+    }
+
+    fn test_state(rpc_url: String, fee_payer: PrivateKeySigner) -> AppState {
+        AppState {
+            fee_payer_address: fee_payer.address().to_string(),
+            fee_payer: Some(fee_payer),
+            chain_id: KAIA_TESTNET_CHAIN_ID,
+            rpc_url,
+            min_confirmations: DEFAULT_MIN_CONFIRMATIONS,
+            fee_ratio: 100,
+            deployment_mode: DeploymentMode::Demo,
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            submitted: Arc::new(Mutex::new(SubmittedHashes::new(MAX_TRACKED_SUBMISSIONS))),
+            daily_spend: Arc::new(DailySpend::new(None)),
+            status_subscriptions: SubscriptionLimiter::new(1, 0),
+        }
+    }
+
+    #[tokio::test]
+    async fn delegation_submits_with_the_fee_payer_signature() {
+        let (rpc_url, calls) = mock_rpc().await;
+        let fee_payer = key(2);
+        let sender = key(1);
+        let state = Arc::new(test_state(rpc_url, fee_payer.clone()));
+        let tx = kaia_tx::FeeDelegatedTx {
+            nonce: 0,
+            gas_price: U256::from(25_000_000_000u64),
+            gas: 21_000,
+            to: Address::repeat_byte(0x11),
+            value: U256::from(1_000u64),
+            from: sender.address(),
+            input: Bytes::new(),
+            fee_ratio: None,
+            chain_id: KAIA_TESTNET_CHAIN_ID,
+        };
+        let user_signature = sender.sign_hash_sync(&tx.sender_sig_hash()).unwrap();
+
+        let (status, Json(response)) = delegate_fee(
+            State(state.clone()),
+            Json(DelegationRequest {
+                transaction: DelegatedTransaction {
+                    nonce: Some(0),
+                    from: sender.address().to_string(),
+                    to: tx.to.to_string(),
+                    value: tx.value.to_string(),
+                    data: String::new(),
+                    gas: tx.gas.to_string(),
+                    gas_price: tx.gas_price.to_string(),
+                },
+                user_signature: Bytes::from(user_signature.as_bytes()).to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.transaction_hash, MOCK_TX_HASH.to_string());
+        assert!(state.was_submitted(MOCK_TX_HASH));
+
+        // Signing is deterministic, so the raw transaction can be rebuilt exactly
+        let sender_sig = tx
+            .verify_sender_signature(&user_signature.as_bytes())
+            .unwrap();
+        let fee_payer_sig = tx.sign_as_fee_payer(&fee_payer).unwrap();
+        let expected = tx.encode_signed(&sender_sig, fee_payer.address(), &fee_payer_sig);
+        let calls = calls.lock().unwrap();
+        let submitted = calls
+            .iter()
+            .find(|c| c["method"] == "kaia_sendRawTransaction")
+            .expect("no kaia_sendRawTransaction call");
+        assert_eq!(submitted["params"][0], Value::from(expected.to_string()));
+    }
+
+    #[tokio::test]
+    async fn forged_user_signature_is_not_paid_for() {
+        let (rpc_url, calls) = mock_rpc().await;
+        let state = Arc::new(test_state(rpc_url, key(2)));
+        let sender = key(1);
+        let tx = kaia_tx::FeeDelegatedTx {
+            nonce: 0,
+            gas_price: U256::from(25_000_000_000u64),
+            gas: 21_000,
+            to: Address::repeat_byte(0x11),
+            value: U256::ZERO,
+            from: sender.address(),
+            input: Bytes::new(),
+            fee_ratio: None,
+            chain_id: KAIA_TESTNET_CHAIN_ID,
+        };
+        // Signed by someone other than `from`
+        let forged = key(3).sign_hash_sync(&tx.sender_sig_hash()).unwrap();
+
+        let result = delegate_fee(
+            State(state),
+            Json(DelegationRequest {
+                transaction: DelegatedTransaction {
+                    nonce: Some(0),
+                    from: sender.address().to_string(),
+                    to: tx.to.to_string(),
+                    value: "0".to_string(),
+                    data: String::new(),
+                    gas: tx.gas.to_string(),
+                    gas_price: tx.gas_price.to_string(),
+                },
+                user_signature: Bytes::from(forged.as_bytes()).to_string(),
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            result.err().map(|(status, _)| status),
+            Some(StatusCode::FORBIDDEN)
+        );
+        assert!(calls
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|c| c["method"] != "kaia_sendRawTransaction"));
+    }
+
+    #[test]
+    fn submitted_hashes_forget_the_oldest_past_capacity() {
+        let mut submitted = SubmittedHashes::new(2);
+        for n in 1..=3 {
+            submitted.insert(B256::repeat_byte(n));
+        }
+        // Re-recording a known hash doesn't push anything out
+        submitted.insert(B256::repeat_byte(3));
+
+        assert!(!submitted.contains(&B256::repeat_byte(1)));
+        assert!(submitted.contains(&B256::repeat_byte(2)));
+        assert!(submitted.contains(&B256::repeat_byte(3)));
+        assert_eq!(submitted.order.len(), 2);
+    }
+
+    // Opens a status WebSocket by hand; the status line of the reply tells
+    // whether the upgrade was accepted (101) or refused
+    async fn open_status_socket(server: SocketAddr, hash: B256) -> (String, tokio::net::TcpStream) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(server).await.unwrap();
+        let request = format!(
+            "GET /api/v1/ws/status/{} HTTP/1.1\r\nHost: {}\r\nConnection: Upgrade\r\n\
+             Upgrade: websocket\r\nSec-WebSocket-Version: 13\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            hash, server
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            let mut byte = [0u8; 1];
+            stream.read_exact(&mut byte).await.unwrap();
+            response.push(byte[0]);
+        }
+        let status_line = String::from_utf8(response)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .to_string();
+        (status_line, stream)
+    }
+
+    // Serves the status WebSocket for `state` on a local port
+    async fn status_server(state: Arc<AppState>) -> SocketAddr {
+        let app = Router::new()
+            .route(
+                "/api/v1/ws/status/{tx_hash}",
+                get(subscribe_delegation_status),
+            )
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+        server
+    }
+
+    // Next unmasked server frame as (opcode, payload)
+    async fn read_frame(stream: &mut tokio::net::TcpStream) -> (u8, Vec<u8>) {
+        use tokio::io::AsyncReadExt;
+
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await.unwrap();
+        let len = match header[1] & 0x7f {
+            126 => stream.read_u16().await.unwrap() as usize,
+            127 => stream.read_u64().await.unwrap() as usize,
+            len => len as usize,
+        };
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload).await.unwrap();
+        (header[0] & 0x0f, payload)
+    }
+
+    #[tokio::test]
+    async fn status_subscriptions_are_capped_per_client() {
+        let (rpc_url, _) = mock_rpc().await;
+        let state = Arc::new(test_state(rpc_url, key(2)));
+        state.record_submitted(MOCK_TX_HASH);
+        let server = status_server(state).await;
+
+        let (status, first) = open_status_socket(server, MOCK_TX_HASH).await;
+        assert!(status.contains("101"), "{}", status);
+        let (status, _) = open_status_socket(server, MOCK_TX_HASH).await;
+        assert!(status.contains("429"), "{}", status);
+
+        // Hanging up frees the slot without waiting for the subscription to time out
+        drop(first);
+        let reopened = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                let (status, stream) = open_status_socket(server, MOCK_TX_HASH).await;
+                if status.contains("101") {
+                    return stream;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(reopened.is_ok());
+    }
+
+    // A plain value transfer from `sender` at `nonce`
+    fn transfer(sender: &PrivateKeySigner, nonce: u64) -> kaia_tx::FeeDelegatedTx {
+        kaia_tx::FeeDelegatedTx {
+            nonce,
+            gas_price: U256::from(25_000_000_000u64),
+            gas: 21_000,
+            to: Address::repeat_byte(0x11),
+            value: U256::from(1_000u64),
+            from: sender.address(),
+            input: Bytes::new(),
+            fee_ratio: None,
+            chain_id: KAIA_TESTNET_CHAIN_ID,
+        }
+    }
+
+    // The request a wallet sends for `tx`, signed by `sender`
+    fn signed_request(
+        sender: &PrivateKeySigner,
+        tx: &kaia_tx::FeeDelegatedTx,
+    ) -> DelegationRequest {
+        let user_signature = sender.sign_hash_sync(&tx.sender_sig_hash()).unwrap();
+        DelegationRequest {
+            transaction: DelegatedTransaction {
+                nonce: Some(tx.nonce),
+                from: tx.from.to_string(),
+                to: tx.to.to_string(),
+                value: tx.value.to_string(),
+                data: String::new(),
+                gas: tx.gas.to_string(),
+                gas_price: tx.gas_price.to_string(),
+            },
+            user_signature: Bytes::from(user_signature.as_bytes()).to_string(),
+        }
+    }
+
+    // Raw transactions handed to kaia_sendRawTransaction, in order
+    fn submitted_raw(calls: &Mutex<Vec<Value>>) -> Vec<Value> {
+        calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c["method"] == "kaia_sendRawTransaction")
+            .map(|c| c["params"][0].clone())
+            .collect()
+    }
+
+    // Node whose fee payer holds `balance` wei
+    async fn funded_rpc(balance: u64) -> (String, Arc<Mutex<Vec<Value>>>) {
+        mock_rpc_with(
+            move |method, params| match method {
+                "eth_getBalance" => Value::from(format!("{:#x}", balance)),
+                _ => default_response(method, params),
+            },
+            Duration::ZERO,
+        )
+        .await
+    }
+
+    // 21000 gas at 25 gwei
+    const TRANSFER_FEE: u64 = 525_000_000_000_000;
+
+    #[tokio::test]
+    async fn underfunded_fee_payer_is_unavailable() {
+        let (rpc_url, calls) = funded_rpc(TRANSFER_FEE - 1).await;
+        let state = Arc::new(test_state(rpc_url, key(2)));
+        let sender = key(1);
+
+        let (status, Json(body)) = delegate_fee(
+            State(state.clone()),
+            Json(signed_request(&sender, &transfer(&sender, 0))),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .contains(&format!("{} wei fee", TRANSFER_FEE)));
+        assert!(submitted_raw(&calls).is_empty());
+        // Operators see the same balance on /health
+        let Json(health) = health(State(state)).await;
+        assert_eq!(
+            health.fee_payer_balance,
+            Some((TRANSFER_FEE - 1).to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn exactly_enough_balance_is_submitted() {
+        let (rpc_url, calls) = funded_rpc(TRANSFER_FEE).await;
+        let state = Arc::new(test_state(rpc_url, key(2)));
+        let sender = key(1);
+
+        let (status, _) = delegate_fee(
+            State(state),
+            Json(signed_request(&sender, &transfer(&sender, 0))),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(submitted_raw(&calls).len(), 1);
+    }
+
+    // Kaia fee delegation spends the sender's nonce; the fee payer only co-signs
+    // and has no nonce of its own at stake. Concurrent delegations are therefore
+    // told apart by (sender, nonce).
+    #[tokio::test]
+    async fn concurrent_delegations_each_submit_their_own_nonce() {
+        let (rpc_url, calls) = mock_rpc_with(default_response, Duration::from_millis(20)).await;
+        let fee_payer = key(2);
+        let state = Arc::new(test_state(rpc_url, fee_payer.clone()));
+        let senders = [key(1), key(3)];
+
+        let delegations: Vec<_> = senders
+            .iter()
+            .flat_map(|sender| (0..8).map(move |nonce| (sender.clone(), nonce)))
+            .map(|(sender, nonce)| {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let tx = transfer(&sender, nonce);
+                    let result =
+                        delegate_fee(State(state), Json(signed_request(&sender, &tx))).await;
+                    (tx, sender, result.map(|(status, _)| status))
+                })
+            })
+            .collect();
+
+        let mut expected = HashSet::new();
+        for delegation in delegations {
+            let (tx, sender, status) = delegation.await.unwrap();
+            assert_eq!(status.ok(), Some(StatusCode::OK));
+            let sender_sig = tx
+                .verify_sender_signature(
+                    &sender
+                        .sign_hash_sync(&tx.sender_sig_hash())
+                        .unwrap()
+                        .as_bytes(),
+                )
+                .unwrap();
+            let fee_payer_sig = tx.sign_as_fee_payer(&fee_payer).unwrap();
+            expected.insert(
+                tx.encode_signed(&sender_sig, fee_payer.address(), &fee_payer_sig)
+                    .to_string(),
+            );
+        }
+
+        let submitted: HashSet<String> = submitted_raw(&calls)
+            .iter()
+            .map(|raw| raw.as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(submitted.len(), 16);
+        assert_eq!(submitted, expected);
+        assert!(state.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn same_sender_nonce_in_flight_twice_is_a_conflict() {
+        let (rpc_url, calls) = mock_rpc_with(default_response, Duration::from_millis(100)).await;
+        let state = Arc::new(test_state(rpc_url, key(2)));
+        let sender = key(1);
+        let tx = transfer(&sender, 0);
+
+        let (first, second) = tokio::join!(
+            delegate_fee(State(state.clone()), Json(signed_request(&sender, &tx))),
+            delegate_fee(State(state.clone()), Json(signed_request(&sender, &tx))),
+        );
+
+        let mut statuses = [
+            first
+                .map(|(status, _)| status)
+                .unwrap_or_else(|(status, _)| status),
+            second
+                .map(|(status, _)| status)
+                .unwrap_or_else(|(status, _)| status),
+        ];
+        statuses.sort();
+        assert_eq!(statuses, [StatusCode::OK, StatusCode::CONFLICT]);
+        assert_eq!(submitted_raw(&calls).len(), 1);
+        // The slot is released once the submission finishes
+        assert!(state.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn half_sponsorship_splits_the_estimate() {
+        // 21001 gas at 25 gwei
+        let (rpc_url, _) = mock_rpc_with(
+            |method, params| match method {
+                "eth_estimateGas" => Value::from("0x5209"),
+                "eth_gasPrice" => Value::from("0x5d21dba00"),
+                _ => default_response(method, params),
+            },
+            Duration::ZERO,
+        )
+        .await;
+        let mut state = test_state(rpc_url, key(2));
+        state.fee_ratio = 50;
+        let sender = key(1);
+        let request = signed_request(&sender, &transfer(&sender, 0)).transaction;
+
+        let Json(estimate) = estimate_fee(State(Arc::new(state)), Json(request))
+            .await
+            .unwrap();
+
+        assert_eq!(estimate.fee_ratio, 50);
+        assert_eq!(estimate.gas_limit, "21001");
+        assert_eq!(estimate.estimated_fee, "525025000000000");
+        assert_eq!(estimate.fee_payer_share, "262512500000000");
+        assert_eq!(estimate.user_share, "262512500000000");
+    }
+
+    #[tokio::test]
+    async fn odd_fee_rounds_in_the_fee_payers_favour() {
+        let (rpc_url, _) = mock_rpc_with(
+            |method, params| match method {
+                "eth_estimateGas" => Value::from("0x5209"),
+                "eth_gasPrice" => Value::from("0x1"),
+                _ => default_response(method, params),
+            },
+            Duration::ZERO,
+        )
+        .await;
+        let mut state = test_state(rpc_url, key(2));
+        state.fee_ratio = 50;
+        let sender = key(1);
+        let request = signed_request(&sender, &transfer(&sender, 0)).transaction;
+
+        let Json(estimate) = estimate_fee(State(Arc::new(state)), Json(request))
+            .await
+            .unwrap();
+
+        assert_eq!(estimate.estimated_fee, "21001");
+        assert_eq!(estimate.fee_payer_share, "10500");
+        assert_eq!(estimate.user_share, "10501");
+    }
+
+    #[tokio::test]
+    async fn failed_gas_estimate_is_a_bad_gateway() {
+        // The default node answers eth_estimateGas with null, which isn't a quantity
+        let (rpc_url, calls) = mock_rpc().await;
+        let sender = key(1);
+        let mut request = signed_request(&sender, &transfer(&sender, 0)).transaction;
+        request.data = "0xa9059cbb".to_string();
+
+        let result =
+            estimate_fee(State(Arc::new(test_state(rpc_url, key(2)))), Json(request)).await;
+
+        assert_eq!(result.err(), Some(StatusCode::BAD_GATEWAY));
+        let methods: Vec<Value> = calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| c["method"].clone())
+            .collect();
+        assert_eq!(methods, ["eth_estimateGas"]);
+    }
+
+    #[test]
+    fn fee_payer_share_applies_the_ratio() {
+        let share = fee_payer_share(U256::from(25_000_000_000u64), U256::from(21_000u64), 30);
+        assert_eq!(share, Some(U256::from(157_500_000_000_000u64)));
+    }
+
+    #[test]
+    fn fee_payer_share_rejects_overflow() {
+        assert_eq!(fee_payer_share(U256::MAX, U256::from(2u64), 100), None);
+        // The product fits, but not once the ratio is applied
+        assert_eq!(
+            fee_payer_share(U256::MAX / U256::from(2u64), U256::from(1u64), 3),
+            None
+        );
+    }
+
+    // Receipt of MOCK_TX_HASH mined in `block`; `status` "0x0" means it reverted
+    fn receipt(block: u64, status: &str) -> Value {
+        serde_json::json!({
+            "transactionHash": MOCK_TX_HASH,
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(0xbb),
+            "blockNumber": format!("{:#x}", block),
+            "from": key(1).address(),
+            "to": Address::repeat_byte(0x11),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x5d21dba00",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": alloy::primitives::Bloom::ZERO,
+            "status": status,
+            "type": "0x0",
+        })
+    }
+
+    // Node with MOCK_TX_HASH mined in block 10 and a chain head that tests move
+    async fn mined_at_block_ten(status: &'static str) -> (String, Arc<AtomicU64>) {
+        let head = Arc::new(AtomicU64::new(10));
+        let latest = head.clone();
+        let (rpc_url, _) = mock_rpc_with(
+            move |method, params| match method {
+                "eth_getTransactionReceipt" => receipt(10, status),
+                "eth_blockNumber" => Value::from(format!("{:#x}", latest.load(Ordering::SeqCst))),
+                _ => default_response(method, params),
+            },
+            Duration::ZERO,
+        )
+        .await;
+        (rpc_url, head)
+    }
+
+    #[tokio::test]
+    async fn subscriber_is_pushed_confirmation_then_closed() {
+        let (rpc_url, head) = mined_at_block_ten("0x1").await;
+        let state = Arc::new(test_state(rpc_url, key(2)));
+        state.record_submitted(MOCK_TX_HASH);
+        let server = status_server(state).await;
+
+        let (status, mut stream) = open_status_socket(server, MOCK_TX_HASH).await;
+        assert!(status.contains("101"), "{}", status);
+        let (opcode, pending) = read_frame(&mut stream).await;
+        assert_eq!(opcode, 0x1);
+        let pending: Value = serde_json::from_slice(&pending).unwrap();
+        assert_eq!(pending["status"], "pending");
+        assert_eq!(pending["confirmations"], 1);
+
+        // Buried under min_confirmations blocks before the next poll
+        head.store(12, Ordering::SeqCst);
+        let (opcode, confirmed) =
+            tokio::time::timeout(Duration::from_secs(5), read_frame(&mut stream))
+                .await
+                .unwrap();
+        assert_eq!(opcode, 0x1);
+        let confirmed: Value = serde_json::from_slice(&confirmed).unwrap();
+        assert_eq!(confirmed["transaction_hash"], MOCK_TX_HASH.to_string());
+        assert_eq!(confirmed["status"], "confirmed");
+        assert_eq!(confirmed["confirmations"], 3);
+        let (opcode, _) = read_frame(&mut stream).await;
+        assert_eq!(opcode, 0x8, "close frame");
+    }
+
+    #[tokio::test]
+    async fn delegation_is_pending_until_buried_deep_enough() {
+        let (rpc_url, head) = mined_at_block_ten("0x1").await;
+        let state = test_state(rpc_url, key(2));
+        assert_eq!(state.min_confirmations, 3);
+
+        for (latest, confirmations, expected) in [
+            (10, 1, "pending"),
+            (11, 2, "pending"),
+            (12, 3, "confirmed"),
+            (20, 11, "confirmed"),
+        ] {
+            head.store(latest, Ordering::SeqCst);
+            let (status, receipt) = delegation_status(&state, MOCK_TX_HASH).await.unwrap();
+            assert_eq!(status, expected, "head at block {}", latest);
+            assert_eq!(receipt.unwrap().confirmations, confirmations);
+        }
+    }
+
+    #[tokio::test]
+    async fn reverted_delegation_fails_only_once_confirmed() {
+        let (rpc_url, head) = mined_at_block_ten("0x0").await;
+        let state = test_state(rpc_url, key(2));
+
+        let (status, _) = delegation_status(&state, MOCK_TX_HASH).await.unwrap();
+        assert_eq!(status, "pending");
+
+        head.store(12, Ordering::SeqCst);
+        let (status, receipt) = delegation_status(&state, MOCK_TX_HASH).await.unwrap();
+        assert_eq!(status, "failed");
+        assert!(!receipt.unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn status_endpoint_maps_each_receipt_state() {
+        let status_of = |state: AppState, hash: String| async move {
+            get_delegation_status(State(Arc::new(state)), axum::extract::Path(hash)).await
+        };
+        let submitted = |rpc_url: String| {
+            let state = test_state(rpc_url, key(2));
+            state.record_submitted(MOCK_TX_HASH);
+            state
+        };
+
+        // Not mined yet: the default node has no receipt
+        let (rpc_url, _) = mock_rpc().await;
+        let Json(pending) = status_of(submitted(rpc_url), MOCK_TX_HASH.to_string())
+            .await
+            .unwrap();
+        assert_eq!(pending.status, "pending");
+        assert_eq!((pending.block_number, pending.gas_used), (None, None));
+
+        for (receipt_status, expected) in [("0x1", "confirmed"), ("0x0", "failed")] {
+            let (rpc_url, head) = mined_at_block_ten(receipt_status).await;
+            head.store(12, Ordering::SeqCst);
+            let Json(mined) = status_of(submitted(rpc_url), MOCK_TX_HASH.to_string())
+                .await
+                .unwrap();
+            assert_eq!(mined.status, expected);
+            assert_eq!(mined.confirmations, Some(3));
+            assert_eq!(mined.block_number, Some(10));
+            assert_eq!(mined.gas_used, Some(21_000));
+        }
+
+        // Never handed out by this service, however the chain sees it
+        let (rpc_url, _) = mined_at_block_ten("0x1").await;
+        let unknown = status_of(
+            test_state(rpc_url.clone(), key(2)),
+            MOCK_TX_HASH.to_string(),
+        )
+        .await;
+        assert_eq!(unknown.err(), Some(StatusCode::NOT_FOUND));
+        let malformed = status_of(test_state(rpc_url, key(2)), "0x1234".to_string()).await;
+        assert_eq!(malformed.err(), Some(StatusCode::BAD_REQUEST));
+    }
+}
@@ -0,0 +1,171 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+pub const DEFAULT_DELEGATE_RATE_LIMIT_PER_MIN: u32 = 10;
+
+// Full buckets carry no state worth keeping, so they're dropped once the map grows past this
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+// Token bucket per client IP: `per_minute` requests of burst, refilled evenly
+// over a minute. None disables the limit.
+#[derive(Clone)]
+pub struct RateLimiter {
+    per_minute: Option<u32>,
+    buckets: Arc<Mutex<HashMap<IpAddr, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute: (per_minute > 0).then_some(per_minute),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // 0 turns the limit off
+    pub fn from_env(var: &str, default: u32) -> Self {
+        let per_minute = match std::env::var(var) {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                warn!("Invalid {} '{}', using {}", var, v, default);
+                default
+            }),
+            Err(_) => default,
+        };
+        Self::new(per_minute)
+    }
+
+    // Err carries how long until the next request would be allowed
+    fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let Some(per_minute) = self.per_minute else {
+            return Ok(());
+        };
+        let capacity = per_minute as f64;
+        let refill_per_sec = capacity / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, b| {
+                b.tokens + now.duration_since(b.updated).as_secs_f64() * refill_per_sec < capacity
+            });
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / refill_per_sec,
+            ))
+        }
+    }
+}
+
+pub async fn limit(State(limiter): State<RateLimiter>, request: Request, next: Next) -> Response {
+    // Peer address as seen by this process; behind a proxy every client shares one bucket
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    match limiter.check(ip) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            warn!("Rate limited {} on {}", ip, request.uri().path());
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, secs.to_string())],
+                Json(serde_json::json!({ "error": "Too many requests, slow down" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::post, Router};
+    use tower::ServiceExt;
+
+    fn app(limiter: RateLimiter) -> Router {
+        Router::new().route(
+            "/api/v1/delegate",
+            post(|| async { "ok" }).layer(axum::middleware::from_fn_with_state(limiter, limit)),
+        )
+    }
+
+    async fn send(app: &Router, from: [u8; 4]) -> Response {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/api/v1/delegate")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((from, 40_000))));
+        app.clone().oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn burst_past_the_limit_gets_429_with_retry_after() {
+        let app = app(RateLimiter::new(3));
+
+        for _ in 0..3 {
+            assert_eq!(send(&app, [10, 0, 0, 1]).await.status(), StatusCode::OK);
+        }
+        let limited = send(&app, [10, 0, 0, 1]).await;
+
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        // One token refills every 20s at 3/min
+        assert_eq!(limited.headers()[header::RETRY_AFTER], "20");
+    }
+
+    #[tokio::test]
+    async fn clients_are_limited_separately() {
+        let app = app(RateLimiter::new(1));
+
+        assert_eq!(send(&app, [10, 0, 0, 1]).await.status(), StatusCode::OK);
+        assert_eq!(
+            send(&app, [10, 0, 0, 1]).await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(send(&app, [10, 0, 0, 2]).await.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn zero_disables_the_limit() {
+        let app = app(RateLimiter::new(0));
+
+        for _ in 0..50 {
+            assert_eq!(send(&app, [10, 0, 0, 1]).await.status(), StatusCode::OK);
+        }
+    }
+}
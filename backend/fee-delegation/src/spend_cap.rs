@@ -0,0 +1,98 @@
+use alloy::primitives::U256;
+use std::{
+    collections::VecDeque,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+const WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Fee payer spend over a rolling 24h window, counted at the estimated fee
+// when a delegation is accepted. None cap means unlimited.
+pub struct DailySpend {
+    cap: Option<U256>,
+    spent: Mutex<SpendLog>,
+}
+
+#[derive(Default)]
+struct SpendLog {
+    entries: VecDeque<(u64, Instant, U256)>,
+    next_id: u64,
+}
+
+impl SpendLog {
+    fn expire(&mut self, now: Instant) {
+        while let Some((_, at, _)) = self.entries.front() {
+            if now.duration_since(*at) < WINDOW {
+                break;
+            }
+            self.entries.pop_front();
+        }
+    }
+
+    fn total(&self) -> U256 {
+        self.entries.iter().fold(U256::ZERO, |sum, (_, _, amount)| {
+            sum.saturating_add(*amount)
+        })
+    }
+}
+
+// Handle to an accepted spend, so a failed submission can give it back
+#[derive(Debug, Clone, Copy)]
+pub struct SpendId(u64);
+
+impl DailySpend {
+    pub fn new(cap: Option<U256>) -> Self {
+        Self {
+            cap,
+            spent: Mutex::new(SpendLog::default()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let cap = std::env::var("FEE_PAYER_DAILY_CAP_WEI")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .and_then(|v| {
+                U256::from_str(&v)
+                    .map_err(|_| warn!("Invalid FEE_PAYER_DAILY_CAP_WEI '{}', no cap applied", v))
+                    .ok()
+            });
+        Self::new(cap)
+    }
+
+    pub fn remaining(&self) -> Option<U256> {
+        let cap = self.cap?;
+        let mut spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+        spent.expire(Instant::now());
+        Some(cap.saturating_sub(spent.total()))
+    }
+
+    // Records `amount` if it fits under the cap; Err carries what's left
+    pub fn try_spend(&self, amount: U256) -> Result<SpendId, U256> {
+        let now = Instant::now();
+        let mut spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+        spent.expire(now);
+        if let Some(cap) = self.cap {
+            let remaining = cap.saturating_sub(spent.total());
+            if amount > remaining {
+                return Err(remaining);
+            }
+        }
+
+        let id = spent.next_id;
+        spent.next_id += 1;
+        spent.entries.push_back((id, now, amount));
+        Ok(SpendId(id))
+    }
+
+    pub fn refund(&self, SpendId(id): SpendId) {
+        self.spent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .retain(|(entry, _, _)| *entry != id);
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+use tracing::warn;
+
+pub const DEFAULT_STATUS_WS_PER_CLIENT: usize = 5;
+pub const DEFAULT_STATUS_WS_TOTAL: usize = 500;
+
+// Each status subscription polls the node until its transaction settles, so open
+// ones are capped per client IP and across the service. 0 disables a cap.
+#[derive(Clone)]
+pub struct SubscriptionLimiter {
+    per_client: Option<usize>,
+    total: Option<usize>,
+    open: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitReached {
+    Client,
+    Service,
+}
+
+impl SubscriptionLimiter {
+    pub fn new(per_client: usize, total: usize) -> Self {
+        Self {
+            per_client: (per_client > 0).then_some(per_client),
+            total: (total > 0).then_some(total),
+            open: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            env_limit("STATUS_WS_MAX_PER_CLIENT", DEFAULT_STATUS_WS_PER_CLIENT),
+            env_limit("STATUS_WS_MAX_TOTAL", DEFAULT_STATUS_WS_TOTAL),
+        )
+    }
+
+    // The slot stays taken until the returned guard is dropped
+    pub fn acquire(&self, ip: IpAddr) -> Result<SubscriptionSlot, LimitReached> {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        if self
+            .total
+            .is_some_and(|max| open.values().sum::<usize>() >= max)
+        {
+            return Err(LimitReached::Service);
+        }
+        let count = open.entry(ip).or_default();
+        if self.per_client.is_some_and(|max| *count >= max) {
+            return Err(LimitReached::Client);
+        }
+        *count += 1;
+
+        Ok(SubscriptionSlot {
+            open: self.open.clone(),
+            ip,
+        })
+    }
+}
+
+fn env_limit(var: &str, default: usize) -> usize {
+    match std::env::var(var) {
+        Ok(v) => v.parse().unwrap_or_else(|_| {
+            warn!("Invalid {} '{}', using {}", var, v, default);
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+pub struct SubscriptionSlot {
+    open: Arc<Mutex<HashMap<IpAddr, usize>>>,
+    ip: IpAddr,
+}
+
+impl Drop for SubscriptionSlot {
+    fn drop(&mut self) {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = open.get_mut(&self.ip) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.ip);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::from([10, 0, 0, last])
+    }
+
+    #[test]
+    fn client_cap_applies_per_ip() {
+        let limiter = SubscriptionLimiter::new(2, 0);
+
+        let _first = limiter.acquire(ip(1)).unwrap();
+        let second = limiter.acquire(ip(1)).unwrap();
+        assert_eq!(limiter.acquire(ip(1)).err(), Some(LimitReached::Client));
+        assert!(limiter.acquire(ip(2)).is_ok());
+
+        // Closing a subscription frees its slot
+        drop(second);
+        assert!(limiter.acquire(ip(1)).is_ok());
+    }
+
+    #[test]
+    fn service_cap_applies_across_clients() {
+        let limiter = SubscriptionLimiter::new(0, 2);
+
+        let _a = limiter.acquire(ip(1)).unwrap();
+        let b = limiter.acquire(ip(2)).unwrap();
+        assert_eq!(limiter.acquire(ip(3)).err(), Some(LimitReached::Service));
+
+        drop(b);
+        assert!(limiter.acquire(ip(3)).is_ok());
+    }
+
+    #[test]
+    fn zero_disables_both_caps() {
+        let limiter = SubscriptionLimiter::new(0, 0);
+
+        let slots: Vec<_> = (0..100).map(|_| limiter.acquire(ip(1)).unwrap()).collect();
+        assert_eq!(slots.len(), 100);
+    }
+}
@@ -0,0 +1,291 @@
+use alloy_primitives::Address;
+use alloy_sol_types::{sol, SolCall};
+use shared::types::AdminOperation;
+
+use crate::{
+    safe_contract::{decode_multisend, Operation, SafeTransaction},
+    safe_contract_abi::ISafe,
+};
+
+// ModuleManager and FallbackManager functions missing from the ISafe ABI file
+sol! {
+    function enableModule(address module);
+    function disableModule(address prevModule, address module);
+    function setFallbackHandler(address handler);
+}
+
+// MultiSend batches nested deeper than this are treated as opaque delegatecalls
+const MAX_MULTISEND_DEPTH: usize = 4;
+
+// Self-calls reach the owner-management functions with the Safe as msg.sender,
+// and so does any delegatecall. Batches sent to the MultiSend contract are
+// unpacked, since its sub-calls run from the Safe too; any other delegatecall
+// is an admin operation in itself.
+pub fn decode(
+    safe_address: Address,
+    multisend: Option<Address>,
+    tx: &SafeTransaction,
+) -> Option<AdminOperation> {
+    decode_call(safe_address, multisend, tx.operation, tx.to, &tx.data, 0)
+}
+
+fn decode_call(
+    safe_address: Address,
+    multisend: Option<Address>,
+    operation: Operation,
+    to: Address,
+    data: &[u8],
+    depth: usize,
+) -> Option<AdminOperation> {
+    match operation {
+        Operation::Call if to == safe_address => decode_self_call(data),
+        Operation::Call => None,
+        Operation::DelegateCall if Some(to) == multisend && depth < MAX_MULTISEND_DEPTH => {
+            let Ok(calls) = decode_multisend(data) else {
+                return Some(AdminOperation::DelegateCall { target: to });
+            };
+            calls.iter().find_map(|call| {
+                // MultiSend substitutes the calling Safe for a zero `to`
+                let to = if call.to == Address::ZERO {
+                    safe_address
+                } else {
+                    call.to
+                };
+                decode_call(
+                    safe_address,
+                    multisend,
+                    call.operation,
+                    to,
+                    &call.data,
+                    depth + 1,
+                )
+            })
+        }
+        Operation::DelegateCall => Some(AdminOperation::DelegateCall { target: to }),
+    }
+}
+
+fn decode_self_call(data: &[u8]) -> Option<AdminOperation> {
+    let selector: [u8; 4] = data.get(..4)?.try_into().ok()?;
+
+    match selector {
+        ISafe::addOwnerWithThresholdCall::SELECTOR => {
+            let call = ISafe::addOwnerWithThresholdCall::abi_decode(data).ok()?;
+            Some(AdminOperation::AddOwner {
+                owner: call.owner,
+                threshold: call._threshold,
+            })
+        }
+        ISafe::removeOwnerCall::SELECTOR => {
+            let call = ISafe::removeOwnerCall::abi_decode(data).ok()?;
+            Some(AdminOperation::RemoveOwner {
+                prev_owner: call.prevOwner,
+                owner: call.owner,
+                threshold: call._threshold,
+            })
+        }
+        ISafe::swapOwnerCall::SELECTOR => {
+            let call = ISafe::swapOwnerCall::abi_decode(data).ok()?;
+            Some(AdminOperation::SwapOwner {
+                prev_owner: call.prevOwner,
+                old_owner: call.oldOwner,
+                new_owner: call.newOwner,
+            })
+        }
+        ISafe::changeThresholdCall::SELECTOR => {
+            let call = ISafe::changeThresholdCall::abi_decode(data).ok()?;
+            Some(AdminOperation::ChangeThreshold {
+                threshold: call._threshold,
+            })
+        }
+        ISafe::setGuardCall::SELECTOR => {
+            let call = ISafe::setGuardCall::abi_decode(data).ok()?;
+            Some(AdminOperation::SetGuard { guard: call.guard })
+        }
+        enableModuleCall::SELECTOR => {
+            let call = enableModuleCall::abi_decode(data).ok()?;
+            Some(AdminOperation::EnableModule {
+                module: call.module,
+            })
+        }
+        disableModuleCall::SELECTOR => {
+            let call = disableModuleCall::abi_decode(data).ok()?;
+            Some(AdminOperation::DisableModule {
+                prev_module: call.prevModule,
+                module: call.module,
+            })
+        }
+        setFallbackHandlerCall::SELECTOR => {
+            let call = setFallbackHandlerCall::abi_decode(data).ok()?;
+            Some(AdminOperation::SetFallbackHandler {
+                handler: call.handler,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safe_contract::MultiSendBuilder;
+    use alloy_primitives::{Bytes, U256};
+
+    const SAFE: Address = Address::repeat_byte(0x5a);
+    const MULTISEND: Address = Address::repeat_byte(0x3d);
+
+    fn tx(operation: Operation, to: Address, data: Vec<u8>) -> SafeTransaction {
+        SafeTransaction {
+            to,
+            value: U256::ZERO,
+            data: data.into(),
+            operation,
+            safe_tx_gas: U256::ZERO,
+            base_gas: U256::ZERO,
+            gas_price: U256::ZERO,
+            gas_token: Address::ZERO,
+            refund_receiver: Address::ZERO,
+            nonce: U256::ZERO,
+        }
+    }
+
+    fn change_threshold() -> Vec<u8> {
+        ISafe::changeThresholdCall {
+            _threshold: U256::from(1),
+        }
+        .abi_encode()
+    }
+
+    fn batch(calls: Vec<(Operation, Address, Vec<u8>)>) -> SafeTransaction {
+        MultiSendBuilder::new(
+            MULTISEND,
+            calls
+                .into_iter()
+                .map(|(op, to, data)| (op, to, U256::ZERO, Bytes::from(data))),
+        )
+        .build()
+    }
+
+    #[test]
+    fn self_call_threshold_change_is_flagged() {
+        let op = decode(
+            SAFE,
+            Some(MULTISEND),
+            &tx(Operation::Call, SAFE, change_threshold()),
+        );
+        assert_eq!(
+            op,
+            Some(AdminOperation::ChangeThreshold {
+                threshold: U256::from(1)
+            })
+        );
+    }
+
+    #[test]
+    fn plain_calls_elsewhere_are_not_admin() {
+        let to = Address::repeat_byte(0x11);
+        assert_eq!(
+            decode(
+                SAFE,
+                Some(MULTISEND),
+                &tx(Operation::Call, to, change_threshold())
+            ),
+            None
+        );
+        let transfer = batch(vec![(Operation::Call, to, Vec::new())]);
+        assert_eq!(decode(SAFE, Some(MULTISEND), &transfer), None);
+    }
+
+    #[test]
+    fn delegatecall_to_unknown_target_is_flagged() {
+        let target = Address::repeat_byte(0x66);
+        let op = decode(
+            SAFE,
+            Some(MULTISEND),
+            &tx(Operation::DelegateCall, target, change_threshold()),
+        );
+        assert_eq!(op, Some(AdminOperation::DelegateCall { target }));
+
+        // Without a configured MultiSend even a batch is an opaque delegatecall
+        let transfer = batch(vec![(
+            Operation::Call,
+            Address::repeat_byte(0x11),
+            Vec::new(),
+        )]);
+        assert_eq!(
+            decode(SAFE, None, &transfer),
+            Some(AdminOperation::DelegateCall { target: MULTISEND })
+        );
+    }
+
+    #[test]
+    fn multisend_wrapped_self_call_is_flagged() {
+        let wrapped = batch(vec![
+            (Operation::Call, Address::repeat_byte(0x11), Vec::new()),
+            (Operation::Call, SAFE, change_threshold()),
+        ]);
+        assert_eq!(
+            decode(SAFE, Some(MULTISEND), &wrapped),
+            Some(AdminOperation::ChangeThreshold {
+                threshold: U256::from(1)
+            })
+        );
+
+        // A zero `to` inside a batch is the Safe itself
+        let zero_to = batch(vec![(Operation::Call, Address::ZERO, change_threshold())]);
+        assert!(decode(SAFE, Some(MULTISEND), &zero_to).is_some());
+    }
+
+    #[test]
+    fn nested_multisend_is_decoded_recursively() {
+        let inner = batch(vec![(Operation::Call, SAFE, change_threshold())]);
+        let outer = batch(vec![(
+            Operation::DelegateCall,
+            MULTISEND,
+            inner.data.to_vec(),
+        )]);
+        assert!(matches!(
+            decode(SAFE, Some(MULTISEND), &outer),
+            Some(AdminOperation::ChangeThreshold { .. })
+        ));
+
+        let target = Address::repeat_byte(0x66);
+        let smuggled = batch(vec![(Operation::DelegateCall, target, Vec::new())]);
+        assert_eq!(
+            decode(SAFE, Some(MULTISEND), &smuggled),
+            Some(AdminOperation::DelegateCall { target })
+        );
+    }
+
+    #[test]
+    fn module_and_fallback_changes_are_flagged() {
+        let module = Address::repeat_byte(0x77);
+        let cases = [
+            (
+                enableModuleCall { module }.abi_encode(),
+                AdminOperation::EnableModule { module },
+            ),
+            (
+                disableModuleCall {
+                    prevModule: Address::with_last_byte(1),
+                    module,
+                }
+                .abi_encode(),
+                AdminOperation::DisableModule {
+                    prev_module: Address::with_last_byte(1),
+                    module,
+                },
+            ),
+            (
+                setFallbackHandlerCall { handler: module }.abi_encode(),
+                AdminOperation::SetFallbackHandler { handler: module },
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(
+                decode(SAFE, Some(MULTISEND), &tx(Operation::Call, SAFE, data)),
+                Some(expected)
+            );
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+use shared::{types::SignerType, SafeWalletError};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::safe_contract::SafeTransaction;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentVerdict {
+    pub agent: String,
+    pub approved: bool,
+    pub risk_score: f64,
+    pub reason: String,
+    #[serde(default)]
+    pub reason_codes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiAnalysis {
+    pub approved: bool,
+    pub aggregate_risk: f64,
+    pub per_agent: Vec<AgentVerdict>,
+    pub analyzed_at: String,
+}
+
+// Maps the role used in API paths to the agent name stored on verdicts
+pub fn agent_name_for_role(role: &str) -> Option<&'static str> {
+    match role {
+        "cfo" => Some("cfo_agent"),
+        "security" => Some("security_agent"),
+        "analyst" => Some("analyst_agent"),
+        _ => None,
+    }
+}
+
+impl AiAnalysis {
+    // `approved` as decided by the agents' decision policy
+    pub fn from_decision(approved: bool, per_agent: Vec<AgentVerdict>) -> Self {
+        let aggregate_risk = per_agent.iter().map(|v| v.risk_score).fold(0.0, f64::max);
+
+        Self {
+            approved,
+            aggregate_risk,
+            per_agent,
+            analyzed_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn recommendation(&self) -> &'static str {
+        if self.approved {
+            "Safe to execute"
+        } else {
+            "Review required before signing"
+        }
+    }
+}
+
+// Request body of the ai-agents service's TransactionData
+#[derive(Debug, Serialize)]
+struct AgentRequest {
+    safe: String, // Budgets are tracked per Safe
+    to: String,
+    value: String,
+    data: String,
+    nonce: u64,
+    existing_signers: Vec<SignerType>, // Lets the decision policy trust prior signers
+}
+
+// The ai-agents service's per-agent AnalysisResult
+#[derive(Debug, Deserialize)]
+struct AgentResult {
+    agent: String,
+    approved: bool,
+    risk_score: f64,
+    reasons: Vec<String>,
+    #[serde(default)]
+    reason_codes: Vec<String>,
+}
+
+// The ai-agents service's DecisionResult, reduced to what the orchestrator keeps
+#[derive(Debug, Deserialize)]
+struct DecisionResponse {
+    approved: bool,
+    results: Vec<AgentResult>,
+}
+
+// Verdicts are stored under the orchestrator's agent names, which the rejection
+// lookup by role depends on
+fn agent_key(name: &str) -> String {
+    match name {
+        "CFO Agent" => "cfo_agent".to_string(),
+        "Security Agent" => "security_agent".to_string(),
+        "Onchain Analyst" => "analyst_agent".to_string(),
+        other => other.to_lowercase().replace(' ', "_"),
+    }
+}
+
+// Default upper bound on a consensus round trip (AI_TIMEOUT_SECS); the agents
+// apply their own per-agent deadline
+pub const DEFAULT_AI_AGENTS_TIMEOUT: Duration = Duration::from_secs(30);
+
+// What proposal creation does when the consensus call fails or times out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiFailurePolicy {
+    Hold,   // Create the proposal but keep it Pending until a later ai-analyze call
+    Open,   // Collect signatures anyway, flagged as not reviewed
+    Closed, // Refuse to create the proposal (503)
+}
+
+impl AiFailurePolicy {
+    // AI_FAILURE_POLICY=hold|open|closed, hold by default
+    pub fn from_env() -> Self {
+        match std::env::var("AI_FAILURE_POLICY").as_deref() {
+            Ok("open") => Self::Open,
+            Ok("closed") => Self::Closed,
+            Ok("hold") | Err(_) => Self::Hold,
+            Ok(other) => {
+                warn!("Unknown AI_FAILURE_POLICY '{}', using hold", other);
+                Self::Hold
+            }
+        }
+    }
+}
+
+fn agent_error(context: &str, e: reqwest::Error) -> SafeWalletError {
+    let cause = if e.is_timeout() {
+        "timed out".to_string()
+    } else if e.is_connect() {
+        format!("unreachable ({})", e)
+    } else {
+        e.to_string()
+    };
+    SafeWalletError::AgentError(format!("{}: {}", context, cause))
+}
+
+pub struct AiAgentsClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl AiAgentsClient {
+    pub fn new(base_url: String, timeout: Duration) -> Result<Self, reqwest::Error> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(timeout).build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    // Runs the ai-agents decision over the stored transaction. Decoding of the
+    // calldata happens on the agents' side.
+    pub async fn analyze_transaction(
+        &self,
+        safe: Address,
+        tx: &SafeTransaction,
+        existing_signers: Vec<SignerType>,
+    ) -> shared::Result<AiAnalysis> {
+        let request = AgentRequest {
+            safe: safe.to_string(),
+            to: tx.to.to_string(),
+            value: tx.value.to_string(),
+            data: format!("0x{}", hex::encode(&tx.data)),
+            nonce: tx.nonce.try_into().unwrap_or(u64::MAX),
+            existing_signers,
+        };
+
+        let response: DecisionResponse = self
+            .client
+            .post(format!("{}/api/v1/decision", self.base_url))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| agent_error("ai-agents request failed", e))?
+            .error_for_status()
+            .map_err(|e| agent_error("ai-agents returned an error", e))?
+            .json()
+            .await
+            .map_err(|e| agent_error("Malformed ai-agents response", e))?;
+
+        if response.results.is_empty() {
+            return Err(SafeWalletError::AgentError(
+                "ai-agents returned no verdicts".to_string(),
+            ));
+        }
+
+        Ok(AiAnalysis::from_decision(
+            response.approved,
+            response
+                .results
+                .into_iter()
+                .map(|r| AgentVerdict {
+                    agent: agent_key(&r.agent),
+                    approved: r.approved,
+                    risk_score: r.risk_score,
+                    reason: r.reasons.join("; "),
+                    reason_codes: r.reason_codes,
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safe_contract::Operation;
+    use alloy_primitives::{Bytes, U256};
+
+    #[tokio::test]
+    async fn configured_timeout_bounds_the_request() {
+        // Accepts connections and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        let client = AiAgentsClient::new(url, Duration::from_millis(200)).unwrap();
+        let tx = SafeTransaction {
+            to: Address::repeat_byte(0x11),
+            value: U256::from(1_000u64),
+            data: Bytes::new(),
+            operation: Operation::Call,
+            safe_tx_gas: U256::ZERO,
+            base_gas: U256::ZERO,
+            gas_price: U256::ZERO,
+            gas_token: Address::ZERO,
+            refund_receiver: Address::ZERO,
+            nonce: U256::ZERO,
+        };
+        let started = std::time::Instant::now();
+
+        let err = client
+            .analyze_transaction(Address::repeat_byte(0x5a), &tx, Vec::new())
+            .await
+            .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+}
@@ -0,0 +1,190 @@
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tracing::warn;
+
+// Keys accepted on /api/v1/*, via `X-API-Key` or `Authorization: Bearer`. Browsers
+// can't set headers on a WebSocket handshake, so upgrades may send `?api_key=` instead.
+// None means authentication is off (API_AUTH_DISABLED=true, or no API_KEYS in demo mode).
+#[derive(Debug, Clone)]
+pub struct ApiKeys {
+    keys: Option<Arc<HashSet<String>>>,
+}
+
+impl ApiKeys {
+    // API_KEYS is comma-separated; API_AUTH_DISABLED=true turns the check off for local dev
+    pub fn from_env() -> Self {
+        if std::env::var("API_AUTH_DISABLED")
+            .map(|v| v == "true")
+            .unwrap_or(false)
+        {
+            return Self { keys: None };
+        }
+
+        Self::new(
+            std::env::var("API_KEYS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    // No keys means authentication is off
+    pub fn new(keys: HashSet<String>) -> Self {
+        Self {
+            keys: (!keys.is_empty()).then(|| Arc::new(keys)),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    // Admin endpoints already use the Authorization header for ADMIN_API_TOKEN,
+    // so X-API-Key is checked first and is what admin callers should send
+    fn accepts(&self, request: &Request) -> bool {
+        let Some(keys) = &self.keys else {
+            return true;
+        };
+        let headers = request.headers();
+        let api_key = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        // Only upgrades, so keys don't end up in the URLs of ordinary requests
+        let query_key = is_websocket_upgrade(headers)
+            .then(|| Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok())
+            .flatten()
+            .and_then(|Query(mut query)| query.remove("api_key"));
+        let accepted = [api_key, bearer, query_key.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|key| keys.contains(key));
+        accepted
+    }
+}
+
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"))
+}
+
+pub async fn require_api_key(
+    State(keys): State<ApiKeys>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !request.uri().path().starts_with("/api/v1/") || keys.accepts(&request) {
+        return next.run(request).await;
+    }
+
+    warn!(
+        "Rejected unauthenticated {} {}",
+        request.method(),
+        request.uri().path()
+    );
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(serde_json::json!({ "error": "Missing or invalid API key" })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(keys: ApiKeys) -> Router {
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/api/v1/safe/info", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(keys, require_api_key))
+    }
+
+    fn keys(keys: &[&str]) -> ApiKeys {
+        ApiKeys {
+            keys: Some(Arc::new(keys.iter().map(|k| k.to_string()).collect())),
+        }
+    }
+
+    async fn status(app: Router, path: &str, headers: &[(&str, &str)]) -> StatusCode {
+        let mut request = Request::builder().uri(path);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn api_routes_need_a_configured_key() {
+        let app = app(keys(&["k1", "k2"]));
+
+        assert_eq!(
+            status(app.clone(), "/api/v1/safe/info", &[]).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(app.clone(), "/api/v1/safe/info", &[("x-api-key", "nope")]).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(app.clone(), "/api/v1/safe/info", &[("x-api-key", "k2")]).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app, "/api/v1/safe/info", &[("authorization", "Bearer k1")]).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn websocket_upgrades_may_pass_the_key_in_the_query() {
+        let app = app(keys(&["k1"]));
+        let upgrade = [("connection", "upgrade"), ("upgrade", "websocket")];
+
+        assert_eq!(
+            status(app.clone(), "/api/v1/safe/info?api_key=k1", &upgrade).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app.clone(), "/api/v1/safe/info?api_key=nope", &upgrade).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // Plain requests still need a header
+        assert_eq!(
+            status(app, "/api/v1/safe/info?api_key=k1", &[]).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn health_and_disabled_auth_are_open() {
+        assert_eq!(
+            status(app(keys(&["k1"])), "/health", &[]).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app(ApiKeys { keys: None }), "/api/v1/safe/info", &[]).await,
+            StatusCode::OK
+        );
+    }
+}
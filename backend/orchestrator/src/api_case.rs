@@ -0,0 +1,174 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{Map, Value};
+use tracing::warn;
+
+// Same as axum's default extractor limit, so renaming never buffers more
+// than the handler itself would accept
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+// Field naming for JSON bodies. Handlers always speak snake_case; this layer
+// renames keys on the way in and out so frontends can use either convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiCase {
+    Snake,
+    Camel,
+}
+
+impl ApiCase {
+    // API_CASE=camel|snake, snake by default
+    pub fn from_env() -> Self {
+        match std::env::var("API_CASE").as_deref() {
+            Ok("camel") => Self::Camel,
+            Ok("snake") | Err(_) => Self::Snake,
+            Ok(other) => {
+                warn!("Unknown API_CASE '{}', using snake", other);
+                Self::Snake
+            }
+        }
+    }
+}
+
+// Requests are always accepted in either case; responses follow the configured one
+pub async fn convert_case(State(case): State<ApiCase>, request: Request, next: Next) -> Response {
+    let (parts, body) = request.into_parts();
+    let body = if is_json(&parts.headers) {
+        match rename_body(body, to_snake_case).await {
+            Ok(body) => body,
+            Err(response) => return response,
+        }
+    } else {
+        body
+    };
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+    if case == ApiCase::Snake || !is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    match rename_body(body, to_camel_case).await {
+        Ok(body) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, body)
+        }
+        Err(response) => response,
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+// Bodies that aren't valid JSON pass through untouched so the handler's own
+// extractor reports the error
+async fn rename_body(body: Body, rename: fn(&str) -> String) -> Result<Body, Response> {
+    let bytes = to_bytes(body, MAX_BODY_BYTES).await.map_err(|e| {
+        warn!("Failed to read body for case conversion: {}", e);
+        let status = if e.into_inner().is::<http_body_util::LengthLimitError>() {
+            StatusCode::PAYLOAD_TOO_LARGE
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        Response::builder()
+            .status(status)
+            .body(Body::empty())
+            .unwrap_or_default()
+    })?;
+
+    match serde_json::from_slice::<Value>(&bytes) {
+        Ok(value) => Ok(Body::from(rename_keys(value, rename).to_string())),
+        Err(_) => Ok(Body::from(bytes)),
+    }
+}
+
+fn rename_keys(value: Value, rename: fn(&str) -> String) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (rename(&k), rename_keys(v, rename)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(|v| rename_keys(v, rename)).collect())
+        }
+        other => other,
+    }
+}
+
+// Only identifier-like keys are renamed; map keys such as addresses ("0xAb..")
+// start with a digit and are left alone
+fn is_identifier(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn to_camel_case(key: &str) -> String {
+    if !is_identifier(key) {
+        return key.to_string();
+    }
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn to_snake_case(key: &str) -> String {
+    if !is_identifier(key) {
+        return key.to_string();
+    }
+    let mut out = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn oversized_body_is_rejected_with_413() {
+        let body = Body::from(vec![b' '; MAX_BODY_BYTES + 1]);
+
+        let response = rename_body(body, to_snake_case).await.unwrap_err();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn keys_are_renamed_within_the_limit() {
+        let body = Body::from(r#"{"txId":"1","signerMap":{"0xAb":{"gasPrice":1}}}"#);
+
+        let renamed = rename_body(body, to_snake_case).await.unwrap();
+        let bytes = to_bytes(renamed, MAX_BODY_BYTES).await.unwrap();
+
+        assert_eq!(
+            serde_json::from_slice::<Value>(&bytes).unwrap(),
+            serde_json::json!({"tx_id": "1", "signer_map": {"0xAb": {"gas_price": 1}}})
+        );
+    }
+}
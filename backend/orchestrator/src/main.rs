@@ -1,22 +1,67 @@
+mod admin_ops;
+mod ai_analysis;
+mod api_auth;
+mod api_case;
+mod notifications;
+mod proposal_auth;
+mod rpc_pool;
 mod safe_contract;
 mod safe_contract_abi;
+mod safe_registry;
+mod store;
+#[cfg(test)]
+mod tests;
+mod tx_events;
 
-use alloy::primitives::{Address, Bytes, U256};
+use ai_analysis::{AiAgentsClient, AiAnalysis, AiFailurePolicy, DEFAULT_AI_AGENTS_TIMEOUT};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy_sol_types::SolCall;
+use api_auth::ApiKeys;
+use api_case::ApiCase;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::{ws::WebSocketUpgrade, FromRequestParts, Path, Query, State},
+    http::{request::Parts, HeaderMap, StatusCode},
+    response::{Json, Response},
     routing::{get, post},
     Router,
 };
-use safe_contract::{SafeTransaction, Signature};
-use safe_contract_abi::SafeExecutor;
+use chrono::{DateTime, Utc};
+use notifications::{
+    ExecutionResultNotification, NotificationPreferences, Notifier, ProposalNotification,
+};
+use proposal_auth::ProposalClaim;
+use rpc_pool::RpcPool;
+use safe_contract::{
+    decode_multisend, validate_signer_set, EncodedSignatures, MultiSendBuilder, MultiSendCall,
+    Operation, SafeTransaction, Signature,
+};
+use safe_contract_abi::{
+    ExecutionEvent, ExecutionReceipt, FeeConfig, SafeBalances, SafeExecutor, SimulationResult,
+    IERC20,
+};
+use safe_registry::SafeRegistry;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, str::FromStr, sync::Arc};
-use tokio::sync::RwLock;
-use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use shared::{
+    constants::{KAIA_TESTNET_CHAIN_ID, REQUIRED_SIGNATURES, SIGNATURE_COLLECTION_TIMEOUT_SECS},
+    deployment::DeploymentMode,
+    observability,
+    policy::{PolicyDecision, PolicyEngine, PolicyRules},
+    signature::{normalize_v, recover_signer, signed_digest, VScheme},
+    tokens::TokenRegistry,
+    types::{InvalidTransition, ProposalMetadata, SignerType, TransactionStatus},
+    utils, SafeWalletError,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+};
+use store::Store;
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tx_events::{EventingStore, TxSnapshot};
 
 #[derive(Clone)]
 struct SignerAddresses {
@@ -27,28 +72,332 @@ struct SignerAddresses {
     ai_analyst: Address,
 }
 
+impl SignerAddresses {
+    fn all(&self) -> [Address; 5] {
+        [
+            self.human1,
+            self.human2,
+            self.ai_cfo,
+            self.ai_security,
+            self.ai_analyst,
+        ]
+    }
+
+    fn humans(&self) -> [Address; 2] {
+        [self.human1, self.human2]
+    }
+
+    fn kind(&self, addr: Address) -> Option<SignerType> {
+        if self.humans().contains(&addr) {
+            Some(SignerType::Human)
+        } else if addr == self.ai_cfo {
+            Some(SignerType::AiCfo)
+        } else if addr == self.ai_security {
+            Some(SignerType::AiSecurity)
+        } else if addr == self.ai_analyst {
+            Some(SignerType::AiAnalyst)
+        } else {
+            None
+        }
+    }
+
+    fn metric_label(&self, addr: Address) -> &'static str {
+        match self.kind(addr) {
+            Some(SignerType::Human) => "human",
+            Some(SignerType::AiCfo) => "ai_cfo",
+            Some(SignerType::AiSecurity) => "ai_security",
+            Some(SignerType::AiAnalyst) => "ai_analyst",
+            None => "other",
+        }
+    }
+
+    // Who has vouched for the proposal so far: its signers, plus a proposer whose
+    // proposal signature was verified
+    fn collected(&self, tx_state: &TransactionState) -> Vec<SignerType> {
+        let mut kinds: Vec<SignerType> = Vec::new();
+        let proposer = tx_state
+            .metadata
+            .proposer
+            .filter(|_| tx_state.metadata.proposer_verified);
+        for addr in tx_state
+            .signatures
+            .iter()
+            .filter_map(|sig| sig.verified_signer().ok())
+            .chain(proposer)
+        {
+            if let Some(kind) = self.kind(addr) {
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+            }
+        }
+        kinds
+    }
+
+    // Signatures are only accepted from configured signers, so anything that
+    // isn't a human is one of the AI agents
+    fn signer_type(&self, addr: Address) -> &'static str {
+        if self.humans().contains(&addr) {
+            "Human"
+        } else {
+            "AI Agent"
+        }
+    }
+}
+
 struct AppState {
     safe_address: Address,
-    transactions: Arc<RwLock<HashMap<String, TransactionState>>>,
+    store: Arc<dyn Store>,
+    tx_events: Arc<EventingStore>, // Same store, for /ws subscriptions
+    // Serializes read-modify-write cycles against the store
+    mutation_lock: Mutex<()>,
     signer_addresses: SignerAddresses,
     safe_executor: Arc<SafeExecutor>,
+    queue_stale_after_secs: i64,
+    execution_grace_secs: i64, // Veto window between reaching the threshold and execution
+    signature_timeout_secs: i64, // Signature collection window before a proposal expires
+    auto_extend: Option<AutoExtendConfig>,
+    tracked_tokens: Vec<Address>, // ERC20s reported by the balances endpoint
+    tokens: Arc<TokenRegistry>,
+    gas_refund: GasRefundConfig,
+    chain_id: u64,
+    verify_hash_onchain: bool,
+    safe_registry: SafeRegistry,
+    notifier: Arc<Notifier>,
+    ai_agents: AiAgentsClient,
+    ai_failure_policy: AiFailurePolicy,
+    require_ai_approval: bool,
+    require_proposer_signature: bool,
+    allow_zero_value_calls: bool,
+    mandatory_review: HashSet<Address>, // Recipients that always need AI approval
+    gas_multiplier: f64,
+    max_gas_price: Option<U256>, // Refuse to execute above this execution gas price (wei)
+    // Last execution gas price reported by /health, so probes don't each hit the RPC
+    health_gas_price: Mutex<Option<(std::time::Instant, Option<U256>)>>,
+    max_proposal_value: Option<U256>, // Sanity ceiling on proposal value (wei)
+    multisend_address: Option<Address>, // Target for batched proposals
+    delegatecall_targets: HashSet<Address>, // MultiSend plus DELEGATECALL_TARGETS
+    admin_token: Option<String>, // Bearer token for /api/v1/admin endpoints that mutate the queue
+    preferred_signature_scheme: SignatureScheme,
+    deployment_mode: DeploymentMode,
 }
 
+// Signing method we steer wallets towards; both are accepted on submission
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum SignatureScheme {
+    #[serde(rename = "eth_signTypedData_v4")]
+    TypedDataV4,
+    #[serde(rename = "eth_sign")]
+    EthSign,
+}
+
+// Safe-level gas refund settings baked into every proposal at creation
 #[derive(Clone)]
+struct GasRefundConfig {
+    gas_price: U256,
+    gas_token: Address,
+}
+
+// Deadline extension for proposals one signature short of the threshold
+#[derive(Clone, Copy)]
+struct AutoExtendConfig {
+    window_secs: i64,    // Extend when the deadline is this close, by this much
+    max_extensions: u32, // Per proposal; after that it expires as usual
+}
+
+impl AppState {
+    // Earliest moment a ready proposal may be executed, once the grace period has passed
+    fn executable_at(&self, tx_state: &TransactionState) -> Option<DateTime<Utc>> {
+        tx_state
+            .ready_at
+            .map(|ready| ready + chrono::Duration::seconds(self.execution_grace_secs))
+    }
+
+    // Admin operations need every human signer in addition to the threshold
+    fn missing_human_approvals(&self, tx_state: &TransactionState) -> Vec<Address> {
+        if tx_state.metadata.admin_operation.is_none() {
+            return Vec::new();
+        }
+        self.signer_addresses
+            .humans()
+            .into_iter()
+            .filter(|human| {
+                !tx_state
+                    .signatures
+                    .iter()
+                    .any(|s| s.verified_signer() == Ok(*human))
+            })
+            .collect()
+    }
+
+    // The threshold, plus every human signer for admin operations
+    fn policy_decision(&self, tx_state: &TransactionState) -> PolicyDecision {
+        let rules = PolicyRules {
+            min_signatures: REQUIRED_SIGNATURES as usize,
+            min_human_signatures: if tx_state.metadata.admin_operation.is_some() {
+                self.signer_addresses.humans().len()
+            } else {
+                0
+            },
+            ..PolicyRules::default()
+        };
+        let signers: Vec<SignerType> = tx_state
+            .signatures
+            .iter()
+            .filter_map(|s| self.signer_addresses.kind(s.verified_signer().ok()?))
+            .collect();
+        PolicyEngine::new(rules).evaluate(tx_state.transaction.value, &signers)
+    }
+
+    fn can_execute(&self, tx_state: &TransactionState) -> bool {
+        self.policy_decision(tx_state).is_executable()
+    }
+}
+
+impl GasRefundConfig {
+    fn enabled(&self) -> bool {
+        !self.gas_price.is_zero() || self.gas_token != Address::ZERO
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct TransactionState {
     transaction: SafeTransaction,
     signatures: Vec<Signature>,
     status: TransactionStatus,
     tx_hash: String, // Hash for signing
+    ai_analysis: Option<AiAnalysis>,
+    created_at: DateTime<Utc>,
+    ready_at: Option<DateTime<Utc>>, // When the signature threshold was reached
+    invalidation_reason: Option<String>,
+    executed_tx_hash: Option<B256>, // On-chain hash of the execTransaction call
+    metadata: ProposalMetadata,
+    #[serde(default)]
+    deadline: Option<DateTime<Utc>>, // Signatures must be collected by then
+    #[serde(default)]
+    deadline_extensions: u32,
+    #[serde(default)]
+    audit_log: Vec<AuditEntry>,
+}
+
+impl TransactionState {
+    // Deadline that still applies, i.e. while signatures are being collected
+    fn open_deadline(&self) -> Option<DateTime<Utc>> {
+        let collecting = matches!(
+            self.status,
+            TransactionStatus::Pending | TransactionStatus::CollectingSignatures
+        );
+        self.deadline.filter(|_| collecting)
+    }
+
+    // Marks the proposal Failed once its deadline has passed; true if it changed
+    fn expire_if_overdue(&mut self, now: DateTime<Utc>) -> bool {
+        let from = self.status;
+        let Some(deadline) = self.open_deadline().filter(|d| *d <= now) else {
+            return false;
+        };
+        let reason = format!(
+            "Signature deadline {} passed with {}/{} signatures",
+            deadline,
+            self.signatures.len(),
+            REQUIRED_SIGNATURES
+        );
+        if self.transition(from, TransactionStatus::Failed).is_err() {
+            return false;
+        }
+        self.audit_log.push(AuditEntry {
+            at: now,
+            event: "expired".to_string(),
+            detail: reason.clone(),
+        });
+        self.invalidation_reason = Some(reason);
+        true
+    }
+
+    // Every status change after creation goes through here or Store::transition_status.
+    // `from_expected` is the status the caller based its decision on.
+    fn transition(
+        &mut self,
+        from_expected: TransactionStatus,
+        to: TransactionStatus,
+    ) -> Result<(), InvalidTransition> {
+        self.status = self.status.transition(from_expected, to)?;
+        Ok(())
+    }
+
+    // Records an on-chain ExecutionSuccess/ExecutionFailure for this proposal;
+    // false if it was already recorded
+    fn apply_execution_event(&mut self, event: &ExecutionEvent) -> Result<bool, InvalidTransition> {
+        let from = self.status;
+        let outcome = if event.success {
+            TransactionStatus::Executed
+        } else {
+            TransactionStatus::Failed
+        };
+        if from == outcome {
+            return Ok(false);
+        }
+        self.transition(from, outcome)?;
+        self.executed_tx_hash = event.transaction_hash;
+        Ok(true)
+    }
+
+    // Settles a proposal a restart left Executing. Its execution event decides if
+    // there is one; otherwise a Safe nonce still at the proposal's means nothing
+    // was executed and it can be retried, and one past it means the outcome is lost.
+    fn recover_interrupted_execution(
+        &mut self,
+        onchain_nonce: U256,
+        events: &[ExecutionEvent],
+    ) -> Result<bool, InvalidTransition> {
+        if self.status != TransactionStatus::Executing {
+            return Ok(false);
+        }
+        if let Some(event) = events
+            .iter()
+            .find(|e| e.safe_tx_hash.to_string() == self.tx_hash)
+        {
+            return self.apply_execution_event(event);
+        }
+
+        let (to, detail) = if self.transaction.nonce >= onchain_nonce {
+            (
+                TransactionStatus::ReadyToExecute,
+                "Restarted mid-execution; the Safe nonce hasn't moved, so it can be executed again"
+                    .to_string(),
+            )
+        } else {
+            (
+                TransactionStatus::Failed,
+                format!(
+                    "Restarted mid-execution; the Safe nonce advanced to {} without an execution event for this proposal",
+                    onchain_nonce
+                ),
+            )
+        };
+        self.transition(TransactionStatus::Executing, to)?;
+        if to == TransactionStatus::Failed {
+            self.invalidation_reason = Some(detail.clone());
+        }
+        self.audit_log.push(AuditEntry {
+            at: Utc::now(),
+            event: "execution_recovered".to_string(),
+            detail,
+        });
+        Ok(true)
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.status == TransactionStatus::Failed && self.deadline.is_some_and(|d| d <= now)
+    }
 }
 
+// Changes made to a proposal by the orchestrator itself rather than a caller
 #[derive(Debug, Clone, Serialize, Deserialize)]
-enum TransactionStatus {
-    Pending,
-    CollectingSignatures,
-    ReadyToExecute,
-    Executed,
-    Failed,
+struct AuditEntry {
+    at: DateTime<Utc>,
+    event: String,
+    detail: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -56,6 +405,197 @@ struct CreateTransactionRequest {
     to: String,
     value: String,
     data: Option<String>,
+    reference: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    proposer: Option<Address>,
+    proposer_signature: Option<String>, // EIP-712 Proposal signature by `proposer`
+    proposed_at: Option<i64>,           // Unix timestamp included in the signed Proposal
+    #[serde(default)]
+    allow_large_value: bool, // Deliberately exceed MAX_PROPOSAL_VALUE
+    #[serde(default)]
+    signature_timeout_secs: Option<i64>, // Overrides SIGNATURE_TIMEOUT_SECS for this proposal
+    #[serde(default)]
+    operation: Option<Operation>, // 0 = call (default), 1 = delegatecall
+    #[serde(flatten)]
+    gas: GasParams,
+}
+
+// Safe refund parameters; unset fields fall back to SAFE_TX_GAS_PRICE /
+// SAFE_TX_GAS_TOKEN and zero gas limits. All are part of the signed hash.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GasParams {
+    safe_tx_gas: Option<String>,
+    base_gas: Option<String>,
+    gas_price: Option<String>,  // Refund price per gas, in gas_token units
+    gas_token: Option<Address>, // Zero address = native KAIA
+    refund_receiver: Option<Address>, // Defaults to the relayer when a refund is paid
+}
+
+// Token transfer proposal; the calldata is encoded here rather than by the client
+#[derive(Debug, Deserialize)]
+struct Erc20TransferRequest {
+    token: String,
+    recipient: String,
+    amount: String, // In the token's base units
+    reference: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    proposer: Option<Address>,
+    proposer_signature: Option<String>, // Signs the encoded call: to = token, value = 0
+    proposed_at: Option<i64>,
+    #[serde(default)]
+    signature_timeout_secs: Option<i64>,
+}
+
+impl Erc20TransferRequest {
+    // The Safe calls token.transfer(recipient, amount) with no native value
+    fn into_create_request(self) -> Result<CreateTransactionRequest, ApiError> {
+        let token = Address::from_str(&self.token)
+            .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid token address"))?;
+        let recipient = Address::from_str(&self.recipient)
+            .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid recipient address"))?;
+        if recipient == Address::ZERO {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "Recipient is the zero address",
+            ));
+        }
+        let amount = U256::from_str(&self.amount)
+            .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid amount"))?;
+        if amount.is_zero() {
+            return Err(api_error(StatusCode::BAD_REQUEST, "Amount is zero"));
+        }
+
+        let data = IERC20::transferCall {
+            to: recipient,
+            amount,
+        }
+        .abi_encode();
+        Ok(CreateTransactionRequest {
+            to: token.to_string(),
+            value: "0".to_string(),
+            data: Some(hex::encode(data)),
+            reference: self.reference,
+            tags: self.tags,
+            proposer: self.proposer,
+            proposer_signature: self.proposer_signature,
+            proposed_at: self.proposed_at,
+            allow_large_value: false,
+            signature_timeout_secs: self.signature_timeout_secs,
+            operation: None,
+            gas: GasParams::default(),
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ImportCsvQuery {
+    #[serde(default)]
+    mode: ImportMode,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ImportMode {
+    #[default]
+    Individual, // One proposal per row
+    Batch, // One MultiSend proposal for all rows
+}
+
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    to: String,
+    value: String,
+    token: Option<String>, // Empty for native KAIA
+    reference: Option<String>,
+}
+
+impl CsvRow {
+    // The call this row pays out with: a plain transfer, or ERC20 transfer() on the token
+    fn into_call(
+        self,
+        max_native_value: Option<U256>,
+    ) -> Result<(MultiSendCall, Option<String>), String> {
+        let to = Address::from_str(&self.to)
+            .map_err(|_| format!("Invalid recipient address '{}'", self.to))?;
+        if to == Address::ZERO {
+            return Err("Recipient is the zero address".to_string());
+        }
+        let value =
+            U256::from_str(&self.value).map_err(|_| format!("Invalid value '{}'", self.value))?;
+        if value.is_zero() {
+            return Err("Value is zero".to_string());
+        }
+
+        let call = match self.token.filter(|t| !t.is_empty()) {
+            None => {
+                if max_native_value.is_some_and(|ceiling| value > ceiling) {
+                    return Err(format!("Value {} wei exceeds MAX_PROPOSAL_VALUE", value));
+                }
+                MultiSendCall {
+                    operation: Operation::Call,
+                    to,
+                    value,
+                    data: Bytes::new(),
+                }
+            }
+            Some(token) => MultiSendCall {
+                operation: Operation::Call,
+                to: Address::from_str(&token)
+                    .map_err(|_| format!("Invalid token address '{}'", token))?,
+                value: U256::ZERO,
+                data: IERC20::transferCall { to, amount: value }
+                    .abi_encode()
+                    .into(),
+            },
+        };
+        Ok((call, self.reference.filter(|r| !r.is_empty())))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ImportRowResult {
+    row: usize, // 1-based, not counting the header
+    tx_id: Option<String>,
+    safe_tx_hash: Option<String>,
+    error: Option<String>,
+}
+
+impl ImportRowResult {
+    fn created(row: usize, created: &CreateTransactionResponse) -> Self {
+        Self {
+            row,
+            tx_id: Some(created.tx_id.clone()),
+            safe_tx_hash: Some(created.safe_tx_hash.clone()),
+            error: None,
+        }
+    }
+
+    fn failed(row: usize, error: String) -> Self {
+        Self {
+            row,
+            tx_id: None,
+            safe_tx_hash: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ImportCsvResponse {
+    mode: ImportMode,
+    created: usize,
+    failed: usize,
+    results: Vec<ImportRowResult>,
+    batch: Option<CreateTransactionResponse>, // The MultiSend proposal in batch mode
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum HashSource {
+    Contract, // Safe.getTransactionHash
+    Local,    // Computed locally; the contract was skipped or unreachable
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,8 +603,16 @@ struct CreateTransactionResponse {
     tx_id: String,
     safe_tx_hash: String,
     sign_message: String,
+    nonce: U256, // Safe nonce the proposal was built against
     required_signatures: u8,
     current_signatures: usize,
+    hash_verified_onchain: bool,
+    hash_source: HashSource, // Where safe_tx_hash came from
+    status: TransactionStatus,
+    ai_review_required: bool,
+    preferred_signature_scheme: SignatureScheme,
+    typed_data: Option<serde_json::Value>, // Present when typed-data signing is preferred
+    deduplicated: bool, // An identical in-flight proposal already existed; this is it
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -77,10 +625,54 @@ struct SignTransactionRequest {
 struct TransactionInfoResponse {
     tx_id: String,
     transaction: SafeTransaction,
+    value_kaia: String, // `transaction.value` in KAIA, for display
+    content_hash: B256,
     signatures: Vec<SignatureInfo>,
     status: TransactionStatus,
     ready_to_execute: bool,
     safe_tx_hash: String,
+    ai_analysis: Option<AiAnalysis>,
+    invalidation_reason: Option<String>,
+    deadline: Option<DateTime<Utc>>,
+    deadline_extensions: u32,
+    audit_log: Vec<AuditEntry>,
+    #[serde(flatten)]
+    metadata: ProposalMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionInfoQuery {
+    refresh: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListTransactionsQuery {
+    reference: Option<String>,
+    status: Option<TransactionStatus>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct TransactionListResponse {
+    transactions: Vec<TransactionInfoResponse>,
+    total: usize, // Matching transactions before pagination
+    limit: usize,
+    offset: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueueMetricsQuery {
+    stale_after_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct QueueMetricsResponse {
+    pending_count: usize,
+    stale_after_secs: i64,
+    stale_count: usize,
+    oldest_pending_age_secs: Option<i64>,
+    median_collection_secs: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -90,12 +682,160 @@ struct SignatureInfo {
     signed_at: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct SignatureDetail {
+    position: usize,       // Index in the execTransaction signature blob
+    received_order: usize, // Order in which the orchestrator accepted it
+    signer: String,
+    signer_type: String,
+    signature: String,
+    recovered_signer: Option<String>,
+    v_scheme: Option<VScheme>,
+    low_s: Option<bool>,
+    matches_signer: bool,
+    signed_at: Option<DateTime<Utc>>,
+    signed_hash: Option<B256>, // Safe tx hash the signature was checked against
+    signed_digest: Option<B256>, // What was actually signed (EIP-191 prefixed for eth_sign)
+    matches_current_hash: Option<bool>, // False means it can't pass checkSignatures (GS026)
+}
+
+// Everything needed to replay execTransaction by hand
+#[derive(Debug, Serialize)]
+struct ExecEncoding {
+    tx_id: String,
+    safe_address: Address, // Target of the call
+    safe_tx_hash: String,
+    transaction: SafeTransaction,
+    signer_order: Vec<Address>, // Ascending, as checkSignatures requires
+    signatures: Vec<SignatureDetail>,
+    signatures_blob: Bytes,
+    calldata: Bytes, // ABI-encoded execTransaction(...)
+}
+
+// One-shot compliance record of an executed proposal
+#[derive(Debug, Serialize)]
+struct ExecutionReport {
+    tx_id: String,
+    safe_address: Address,
+    safe_tx_hash: String,
+    content_hash: B256,
+    transaction: SafeTransaction,
+    #[serde(flatten)]
+    metadata: ProposalMetadata,
+    status: TransactionStatus,
+    created_at: DateTime<Utc>,
+    ready_at: Option<DateTime<Utc>>,
+    signatures: Vec<SignatureDetail>,
+    ai_analysis: Option<AiAnalysis>,
+    receipt: Option<ExecutionReceipt>, // None if the chain no longer returns it
+    generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AgentRejection {
+    tx_id: String,
+    safe_tx_hash: String,
+    status: TransactionStatus,
+    risk_score: f64,
+    reason: String,
+    reason_codes: Vec<String>,
+    analyzed_at: String,
+}
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn api_error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (status, Json(serde_json::json!({ "error": message.into() })))
+}
+
+// `tx_id` path segment. Ids are UUIDs, so anything else is rejected with 400
+// before touching the store; a well-formed id that doesn't exist is still 404.
+struct TxId(String);
+
+impl<S: Send + Sync> FromRequestParts<S> for TxId {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(tx_id) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| api_error(StatusCode::BAD_REQUEST, e.body_text()))?;
+        uuid::Uuid::parse_str(&tx_id).map_err(|_| {
+            api_error(
+                StatusCode::BAD_REQUEST,
+                format!("Malformed tx_id '{}'", tx_id),
+            )
+        })?;
+        Ok(Self(tx_id))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ExecuteTransactionResponse {
     tx_hash: String,
     success: bool,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum NonceSource {
+    Local,
+    External, // Executed on-chain without a proposal in this orchestrator
+}
+
+#[derive(Debug, Serialize)]
+struct NonceHistoryEntry {
+    source: NonceSource,
+    tx_id: Option<String>,
+    // On-chain transaction; null for nonces consumed before the event lookback window
+    tx_hash: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NonceHistoryQuery {
+    before: Option<u64>, // Exclusive; defaults to the current on-chain nonce
+    limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReconcileQuery {
+    lookback_blocks: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CancelPendingRequest {
+    up_to_nonce: Option<u64>, // Inclusive; omit to cancel the whole queue
+}
+
+#[derive(Debug, Serialize)]
+struct CancelPendingResponse {
+    cancelled: Vec<String>,
+    skipped_executing: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReconcileResponse {
+    onchain_nonce: String,
+    events_scanned: usize,
+    marked_executed: Vec<String>,
+    marked_failed: Vec<String>,
+    invalidated: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VerifySignatureRequest {
+    hash: String,
+    signature: String,
+    expected_signer: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VerifySignatureResponse {
+    recovered_signer: String,
+    low_s: bool,
+    v_scheme: VScheme,
+    matches_expected: Option<bool>,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::registry()
@@ -116,36 +856,73 @@ async fn main() -> anyhow::Result<()> {
     let rpc_url = std::env::var("KAIROS_RPC_URL")
         .unwrap_or_else(|_| "https://public-en-kairos.node.kaia.io".to_string());
 
-    // Load Safe address from env
-    let safe_address = std::env::var("SAFE_ADDRESS")
+    // RPC_URLS lists endpoints in priority order; KAIROS_RPC_URL is the single-endpoint fallback
+    let rpc_urls: Vec<String> = std::env::var("RPC_URLS")
         .ok()
-        .and_then(|s| Address::from_str(&s).ok())
-        .unwrap_or(Address::ZERO);
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| v.split(',').map(str::to_string).collect())
+        .unwrap_or_else(|| vec![rpc_url]);
+    let rpc_pool = Arc::new(
+        RpcPool::new(&rpc_urls)
+            .expect("Invalid RPC URL configuration")
+            .with_retry(
+                std::env::var("RPC_MAX_ATTEMPTS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(rpc_pool::DEFAULT_RPC_MAX_ATTEMPTS),
+                std::env::var("RPC_RETRY_BASE_DELAY_MS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .map(std::time::Duration::from_millis)
+                    .unwrap_or(rpc_pool::DEFAULT_RPC_RETRY_BASE_DELAY),
+            ),
+    );
+
+    // Load Safe address from env
+    let safe_address = env_address("SAFE_ADDRESS");
 
     // Load signer addresses from env
     let signer_addresses = SignerAddresses {
-        human1: std::env::var("HUMAN1_ADDRESS")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
-            .unwrap_or(Address::ZERO),
-        human2: std::env::var("HUMAN2_ADDRESS")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
-            .unwrap_or(Address::ZERO),
-        ai_cfo: std::env::var("AI_CFO_ADDRESS")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
-            .unwrap_or(Address::ZERO),
-        ai_security: std::env::var("AI_SECURITY_ADDRESS")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
-            .unwrap_or(Address::ZERO),
-        ai_analyst: std::env::var("AI_ANALYST_ADDRESS")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
-            .unwrap_or(Address::ZERO),
+        human1: env_address("HUMAN1_ADDRESS"),
+        human2: env_address("HUMAN2_ADDRESS"),
+        ai_cfo: env_address("AI_CFO_ADDRESS"),
+        ai_security: env_address("AI_SECURITY_ADDRESS"),
+        ai_analyst: env_address("AI_ANALYST_ADDRESS"),
     };
 
+    let deployment_mode = DeploymentMode::from_env();
+
+    // Zero addresses and a missing relayer key only make sense against a demo Safe
+    let mut unsafe_defaults = Vec::new();
+    if safe_address.is_zero() {
+        unsafe_defaults.push("SAFE_ADDRESS must be set".to_string());
+    }
+    for (var, address) in [
+        ("HUMAN1_ADDRESS", signer_addresses.human1),
+        ("HUMAN2_ADDRESS", signer_addresses.human2),
+        ("AI_CFO_ADDRESS", signer_addresses.ai_cfo),
+        ("AI_SECURITY_ADDRESS", signer_addresses.ai_security),
+        ("AI_ANALYST_ADDRESS", signer_addresses.ai_analyst),
+    ] {
+        if address.is_zero() {
+            unsafe_defaults.push(format!("{} must be set", var));
+        }
+    }
+    if !safe_contract_abi::executor_key_configured() {
+        unsafe_defaults.push("EXECUTOR_PRIVATE_KEY must be set".to_string());
+    }
+    let api_keys = ApiKeys::from_env();
+    if !api_keys.enabled() {
+        unsafe_defaults.push("API_KEYS must be set and API_AUTH_DISABLED unset".to_string());
+        warn!("API key authentication is off; /api/v1 is open to anyone who can reach it");
+    }
+    deployment_mode
+        .ensure_production_ready("Orchestrator", &unsafe_defaults)
+        .expect("Production-unsafe configuration");
+    let cors = deployment_mode
+        .cors_layer()
+        .expect("Invalid CORS configuration");
+
     info!("Loaded signer addresses:");
     info!("  Human 1: {}", signer_addresses.human1);
     info!("  Human 2: {}", signer_addresses.human2);
@@ -153,30 +930,263 @@ async fn main() -> anyhow::Result<()> {
     info!("  AI Security: {}", signer_addresses.ai_security);
     info!("  AI Analyst: {}", signer_addresses.ai_analyst);
 
+    // Proposals waiting longer than this for signatures are reported as stale
+    let queue_stale_after_secs = std::env::var("QUEUE_STALE_AFTER_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(900);
+
+    let execution_grace_secs = std::env::var("EXECUTION_GRACE_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|secs: &i64| *secs >= 0)
+        .unwrap_or(0);
+
+    let signature_timeout_secs = std::env::var("SIGNATURE_TIMEOUT_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|secs: &i64| *secs > 0)
+        .unwrap_or(SIGNATURE_COLLECTION_TIMEOUT_SECS as i64);
+
+    let auto_extend = std::env::var("AUTO_EXTEND_NEAR_THRESHOLD")
+        .map(|v| v == "true")
+        .unwrap_or(false)
+        .then(|| AutoExtendConfig {
+            window_secs: std::env::var("AUTO_EXTEND_WINDOW_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|secs: &i64| *secs > 0)
+                .unwrap_or(600),
+            max_extensions: std::env::var("AUTO_EXTEND_MAX_EXTENSIONS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+        });
+
+    let tokens = Arc::new(TokenRegistry::from_env().expect("Invalid KNOWN_TOKENS"));
+
+    // Configured tokens are always reported, plus any extra TRACKED_TOKENS
+    let mut tracked_tokens: Vec<Address> = tokens.configured().to_vec();
+    for token in std::env::var("TRACKED_TOKENS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        let token = Address::from_str(token).expect("Invalid TRACKED_TOKENS address");
+        if !tracked_tokens.contains(&token) {
+            tracked_tokens.push(token);
+        }
+    }
+
+    let gas_refund = GasRefundConfig {
+        gas_price: std::env::var("SAFE_TX_GAS_PRICE")
+            .ok()
+            .and_then(|s| U256::from_str(&s).ok())
+            .unwrap_or(U256::ZERO),
+        gas_token: std::env::var("SAFE_TX_GAS_TOKEN")
+            .ok()
+            .and_then(|s| Address::from_str(&s).ok())
+            .unwrap_or(Address::ZERO),
+    };
+
+    let chain_id =
+        parse_chain_id(std::env::var("CHAIN_ID").ok().as_deref()).expect("Invalid CHAIN_ID");
+
+    // Cross-check locally computed hashes against Safe.getTransactionHash
+    let verify_hash_onchain = std::env::var("VERIFY_HASH_ONCHAIN")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+
     // Initialize Safe executor
     let safe_executor = Arc::new(
-        SafeExecutor::new(&rpc_url, &safe_address.to_string())
+        SafeExecutor::new(rpc_pool.clone(), &safe_address.to_string())
             .await
-            .expect("Failed to initialize Safe executor"),
+            .expect("Failed to initialize Safe executor")
+            .with_fees(FeeConfig {
+                priority_fee_tip: std::env::var("EXEC_PRIORITY_FEE_WEI")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(safe_contract_abi::DEFAULT_PRIORITY_FEE_TIP),
+                max_fee_per_gas: std::env::var("EXEC_MAX_FEE_PER_GAS")
+                    .ok()
+                    .and_then(|s| s.parse().ok()),
+                max_priority_fee_per_gas: std::env::var("EXEC_MAX_PRIORITY_FEE_PER_GAS")
+                    .ok()
+                    .and_then(|s| s.parse().ok()),
+            })
+            .with_signer(
+                safe_contract_abi::executor_signer_from_env()
+                    .expect("Invalid executor key configuration"),
+            ),
     );
 
+    let require_ai_approval = std::env::var("REQUIRE_AI_APPROVAL")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+
+    let require_proposer_signature = std::env::var("REQUIRE_PROPOSER_SIGNATURE")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+
+    // Zero-value proposals are dust unless they carry calldata (e.g. approve)
+    let allow_zero_value_calls = std::env::var("ALLOW_ZERO_VALUE_CALLS")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+
+    let mandatory_review: HashSet<Address> = std::env::var("MANDATORY_REVIEW_RECIPIENTS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|s| Address::from_str(s.trim()).ok())
+        .collect();
+
+    // Headroom applied to execTransaction gas estimates
+    let gas_multiplier = std::env::var("GAS_MULTIPLIER")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1.2);
+
+    let max_gas_price = std::env::var("MAX_GAS_PRICE")
+        .ok()
+        .and_then(|s| U256::from_str(&s).ok());
+
+    let max_proposal_value = std::env::var("MAX_PROPOSAL_VALUE")
+        .ok()
+        .and_then(|s| U256::from_str(&s).ok());
+
+    let preferred_signature_scheme = match std::env::var("PREFERRED_SIGNATURE_SCHEME").as_deref() {
+        Ok("eth_sign") => SignatureScheme::EthSign,
+        _ => SignatureScheme::TypedDataV4,
+    };
+
+    let api_case = ApiCase::from_env();
+
+    // Delegatecall runs foreign code against the Safe's storage, so only these
+    // targets are accepted; anything but a MultiSend batch still needs every human
+    let multisend_address = std::env::var("MULTISEND_ADDRESS")
+        .ok()
+        .and_then(|s| Address::from_str(&s).ok());
+    let delegatecall_targets: HashSet<Address> = std::env::var("DELEGATECALL_TARGETS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| Address::from_str(s).expect("Invalid DELEGATECALL_TARGETS address"))
+        .chain(multisend_address)
+        .collect();
+
+    // Additional Safes to expose alongside the default SAFE_ADDRESS
+    let additional_safes: Vec<Address> = std::env::var("SAFE_ADDRESSES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|s| Address::from_str(s.trim()).ok())
+        .collect();
+
+    let safe_registry = SafeRegistry::new(
+        rpc_pool.clone(),
+        safe_address,
+        safe_executor.clone(),
+        &additional_safes,
+        &signer_addresses.all(),
+        U256::from(REQUIRED_SIGNATURES),
+    )
+    .await
+    .expect("Failed to initialize Safe registry");
+
+    let store = Arc::new(EventingStore::new(
+        store::from_env()
+            .await
+            .expect("Failed to initialize transaction store"),
+    ));
+
     let state = Arc::new(AppState {
         safe_address,
-        transactions: Arc::new(RwLock::new(HashMap::new())),
+        store: store.clone(),
+        tx_events: store,
+        mutation_lock: Mutex::new(()),
         signer_addresses,
         safe_executor,
-    });
+        queue_stale_after_secs,
+        execution_grace_secs,
+        signature_timeout_secs,
+        auto_extend,
+        tracked_tokens,
+        tokens,
+        gas_refund,
+        chain_id,
+        verify_hash_onchain,
+        safe_registry,
+        ai_agents: AiAgentsClient::new(
+            std::env::var("AI_AGENTS_URL").unwrap_or_else(|_| "http://localhost:3002".to_string()),
+            std::env::var("AI_TIMEOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(DEFAULT_AI_AGENTS_TIMEOUT),
+        )
+        .expect("Failed to build ai-agents client"),
+        ai_failure_policy: AiFailurePolicy::from_env(),
+        deployment_mode,
+        notifier: Arc::new(Notifier::new(
+            std::env::var("NOTIFICATION_WEBHOOK_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+            std::env::var("ALERT_WEBHOOK_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+        )),
+        require_ai_approval,
+        require_proposer_signature,
+        allow_zero_value_calls,
+        mandatory_review,
+        gas_multiplier,
+        max_gas_price,
+        health_gas_price: Mutex::new(None),
+        max_proposal_value,
+        multisend_address,
+        delegatecall_targets,
+        admin_token: std::env::var("ADMIN_API_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty()),
+        preferred_signature_scheme,
+    });
 
-    let app = Router::new()
+    let routes = Router::new()
         .route("/health", get(health))
         .route("/api/v1/safe/info", get(get_safe_info))
-        .route("/api/v1/transactions", post(create_transaction))
+        .route("/api/v1/safe/balances", get(get_safe_balances))
+        .route("/api/v1/safes", get(list_safes))
+        .route(
+            "/api/v1/safes/{address}/nonce-history",
+            get(get_nonce_history),
+        )
+        .route(
+            "/api/v1/transactions",
+            get(list_transactions).post(create_transaction),
+        )
+        .route("/api/v1/transactions/import-csv", post(import_csv))
+        .route(
+            "/api/v1/transactions/erc20-transfer",
+            post(create_erc20_transfer),
+        )
         .route("/api/v1/transactions/{tx_id}", get(get_transaction))
         .route("/api/v1/transactions/{tx_id}/sign", post(sign_transaction))
+        .route(
+            "/api/v1/transactions/{tx_id}/execution-report",
+            get(get_execution_report),
+        )
+        .route(
+            "/api/v1/transactions/{tx_id}/resign",
+            post(resign_transaction),
+        )
         .route(
             "/api/v1/transactions/{tx_id}/execute",
             post(execute_transaction),
         )
+        .route(
+            "/api/v1/transactions/{tx_id}/signatures",
+            get(get_transaction_signatures),
+        )
         .route(
             "/api/v1/transactions/{tx_id}/status",
             get(get_transaction_status),
@@ -185,176 +1195,1517 @@ async fn main() -> anyhow::Result<()> {
             "/api/v1/ai-agents/analyze/{tx_id}",
             get(ai_analyze_transaction),
         )
-        .layer(CorsLayer::permissive())
-        .with_state(state);
+        .route(
+            "/api/v1/transactions/{tx_id}/batch-preview",
+            get(get_batch_preview),
+        )
+        .route(
+            "/api/v1/transactions/{tx_id}/simulate",
+            get(simulate_transaction),
+        )
+        .route(
+            "/api/v1/transactions/{tx_id}/exec-encoding",
+            get(get_exec_encoding),
+        )
+        .route(
+            "/api/v1/transactions/{tx_id}/ws",
+            get(subscribe_transaction),
+        )
+        .route(
+            "/api/v1/agents/{agent}/rejections",
+            get(get_agent_rejections),
+        )
+        .route(
+            "/api/v1/signers/{address}/notification-preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route("/api/v1/verify-signature", post(verify_signature))
+        .route("/api/v1/admin/reconcile", post(reconcile))
+        .route("/api/v1/admin/cancel-pending", post(cancel_pending))
+        .route("/api/v1/system/queue-metrics", get(get_queue_metrics));
+    let app = observability::with_metrics(routes)
+        .layer(axum::middleware::from_fn_with_state(
+            api_case,
+            api_case::convert_case,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            api_keys,
+            api_auth::require_api_key,
+        ))
+        .layer(cors)
+        .with_state(state.clone());
+
+    // Nothing executes before the server starts, so anything Executing now was cut
+    // short by a restart. Collected before serving so new executions aren't mistaken for them.
+    let interrupted = state
+        .store
+        .list()
+        .await?
+        .into_iter()
+        .filter(|(_, tx)| tx.status == TransactionStatus::Executing)
+        .map(|(tx_id, _)| tx_id)
+        .collect::<Vec<_>>();
+    if !interrupted.is_empty() {
+        warn!(
+            "{} proposals were left executing by a restart; recovering from chain state",
+            interrupted.len()
+        );
+    }
+
+    // Periodically invalidate proposals whose nonce was consumed on-chain
+    let reconcile_interval_secs = std::env::var("NONCE_RECONCILE_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(30);
+    tokio::spawn(nonce_reconciliation_loop(
+        state.clone(),
+        std::time::Duration::from_secs(reconcile_interval_secs),
+        interrupted,
+    ));
+
+    // Periodically compare owners/threshold against the chain
+    let drift_interval_secs = std::env::var("OWNER_DRIFT_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(60);
+    tokio::spawn(owner_drift_loop(
+        state.clone(),
+        std::time::Duration::from_secs(drift_interval_secs),
+    ));
+
+    // Periodically expire proposals that missed their signature deadline
+    let expiry_interval_secs = std::env::var("EXPIRY_SWEEP_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(30);
+    tokio::spawn(expiry_sweep_loop(
+        state.clone(),
+        std::time::Duration::from_secs(expiry_interval_secs),
+    ));
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3001").await?;
 
     info!("🚀 Orchestrator running on http://0.0.0.0:3001");
     info!("Safe address: {}", safe_address);
-    info!("⚠️  NOTE: This is a DEMO. In production:");
-    info!("   - Human signers would use their own wallets (MetaMask, etc.)");
-    info!("   - AI agents would run as separate services");
-    info!("   - Orchestrator would NEVER have access to private keys");
+    if !deployment_mode.is_production() {
+        info!("⚠️  NOTE: This is a DEMO. In production:");
+        info!("   - Human signers would use their own wallets (MetaMask, etc.)");
+        info!("   - AI agents would run as separate services");
+        info!("   - Orchestrator would NEVER have access to private keys");
+    }
+
+    shared::shutdown::serve(listener, app, shared::shutdown::drain_timeout_from_env()).await?;
+    Ok(())
+}
+
+// Unset leaves the zero address (refused in production); a malformed value or
+// bad EIP-55 checksum is most likely a typo, and SAFE_ADDRESS is part of the
+// signing domain, so it stops startup
+fn env_address(var: &str) -> Address {
+    match std::env::var(var) {
+        Ok(value) if !value.is_empty() => {
+            utils::parse_and_checksum(&value).unwrap_or_else(|e| panic!("Invalid {}: {}", var, e))
+        }
+        _ => Address::ZERO,
+    }
+}
+
+// The chain id is part of the EIP-712 domain every signature commits to, so
+// only an unset value falls back to testnet
+fn parse_chain_id(value: Option<&str>) -> Result<u64, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(KAIA_TESTNET_CHAIN_ID),
+        Some(value) => value
+            .parse()
+            .map_err(|e| format!("{:?} is not a chain id: {}", value, e)),
+    }
+}
+
+async fn nonce_reconciliation_loop(
+    state: Arc<AppState>,
+    interval: std::time::Duration,
+    mut interrupted: Vec<String>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match state.safe_executor.get_nonce().await {
+            Ok(onchain_nonce) => {
+                if !interrupted.is_empty() {
+                    match recover_interrupted_executions(&state, onchain_nonce, &interrupted).await
+                    {
+                        Ok(()) => interrupted.clear(),
+                        Err(e) => warn!("Recovery of interrupted executions deferred: {}", e),
+                    }
+                }
+                invalidate_consumed_nonces(&state, onchain_nonce).await;
+            }
+            Err(e) => warn!("Nonce reconciliation skipped, RPC unavailable: {}", e),
+        }
+    }
+}
+
+// How far back reconcile and restart recovery look for ExecutionSuccess/Failure
+const DEFAULT_EVENT_LOOKBACK_BLOCKS: u64 = 10_000;
+
+// Nonces per page of /safes/{address}/nonce-history
+const DEFAULT_NONCE_HISTORY_LIMIT: u64 = 50;
+const MAX_NONCE_HISTORY_LIMIT: u64 = 200;
+
+async fn recover_interrupted_executions(
+    state: &AppState,
+    onchain_nonce: U256,
+    tx_ids: &[String],
+) -> anyhow::Result<()> {
+    let events = state
+        .safe_executor
+        .scan_execution_events(DEFAULT_EVENT_LOOKBACK_BLOCKS)
+        .await?;
+    let _guard = state.mutation_lock.lock().await;
+    for tx_id in tx_ids {
+        let Some(mut tx_state) = state.store.get(tx_id).await? else {
+            continue;
+        };
+        match tx_state.recover_interrupted_execution(onchain_nonce, &events) {
+            Ok(true) => {
+                state.store.update(tx_id, &tx_state).await?;
+                warn!(
+                    "Recovered interrupted execution of {}: now {:?}",
+                    tx_id, tx_state.status
+                );
+            }
+            Ok(false) => {}
+            Err(e) => warn!("Could not recover {}: {}", tx_id, e),
+        }
+    }
+    Ok(())
+}
+
+async fn expiry_sweep_loop(state: Arc<AppState>, interval: std::time::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        sweep_expired(&state, Utc::now()).await;
+    }
+}
+
+// Proposals still collecting signatures past their deadline are failed. With
+// AUTO_EXTEND_NEAR_THRESHOLD, one that only lacks a single signature gets its
+// deadline pushed back instead, up to the configured number of times.
+async fn sweep_expired(state: &AppState, now: DateTime<Utc>) {
+    let _guard = state.mutation_lock.lock().await;
+    let txs = match state.store.list().await {
+        Ok(txs) => txs,
+        Err(e) => {
+            error!("Expiry sweep skipped, store unavailable: {}", e);
+            return;
+        }
+    };
+    for (tx_id, mut tx_state) in txs {
+        let Some(deadline) = tx_state.open_deadline() else {
+            continue;
+        };
+
+        let near_threshold = tx_state.signatures.len() + 1 == REQUIRED_SIGNATURES as usize;
+        let extension = state.auto_extend.filter(|cfg| {
+            near_threshold
+                && tx_state.deadline_extensions < cfg.max_extensions
+                && deadline - now <= chrono::Duration::seconds(cfg.window_secs)
+        });
+
+        if let Some(cfg) = extension {
+            let extended = deadline + chrono::Duration::seconds(cfg.window_secs);
+            tx_state.deadline = Some(extended);
+            tx_state.deadline_extensions += 1;
+            let detail = format!(
+                "One signature short; deadline extended from {} to {} ({}/{})",
+                deadline, extended, tx_state.deadline_extensions, cfg.max_extensions
+            );
+            info!("Proposal {}: {}", tx_id, detail);
+            tx_state.audit_log.push(AuditEntry {
+                at: now,
+                event: "deadline_extended".to_string(),
+                detail,
+            });
+        } else if tx_state.expire_if_overdue(now) {
+            warn!(
+                "Expiring {}: {}",
+                tx_id,
+                tx_state.invalidation_reason.as_deref().unwrap_or_default()
+            );
+        } else {
+            continue;
+        }
+
+        if let Err(e) = state.store.update(&tx_id, &tx_state).await {
+            error!("Failed to persist expiry sweep of {}: {}", tx_id, e);
+        }
+    }
+}
+
+// The signer set is configured, not read from the chain, so an on-chain owner
+// change silently breaks signer classification until someone notices
+async fn owner_drift_loop(state: Arc<AppState>, interval: std::time::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        for drift in state.safe_registry.check_drift().await {
+            error!(
+                "🚨 ALERT: Safe {} changed on-chain: owners added {:?}, removed {:?}, threshold {} -> {}",
+                drift.safe,
+                drift.added_owners,
+                drift.removed_owners,
+                drift.previous_threshold,
+                drift.threshold
+            );
+
+            let unknown_owners: Vec<Address> = drift
+                .added_owners
+                .iter()
+                .filter(|o| !state.signer_addresses.all().contains(o))
+                .copied()
+                .collect();
+            let removed_signers: Vec<Address> = drift
+                .removed_owners
+                .iter()
+                .filter(|o| state.signer_addresses.all().contains(o))
+                .copied()
+                .collect();
+            if !unknown_owners.is_empty() || !removed_signers.is_empty() {
+                error!(
+                    "🚨 Configured signers are out of date: unknown owners {:?}, removed signers {:?}",
+                    unknown_owners, removed_signers
+                );
+            }
+
+            state
+                .notifier
+                .send_alert(&serde_json::json!({
+                    "event": "safe_owner_drift",
+                    "drift": drift,
+                    "unknown_owners": unknown_owners,
+                    "removed_signers": removed_signers,
+                }))
+                .await;
+        }
+    }
+}
+
+// Once the Safe nonce moves past a proposal, its hash can never be executed, so
+// collected signatures are dropped rather than left to fail at execution time
+async fn invalidate_consumed_nonces(state: &AppState, onchain_nonce: U256) -> Vec<String> {
+    let mut invalidated = Vec::new();
+    let _guard = state.mutation_lock.lock().await;
+    let txs = match state.store.list().await {
+        Ok(txs) => txs,
+        Err(e) => {
+            error!("Nonce invalidation skipped, store unavailable: {}", e);
+            return invalidated;
+        }
+    };
+    for (tx_id, mut tx_state) in txs {
+        let observed = tx_state.status;
+        let open = matches!(
+            observed,
+            TransactionStatus::Pending
+                | TransactionStatus::CollectingSignatures
+                | TransactionStatus::ReadyToExecute
+        );
+        if !open || tx_state.transaction.nonce >= onchain_nonce {
+            continue;
+        }
+
+        let reason = format!(
+            "Safe nonce advanced to {}; proposal nonce {} was consumed by another transaction",
+            onchain_nonce, tx_state.transaction.nonce
+        );
+        warn!(
+            "Invalidating {} ({} signatures dropped): {}",
+            tx_id,
+            tx_state.signatures.len(),
+            reason
+        );
+
+        if let Err(e) = tx_state.transition(observed, TransactionStatus::Invalidated) {
+            warn!("Not invalidating {}: {}", tx_id, e);
+            continue;
+        }
+        tx_state.signatures.clear();
+        tx_state.invalidation_reason = Some(reason);
+        if let Err(e) = state.store.update(&tx_id, &tx_state).await {
+            error!("Failed to persist invalidation of {}: {}", tx_id, e);
+            continue;
+        }
+        invalidated.push(tx_id);
+    }
+
+    invalidated
+}
+
+// Store failures are infrastructure errors, never the caller's fault, except a
+// status transition rejected because the proposal has moved on
+fn store_error(e: anyhow::Error) -> StatusCode {
+    if let Some(rejected) = e.downcast_ref::<InvalidTransition>() {
+        warn!("Rejected status change: {}", rejected);
+        return StatusCode::CONFLICT;
+    }
+    error!("Transaction store error: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+fn ai_agents_error(e: SafeWalletError) -> StatusCode {
+    error!("AI analysis failed: {}", e);
+    match e {
+        SafeWalletError::AgentError(_) => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn store_api_error(e: anyhow::Error) -> ApiError {
+    let message = match e.downcast_ref::<InvalidTransition>() {
+        Some(rejected) => rejected.to_string(),
+        None => "Transaction store unavailable".to_string(),
+    };
+    api_error(store_error(e), message)
+}
+
+// Admin endpoints are disabled unless ADMIN_API_TOKEN is configured
+fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let expected = state.admin_token.as_deref().ok_or(StatusCode::FORBIDDEN)?;
+    let provided = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
 
-    axum::serve(listener, app).await?;
+    // Constant time, so response timing doesn't reveal how much of the token matched
+    if !bool::from(provided.as_bytes().ct_eq(expected.as_bytes())) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
     Ok(())
 }
 
-async fn health() -> Json<serde_json::Value> {
+async fn cancel_pending(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Option<Json<CancelPendingRequest>>,
+) -> Result<Json<CancelPendingResponse>, StatusCode> {
+    require_admin(&state, &headers)?;
+    let Json(req) = body.unwrap_or_default();
+    let up_to_nonce = req.up_to_nonce.map(U256::from);
+
+    let mut cancelled = Vec::new();
+    let mut skipped_executing = Vec::new();
+    {
+        let _guard = state.mutation_lock.lock().await;
+        for (tx_id, tx_state) in state.store.list().await.map_err(store_error)? {
+            if up_to_nonce.is_some_and(|max| tx_state.transaction.nonce > max) {
+                continue;
+            }
+
+            match tx_state.status {
+                TransactionStatus::CollectingSignatures | TransactionStatus::ReadyToExecute => {
+                    state
+                        .store
+                        .transition_status(&tx_id, tx_state.status, TransactionStatus::Cancelled)
+                        .await
+                        .map_err(store_error)?;
+                    cancelled.push(tx_id);
+                }
+                // Already submitted on-chain; cancelling here would only lose track of it
+                TransactionStatus::Executing => skipped_executing.push(tx_id),
+                _ => {}
+            }
+        }
+    }
+    cancelled.sort();
+    skipped_executing.sort();
+
+    warn!(
+        "Admin cancelled {} pending proposals (up to nonce {:?}), {} executing left untouched",
+        cancelled.len(),
+        req.up_to_nonce,
+        skipped_executing.len()
+    );
+
+    Ok(Json(CancelPendingResponse {
+        cancelled,
+        skipped_executing,
+    }))
+}
+
+// Manual counterpart to the nonce reconciliation loop: applies execution events
+// first so proposals executed out-of-band aren't merely marked invalidated
+async fn reconcile(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ReconcileQuery>,
+) -> Result<Json<ReconcileResponse>, StatusCode> {
+    require_admin(&state, &headers)?;
+    let lookback_blocks = query
+        .lookback_blocks
+        .unwrap_or(DEFAULT_EVENT_LOOKBACK_BLOCKS);
+
+    let onchain_nonce = state.safe_executor.get_nonce().await.map_err(|e| {
+        error!("Reconcile failed to read Safe nonce: {}", e);
+        StatusCode::BAD_GATEWAY
+    })?;
+    let events = state
+        .safe_executor
+        .scan_execution_events(lookback_blocks)
+        .await
+        .map_err(|e| {
+            error!("Reconcile failed to scan execution events: {}", e);
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    let mut marked_executed = Vec::new();
+    let mut marked_failed = Vec::new();
+    {
+        let _guard = state.mutation_lock.lock().await;
+        let mut txs = state.store.list().await.map_err(store_error)?;
+        for event in &events {
+            let safe_tx_hash = event.safe_tx_hash.to_string();
+            let Some((tx_id, tx_state)) = txs.iter_mut().find(|(_, tx)| tx.tx_hash == safe_tx_hash)
+            else {
+                continue;
+            };
+
+            match tx_state.apply_execution_event(event) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!("Reconcile skipped {}: {}", tx_id, e);
+                    continue;
+                }
+            }
+            state
+                .store
+                .update(tx_id, tx_state)
+                .await
+                .map_err(store_error)?;
+
+            if event.success {
+                marked_executed.push(tx_id.clone());
+            } else {
+                marked_failed.push(tx_id.clone());
+            }
+        }
+    }
+
+    let invalidated = invalidate_consumed_nonces(&state, onchain_nonce).await;
+
+    info!(
+        "Reconciled: {} executed, {} failed, {} invalidated",
+        marked_executed.len(),
+        marked_failed.len(),
+        invalidated.len()
+    );
+
+    Ok(Json(ReconcileResponse {
+        onchain_nonce: onchain_nonce.to_string(),
+        events_scanned: events.len(),
+        marked_executed,
+        marked_failed,
+        invalidated,
+    }))
+}
+
+// How long /health reuses the execution gas price it last fetched
+const HEALTH_GAS_PRICE_TTL: std::time::Duration = std::time::Duration::from_secs(15);
+
+async fn health(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let gas_price = {
+        let mut cached = state.health_gas_price.lock().await;
+        match *cached {
+            Some((fetched_at, price)) if fetched_at.elapsed() < HEALTH_GAS_PRICE_TTL => price,
+            _ => {
+                let price = state.safe_executor.execution_gas_price().await.ok();
+                *cached = Some((std::time::Instant::now(), price));
+                price
+            }
+        }
+    };
+
     Json(serde_json::json!({
         "status": "healthy",
         "service": "orchestrator",
         "network": "Kaia Kairos Testnet",
-        "mode": "DEMO - Not for production use"
+        "mode": state.deployment_mode.as_str(),
+        "gas_price": gas_price.map(|p| p.to_string()),
+        "max_gas_price": state.max_gas_price.map(|p| p.to_string()),
+        "rpc_endpoints": state.safe_executor.rpc_health(),
+        "gas_price_above_ceiling": matches!(
+            (gas_price, state.max_gas_price),
+            (Some(price), Some(ceiling)) if price > ceiling
+        )
     }))
 }
 
 async fn get_safe_info(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    // In production, this would query the Safe contract
+    // Cached by the registry for a short TTL, so page loads don't each hit the RPC
+    let onchain = state
+        .safe_registry
+        .state_of(state.safe_address)
+        .await
+        .ok_or(StatusCode::BAD_GATEWAY)?;
+
+    let (mut humans, mut ai_agents, mut other) = (Vec::new(), Vec::new(), Vec::new());
+    for owner in &onchain.owners {
+        match state.signer_addresses.kind(*owner) {
+            Some(SignerType::Human) => humans.push(owner.to_string()),
+            Some(_) => ai_agents.push(owner.to_string()),
+            None => other.push(owner.to_string()),
+        }
+    }
+
     Ok(Json(serde_json::json!({
         "safe_address": state.safe_address.to_string(),
-        "threshold": 4,
+        "threshold": onchain.threshold.saturating_to::<u64>(),
         "owners": {
-            "humans": [
-                state.signer_addresses.human1.to_string(),
-                state.signer_addresses.human2.to_string()
-            ],
-            "ai_agents": [
-                state.signer_addresses.ai_cfo.to_string(),
-                state.signer_addresses.ai_security.to_string(),
-                state.signer_addresses.ai_analyst.to_string()
-            ]
+            "humans": humans,
+            "ai_agents": ai_agents,
+            "other": other // Owners that aren't a configured signer
         },
-        "nonce": 0,
+        "nonce": onchain.nonce.saturating_to::<u64>(),
         "note": "All signers must provide their own signatures. Orchestrator does not hold any private keys."
     })))
 }
 
+async fn get_safe_balances(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SafeBalances>, StatusCode> {
+    let balances = state
+        .safe_executor
+        .balances(&state.tracked_tokens, &state.tokens)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch Safe balances: {}", e);
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    Ok(Json(balances))
+}
+
+async fn list_safes(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "safes": state.safe_registry.list().await
+    }))
+}
+
+async fn get_nonce_history(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+    Query(query): Query<NonceHistoryQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let address = Address::from_str(&address).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let executor = state
+        .safe_registry
+        .executor(address)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (onchain_nonce, events) = executor
+        .execution_history(DEFAULT_EVENT_LOOKBACK_BLOCKS)
+        .await
+        .map_err(|e| {
+            error!("Failed to read execution history of {}: {}", address, e);
+            StatusCode::BAD_GATEWAY
+        })?;
+    let onchain_nonce: u64 = onchain_nonce
+        .try_into()
+        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+
+    // One page of consumed nonces, newest first: [start, end)
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_NONCE_HISTORY_LIMIT)
+        .clamp(1, MAX_NONCE_HISTORY_LIMIT);
+    let end = query.before.unwrap_or(onchain_nonce).min(onchain_nonce);
+    let start = end.saturating_sub(limit);
+
+    // Every nonce below the current one was consumed. Events in the lookback window
+    // fill in the on-chain hash of the nonce they're matched to; nonces consumed
+    // before the window keep a null tx_hash. Local executions are filled in below.
+    let mut history: BTreeMap<u64, NonceHistoryEntry> = (start..end)
+        .map(|nonce| {
+            (
+                nonce,
+                NonceHistoryEntry {
+                    source: NonceSource::External,
+                    tx_id: None,
+                    tx_hash: None,
+                },
+            )
+        })
+        .collect();
+    for event in events.iter().rev() {
+        if history.values().all(|entry| entry.tx_hash.is_some()) {
+            break;
+        }
+        let nonce = executor
+            .execution_nonce(event, state.chain_id, start..end)
+            .await
+            .map_err(|e| {
+                error!("Failed to match execution event of {}: {}", address, e);
+                StatusCode::BAD_GATEWAY
+            })?;
+        if let Some(entry) = nonce.and_then(|nonce| history.get_mut(&nonce)) {
+            entry.tx_hash = event.transaction_hash.map(|h| h.to_string());
+        }
+    }
+
+    // Only the default Safe has proposals in the local store
+    if address == state.safe_address {
+        let txs = state.store.list().await.map_err(store_error)?;
+        for (tx_id, tx_state) in &txs {
+            if !matches!(tx_state.status, TransactionStatus::Executed) {
+                continue;
+            }
+            let Ok(nonce) = u64::try_from(tx_state.transaction.nonce) else {
+                continue;
+            };
+            let Some(entry) = history.get_mut(&nonce) else {
+                continue;
+            };
+            // A local proposal's safeTxHash identifies its event without decoding
+            let event_hash = events
+                .iter()
+                .find(|event| event.safe_tx_hash.to_string() == tx_state.tx_hash)
+                .and_then(|event| event.transaction_hash);
+            *entry = NonceHistoryEntry {
+                source: NonceSource::Local,
+                tx_id: Some(tx_id.clone()),
+                tx_hash: tx_state
+                    .executed_tx_hash
+                    .or(event_hash)
+                    .map(|h| h.to_string())
+                    .or(entry.tx_hash.take()),
+            };
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "safe_address": address.to_string(),
+        "current_nonce": onchain_nonce,
+        "history": history,
+        // Pass as `before` for the next (older) page
+        "next_before": (start > 0).then_some(start)
+    })))
+}
+
 async fn create_transaction(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateTransactionRequest>,
 ) -> Result<Json<CreateTransactionResponse>, StatusCode> {
+    let operation = req.operation.unwrap_or(Operation::Call);
+    create_proposal(&state, req, operation, None)
+        .await
+        .map(Json)
+}
+
+async fn create_erc20_transfer(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<Erc20TransferRequest>,
+) -> Result<Json<CreateTransactionResponse>, ApiError> {
+    let create = req.into_create_request()?;
+    create_proposal(&state, create, Operation::Call, None)
+        .await
+        .map(Json)
+        .map_err(|status| api_error(status, "Failed to create token transfer proposal"))
+}
+
+// An in-flight proposal for the same SafeTx, by content hash
+async fn find_in_flight(
+    state: &AppState,
+    content_hash: B256,
+) -> Result<Option<(String, TransactionState)>, StatusCode> {
+    state
+        .store
+        .find_in_flight(content_hash)
+        .await
+        .map_err(store_error)
+}
+
+// Shared by the JSON, ERC20 transfer and CSV endpoints. `nonce` pins the Safe
+// nonce, e.g. for a series of imported payments; otherwise the Safe's current
+// nonce is used.
+async fn create_proposal(
+    state: &Arc<AppState>,
+    req: CreateTransactionRequest,
+    operation: Operation,
+    nonce: Option<U256>,
+) -> Result<CreateTransactionResponse, StatusCode> {
     info!("Creating transaction to: {}, value: {}", req.to, req.value);
 
     let to = Address::from_str(&req.to).map_err(|_| StatusCode::BAD_REQUEST)?;
 
+    // A call to 0x0 burns the value; contract creation isn't possible through
+    // execTransaction, so there's no legitimate reason to target it
+    if to == Address::ZERO {
+        warn!("Rejecting proposal to the zero address");
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    if operation == Operation::DelegateCall && !state.delegatecall_targets.contains(&to) {
+        warn!(
+            "Rejecting delegatecall to {}, not in DELEGATECALL_TARGETS",
+            to
+        );
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
     let value = U256::from_str(&req.value).map_err(|_| StatusCode::BAD_REQUEST)?;
 
+    let signature_timeout_secs = match req.signature_timeout_secs {
+        Some(secs) if secs <= 0 => return Err(StatusCode::BAD_REQUEST),
+        Some(secs) => secs,
+        None => state.signature_timeout_secs,
+    };
+
+    // Values this large are almost always a units mistake (KAIA passed as wei
+    // with extra zeros); the proposer has to opt in explicitly
+    if let Some(ceiling) = state.max_proposal_value {
+        if value > ceiling && !req.allow_large_value {
+            warn!(
+                "Rejecting proposal value {} wei above MAX_PROPOSAL_VALUE {}; check units, or set allow_large_value to override",
+                value, ceiling
+            );
+            return Err(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
     let data = req
         .data
         .and_then(|d| hex::decode(d.trim_start_matches("0x")).ok())
         .map(Bytes::from)
-        .unwrap_or_else(Bytes::new);
+        .unwrap_or_default();
 
-    // Get current nonce from Safe contract
-    let nonce = state.safe_executor.get_nonce().await.unwrap_or(U256::ZERO);
+    // Spam filter: an empty zero-value transfer does nothing but burn a nonce
+    if value.is_zero() && (data.is_empty() || !state.allow_zero_value_calls) {
+        warn!(
+            "Rejecting zero-value proposal to {} (calldata: {} bytes)",
+            to,
+            data.len()
+        );
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let proposer_verified = match (req.proposer, &req.proposer_signature) {
+        (Some(proposer), Some(signature)) => {
+            let signature = hex::decode(signature.trim_start_matches("0x"))
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            let claim = ProposalClaim {
+                safe: state.safe_address,
+                to,
+                value,
+                data: data.clone(),
+                proposer,
+                timestamp: req.proposed_at.ok_or(StatusCode::BAD_REQUEST)?,
+            };
+            claim.verify(state.chain_id, &signature).map_err(|e| {
+                warn!("Rejecting proposal with invalid proposer signature: {}", e);
+                StatusCode::UNAUTHORIZED
+            })?;
+            true
+        }
+        (None, Some(_)) => return Err(StatusCode::BAD_REQUEST),
+        _ => false,
+    };
+    if state.require_proposer_signature && !proposer_verified {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    // Get current nonce from Safe contract. Guessing would produce a hash that
+    // can never execute, so an RPC failure fails the request.
+    let nonce = match nonce {
+        Some(nonce) => nonce,
+        None => state.safe_executor.get_nonce().await.map_err(|e| {
+            error!("Failed to fetch Safe nonce: {}", e);
+            StatusCode::BAD_GATEWAY
+        })?,
+    };
+
+    let parse_gas = |field: Option<String>| {
+        field
+            .map(|s| U256::from_str(&s).map_err(|_| StatusCode::BAD_REQUEST))
+            .transpose()
+    };
+    let safe_tx_gas = parse_gas(req.gas.safe_tx_gas)?.unwrap_or(U256::ZERO);
+    let base_gas = parse_gas(req.gas.base_gas)?.unwrap_or(U256::ZERO);
+    let gas_refund = GasRefundConfig {
+        gas_price: parse_gas(req.gas.gas_price)?.unwrap_or(state.gas_refund.gas_price),
+        gas_token: req.gas.gas_token.unwrap_or(state.gas_refund.gas_token),
+    };
+
+    // Without a refund price these fields only change the hash, so they're
+    // almost certainly a mistake
+    if gas_refund.gas_price.is_zero() && (!base_gas.is_zero() || req.gas.refund_receiver.is_some())
+    {
+        warn!("Rejecting proposal with base_gas or refund_receiver but no gas_price");
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    // A native refund above the network ceiling overpays the relayer at the Safe's expense
+    if let Some(max) = state.max_gas_price {
+        if gas_refund.gas_token == Address::ZERO && gas_refund.gas_price > max {
+            warn!(
+                "Rejecting refund gas_price {} above MAX_GAS_PRICE {}",
+                gas_refund.gas_price, max
+            );
+            return Err(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
+    // When the Safe refunds gas, the refund must go to the relayer that pays it.
+    // This is part of the signed hash, so it has to be fixed here, not at execution.
+    let refund_receiver = match req.gas.refund_receiver {
+        Some(receiver) => receiver,
+        None if gas_refund.enabled() => state.safe_executor.relayer_address().map_err(|e| {
+            error!("Failed to resolve relayer address: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?,
+        None => Address::ZERO,
+    };
 
     // Create Safe transaction
     let safe_tx = SafeTransaction {
         to,
         value,
-        data: data.clone(),
-        operation: 0, // Call
-        safe_tx_gas: U256::ZERO,
-        base_gas: U256::ZERO,
-        gas_price: U256::ZERO,
-        gas_token: Address::ZERO,
-        refund_receiver: Address::ZERO,
+        data,
+        operation,
+        safe_tx_gas,
+        base_gas,
+        gas_price: gas_refund.gas_price,
+        gas_token: gas_refund.gas_token,
+        refund_receiver,
         nonce,
     };
 
     let tx_id = uuid::Uuid::new_v4().to_string();
 
-    // Get the actual Safe transaction hash from the contract
-    let safe_tx_hash = state
-        .safe_executor
-        .get_transaction_hash(to, value, data, nonce)
-        .await
-        .map(|h| h.to_string())
-        .unwrap_or_else(|e| {
-            error!("Failed to get transaction hash from Safe: {}", e);
-            format!("0x{}", hex::encode(safe_tx.encode_for_signing()))
-        });
+    // With VERIFY_HASH_ONCHAIN the contract's getTransactionHash is canonical, since
+    // that's what it validates signatures against; the local hash is the fallback
+    let local_hash = safe_tx.safe_tx_hash(state.chain_id, state.safe_address);
+    let mut hash_verified_onchain = false;
+    let mut hash_source = HashSource::Local;
+    let mut canonical_hash = local_hash;
+
+    if state.verify_hash_onchain {
+        match state.safe_executor.get_transaction_hash(&safe_tx).await {
+            Ok(onchain_hash) => {
+                hash_source = HashSource::Contract;
+                canonical_hash = onchain_hash;
+                hash_verified_onchain = onchain_hash == local_hash;
+                if !hash_verified_onchain {
+                    error!(
+                        "Local Safe tx hash {} differs from contract hash {}; check CHAIN_ID",
+                        local_hash, onchain_hash
+                    );
+                }
+            }
+            Err(e) => warn!(
+                "Safe.getTransactionHash unavailable, falling back to local hash {}: {}",
+                local_hash, e
+            ),
+        }
+    }
 
-    let tx_state = TransactionState {
+    let safe_tx_hash = canonical_hash.to_string();
+
+    let admin_operation = admin_ops::decode(state.safe_address, state.multisend_address, &safe_tx);
+    if let Some(op) = &admin_operation {
+        warn!(
+            "Proposal {} modifies the Safe itself ({:?}); all human signers must approve",
+            tx_id, op
+        );
+    }
+
+    // Listed recipients (bridges, fresh contracts) need AI approval regardless of the global flag
+    let ai_review_required = state.require_ai_approval || state.mandatory_review.contains(&to);
+
+    let created_at = Utc::now();
+    let deadline = chrono::Duration::try_seconds(signature_timeout_secs)
+        .and_then(|timeout| created_at.checked_add_signed(timeout))
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let mut tx_state = TransactionState {
         transaction: safe_tx,
         signatures: Vec::new(),
         status: TransactionStatus::CollectingSignatures,
         tx_hash: safe_tx_hash.clone(),
+        ai_analysis: None,
+        created_at,
+        ready_at: None,
+        invalidation_reason: None,
+        executed_tx_hash: None,
+        metadata: ProposalMetadata {
+            reference: req.reference.filter(|r| !r.trim().is_empty()),
+            tags: req.tags,
+            proposer: req.proposer,
+            proposer_verified,
+            admin_operation,
+            ai_unavailable: false,
+        },
+        deadline: Some(deadline),
+        deadline_extensions: 0,
+        audit_log: Vec::new(),
+    };
+
+    // Typed data is built from the local domain; when the contract hashes
+    // differently, a wallet signing it would produce a signature the Safe rejects
+    let typed_data = match state.preferred_signature_scheme {
+        SignatureScheme::TypedDataV4
+            if matches!(hash_source, HashSource::Contract) && !hash_verified_onchain =>
+        {
+            warn!(
+                "Withholding typed data for {}: local domain doesn't match the contract",
+                tx_id
+            );
+            None
+        }
+        SignatureScheme::TypedDataV4 => Some(
+            tx_state
+                .transaction
+                .typed_data(state.chain_id, state.safe_address),
+        ),
+        SignatureScheme::EthSign => None,
     };
 
+    // Racing clients proposing the same SafeTx (content hash covers the nonce)
+    // get the existing proposal instead of a second one competing for the nonce
+    let content_hash = tx_state.transaction.content_hash();
+    let deduplicated = |existing_id: String, existing: TransactionState| {
+        info!(
+            "Proposal identical to in-flight {} (nonce {}), returning it",
+            existing_id, nonce
+        );
+        CreateTransactionResponse {
+            sign_message: format!(
+                "Please sign this hash with your wallet: {}",
+                existing.tx_hash
+            ),
+            safe_tx_hash: existing.tx_hash,
+            tx_id: existing_id,
+            nonce,
+            required_signatures: 4,
+            current_signatures: existing.signatures.len(),
+            hash_verified_onchain,
+            hash_source,
+            status: existing.status,
+            ai_review_required,
+            preferred_signature_scheme: state.preferred_signature_scheme,
+            typed_data: typed_data.clone(),
+            deduplicated: true,
+        }
+    };
+    // Checked before the AI call so a repeat doesn't cost another analysis, and
+    // again under the lock below in case an identical proposal landed meanwhile
+    if let Some((existing_id, existing)) = find_in_flight(state, content_hash).await? {
+        return Ok(deduplicated(existing_id, existing));
+    }
+
+    if ai_review_required {
+        info!("AI review required for proposal to {}", to);
+        // Held in Pending until the AI consensus approves it
+        tx_state.status = TransactionStatus::Pending;
+        // An unreachable or slow agent service is handled per AI_FAILURE_POLICY
+        match state
+            .ai_agents
+            .analyze_transaction(
+                state.safe_address,
+                &tx_state.transaction,
+                state.signer_addresses.collected(&tx_state),
+            )
+            .await
+        {
+            Ok(analysis) => apply_ai_analysis(&mut tx_state, analysis),
+            Err(e) => match state.ai_failure_policy {
+                // A later ai-analyze call can release it
+                AiFailurePolicy::Hold => warn!("AI analysis failed for {}: {}", tx_id, e),
+                AiFailurePolicy::Open => {
+                    warn!(
+                        "AI analysis failed for {}, collecting signatures without review: {}",
+                        tx_id, e
+                    );
+                    let _ = tx_state.transition(
+                        TransactionStatus::Pending,
+                        TransactionStatus::CollectingSignatures,
+                    );
+                    tx_state.metadata.ai_unavailable = true;
+                    tx_state.audit_log.push(AuditEntry {
+                        at: Utc::now(),
+                        event: "ai_unavailable".to_string(),
+                        detail: format!("AI review skipped: {}", e),
+                    });
+                }
+                AiFailurePolicy::Closed => {
+                    error!(
+                        "AI analysis failed for {}, rejecting proposal: {}",
+                        tx_id, e
+                    );
+                    return Err(StatusCode::SERVICE_UNAVAILABLE);
+                }
+            },
+        }
+    }
+    let status = tx_state.status;
+
+    let notification = ProposalNotification {
+        event: "proposal_created",
+        tx_id: tx_id.clone(),
+        safe_tx_hash: safe_tx_hash.clone(),
+        to,
+        value,
+        tags: tx_state.metadata.tags.clone(),
+    };
+
+    let guard = state.mutation_lock.lock().await;
+    if let Some((existing_id, existing)) = find_in_flight(state, content_hash).await? {
+        return Ok(deduplicated(existing_id, existing));
+    }
+
     state
-        .transactions
-        .write()
+        .store
+        .insert(&tx_id, &tx_state)
         .await
-        .insert(tx_id.clone(), tx_state);
+        .map_err(store_error)?;
+    drop(guard);
+
+    // Webhook delivery must not hold up the proposer's response
+    let notifier = state.notifier.clone();
+    let signers = state.signer_addresses.all();
+    tokio::spawn(async move { notifier.notify_signers(&signers, &notification).await });
 
-    Ok(Json(CreateTransactionResponse {
+    Ok(CreateTransactionResponse {
         tx_id: tx_id.clone(),
         safe_tx_hash: safe_tx_hash.clone(),
         sign_message: format!("Please sign this hash with your wallet: {}", safe_tx_hash),
+        nonce,
         required_signatures: 4,
         current_signatures: 0,
-    }))
+        hash_verified_onchain,
+        hash_source,
+        status,
+        ai_review_required,
+        preferred_signature_scheme: state.preferred_signature_scheme,
+        typed_data,
+        deduplicated: false,
+    })
+}
+
+// Payment rows from a spreadsheet export: `to,value,token,reference`, with
+// `token` empty for native KAIA and `value` in wei / the token's base units
+async fn import_csv(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ImportCsvQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<ImportCsvResponse>), ApiError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+
+    let mut results = Vec::new();
+    let mut valid = Vec::new();
+    for (index, row) in reader.deserialize::<CsvRow>().enumerate() {
+        let row_number = index + 1;
+        match row
+            .map_err(|e| format!("Malformed row: {}", e))
+            .and_then(|row| row.into_call(state.max_proposal_value))
+        {
+            Ok((call, reference)) => valid.push((row_number, call, reference)),
+            Err(error) => results.push(ImportRowResult::failed(row_number, error)),
+        }
+    }
+    if valid.is_empty() && results.is_empty() {
+        return Err(api_error(StatusCode::BAD_REQUEST, "CSV contains no rows"));
+    }
+
+    let mut batch = None;
+    match query.mode {
+        // One proposal per payment, on consecutive nonces so they can all execute
+        ImportMode::Individual => {
+            let mut nonce = next_free_nonce(&state).await?;
+            for (row, call, reference) in valid {
+                let req = CreateTransactionRequest {
+                    to: call.to.to_string(),
+                    value: call.value.to_string(),
+                    data: Some(hex::encode(&call.data)),
+                    reference,
+                    tags: Vec::new(),
+                    proposer: None,
+                    proposer_signature: None,
+                    proposed_at: None,
+                    allow_large_value: false,
+                    signature_timeout_secs: None,
+                    operation: None,
+                    gas: GasParams::default(),
+                };
+                match create_proposal(&state, req, Operation::Call, Some(nonce)).await {
+                    Ok(created) => {
+                        if !created.deduplicated {
+                            nonce += U256::from(1);
+                        }
+                        results.push(ImportRowResult::created(row, &created));
+                    }
+                    Err(status) => results.push(ImportRowResult::failed(
+                        row,
+                        format!("Rejected: {}", status),
+                    )),
+                }
+            }
+        }
+        // All-or-nothing: a single MultiSend proposal, only if every row is valid
+        ImportMode::Batch => {
+            let multisend = state.multisend_address.ok_or_else(|| {
+                api_error(
+                    StatusCode::NOT_IMPLEMENTED,
+                    "Batch import requires MULTISEND_ADDRESS",
+                )
+            })?;
+            if !results.is_empty() {
+                results.sort_by_key(|r| r.row);
+                return Ok((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(ImportCsvResponse {
+                        mode: query.mode,
+                        created: 0,
+                        failed: results.len(),
+                        results,
+                        batch: None,
+                    }),
+                ));
+            }
+
+            let batch_tx = MultiSendBuilder::new(
+                multisend,
+                valid
+                    .iter()
+                    .map(|(_, call, _)| (call.operation, call.to, call.value, call.data.clone())),
+            )
+            .build();
+            let references: Vec<String> = valid
+                .iter()
+                .filter_map(|(_, _, reference)| reference.clone())
+                .collect();
+            let req = CreateTransactionRequest {
+                to: batch_tx.to.to_string(),
+                value: batch_tx.value.to_string(),
+                data: Some(hex::encode(&batch_tx.data)),
+                reference: (!references.is_empty()).then(|| references.join(",")),
+                tags: Vec::new(),
+                proposer: None,
+                proposer_signature: None,
+                proposed_at: None,
+                allow_large_value: false,
+                signature_timeout_secs: None,
+                operation: None,
+                gas: GasParams::default(),
+            };
+            let created = create_proposal(&state, req, batch_tx.operation, None)
+                .await
+                .map_err(|status| api_error(status, "Failed to create batch proposal"))?;
+            results.extend(
+                valid
+                    .iter()
+                    .map(|(row, _, _)| ImportRowResult::created(*row, &created)),
+            );
+            batch = Some(created);
+        }
+    }
+
+    results.sort_by_key(|r| r.row);
+    let created = results.iter().filter(|r| r.error.is_none()).count();
+    info!(
+        "CSV import ({:?}): {} rows accepted, {} rejected",
+        query.mode,
+        created,
+        results.len() - created
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(ImportCsvResponse {
+            mode: query.mode,
+            created,
+            failed: results.len() - created,
+            results,
+            batch,
+        }),
+    ))
+}
+
+// First nonce at or above the Safe's that no in-flight proposal is using
+async fn next_free_nonce(state: &AppState) -> Result<U256, ApiError> {
+    let mut nonce = state.safe_executor.get_nonce().await.map_err(|e| {
+        error!("Failed to fetch Safe nonce: {}", e);
+        api_error(StatusCode::BAD_GATEWAY, "Failed to fetch Safe nonce")
+    })?;
+    let taken: HashSet<U256> = state
+        .store
+        .list()
+        .await
+        .map_err(store_api_error)?
+        .into_iter()
+        .filter(|(_, tx)| tx.status.is_in_flight())
+        .map(|(_, tx)| tx.transaction.nonce)
+        .collect();
+    while taken.contains(&nonce) {
+        nonce += U256::from(1);
+    }
+    Ok(nonce)
+}
+
+// Read paths don't wait for the sweep: an overdue proposal is expired on access
+async fn expire_on_read(
+    state: &AppState,
+    tx_id: &str,
+    tx_state: TransactionState,
+) -> Result<TransactionState, StatusCode> {
+    let now = Utc::now();
+    if tx_state.open_deadline().is_none_or(|d| d > now) {
+        return Ok(tx_state);
+    }
+
+    let _guard = state.mutation_lock.lock().await;
+    let mut tx_state = state
+        .store
+        .get(tx_id)
+        .await
+        .map_err(store_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if tx_state.expire_if_overdue(now) {
+        warn!(
+            "Expiring {}: {}",
+            tx_id,
+            tx_state.invalidation_reason.as_deref().unwrap_or_default()
+        );
+        state
+            .store
+            .update(tx_id, &tx_state)
+            .await
+            .map_err(store_error)?;
+    }
+    Ok(tx_state)
+}
+
+// Stores a fresh verdict and releases a proposal held for review once it passes
+fn apply_ai_analysis(tx_state: &mut TransactionState, analysis: AiAnalysis) {
+    if analysis.approved && matches!(tx_state.status, TransactionStatus::Pending) {
+        // Pending -> CollectingSignatures is always allowed
+        let _ = tx_state.transition(
+            TransactionStatus::Pending,
+            TransactionStatus::CollectingSignatures,
+        );
+    }
+    tx_state.ai_analysis = Some(analysis);
+}
+
+// Runs a new AI review and stores it. mutation_lock isn't held across the
+// ai-agents round-trip, so the verdict is applied to the proposal as it is
+// once the review comes back.
+async fn refresh_ai_analysis(state: &AppState, tx_id: &str) -> Result<AiAnalysis, StatusCode> {
+    let tx_state = {
+        let _guard = state.mutation_lock.lock().await;
+        state
+            .store
+            .get(tx_id)
+            .await
+            .map_err(store_error)?
+            .ok_or(StatusCode::NOT_FOUND)?
+    };
+    let analysis = state
+        .ai_agents
+        .analyze_transaction(
+            state.safe_address,
+            &tx_state.transaction,
+            state.signer_addresses.collected(&tx_state),
+        )
+        .await
+        .map_err(ai_agents_error)?;
+
+    let _guard = state.mutation_lock.lock().await;
+    let mut tx_state = state
+        .store
+        .get(tx_id)
+        .await
+        .map_err(store_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    apply_ai_analysis(&mut tx_state, analysis.clone());
+    state
+        .store
+        .update(tx_id, &tx_state)
+        .await
+        .map_err(store_error)?;
+    Ok(analysis)
 }
 
 async fn get_transaction(
     State(state): State<Arc<AppState>>,
-    Path(tx_id): Path<String>,
+    TxId(tx_id): TxId,
+    Query(query): Query<TransactionInfoQuery>,
 ) -> Result<Json<TransactionInfoResponse>, StatusCode> {
-    let txs = state.transactions.read().await;
-    let tx_state = txs.get(&tx_id).ok_or(StatusCode::NOT_FOUND)?;
+    if query.refresh.unwrap_or(false) {
+        refresh_ai_analysis(&state, &tx_id).await?;
+    }
+
+    let tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let tx_state = expire_on_read(&state, &tx_id, tx_state).await?;
+
+    Ok(Json(transaction_info(&state, tx_id, &tx_state)))
+}
+
+async fn list_transactions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListTransactionsQuery>,
+) -> Result<Json<TransactionListResponse>, StatusCode> {
+    let limit = query.limit.unwrap_or(20).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let mut txs: Vec<(String, TransactionState)> = state
+        .store
+        .list()
+        .await
+        .map_err(store_error)?
+        .into_iter()
+        .filter(|(_, tx_state)| match &query.reference {
+            Some(reference) => tx_state.metadata.reference.as_deref() == Some(reference.as_str()),
+            None => true,
+        })
+        .filter(|(_, tx_state)| query.status.is_none_or(|status| tx_state.status == status))
+        .collect();
+
+    // Newest first; the id breaks ties so pages are stable
+    txs.sort_by(|(a_id, a), (b_id, b)| {
+        b.created_at.cmp(&a.created_at).then_with(|| a_id.cmp(b_id))
+    });
+
+    Ok(Json(TransactionListResponse {
+        total: txs.len(),
+        transactions: txs
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(tx_id, tx_state)| transaction_info(&state, tx_id, &tx_state))
+            .collect(),
+        limit,
+        offset,
+    }))
+}
 
+fn transaction_info(
+    state: &AppState,
+    tx_id: String,
+    tx_state: &TransactionState,
+) -> TransactionInfoResponse {
     let signatures: Vec<SignatureInfo> = tx_state
         .signatures
         .iter()
         .map(|sig| {
-            let signer_type = if sig.signer == state.signer_addresses.human1
-                || sig.signer == state.signer_addresses.human2
-            {
-                "Human"
-            } else if sig.signer == state.signer_addresses.ai_cfo
-                || sig.signer == state.signer_addresses.ai_security
-                || sig.signer == state.signer_addresses.ai_analyst
-            {
-                "AI Agent"
-            } else {
-                "Unknown"
-            };
+            let signer_type = sig.verified_signer().map_or("Unverified", |signer| {
+                state.signer_addresses.signer_type(signer)
+            });
 
             SignatureInfo {
                 signer: sig.signer.to_string(),
                 signer_type: signer_type.to_string(),
-                signed_at: chrono::Utc::now().to_rfc3339(),
+                signed_at: sig.signed_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
             }
         })
         .collect();
 
-    let ready_to_execute = tx_state.signatures.len() >= 4;
+    let ready_to_execute = state.can_execute(tx_state);
 
-    Ok(Json(TransactionInfoResponse {
+    TransactionInfoResponse {
         tx_id,
         transaction: tx_state.transaction.clone(),
+        value_kaia: utils::format_wei(tx_state.transaction.value),
+        content_hash: tx_state.transaction.content_hash(),
         signatures,
-        status: tx_state.status.clone(),
+        status: tx_state.status,
         ready_to_execute,
         safe_tx_hash: tx_state.tx_hash.clone(),
-    }))
+        ai_analysis: tx_state.ai_analysis.clone(),
+        invalidation_reason: tx_state.invalidation_reason.clone(),
+        deadline: tx_state.deadline,
+        deadline_extensions: tx_state.deadline_extensions,
+        audit_log: tx_state.audit_log.clone(),
+        metadata: tx_state.metadata.clone(),
+    }
 }
 
 async fn sign_transaction(
     State(state): State<Arc<AppState>>,
-    Path(tx_id): Path<String>,
+    TxId(tx_id): TxId,
     Json(req): Json<SignTransactionRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let mut txs = state.transactions.write().await;
-    let tx_state = txs.get_mut(&tx_id).ok_or(StatusCode::NOT_FOUND)?;
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let _guard = state.mutation_lock.lock().await;
+    let mut tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_api_error)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not found"))?;
+
+    let signer_addr = Address::from_str(&req.signer_address)
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid signer address"))?;
+
+    // Only the configured owners count toward the threshold
+    if !state.signer_addresses.all().contains(&signer_addr) {
+        warn!(
+            "Rejecting signature from non-owner {} for {}",
+            signer_addr, tx_id
+        );
+        return Err(api_error(
+            StatusCode::FORBIDDEN,
+            format!("{} is not a signer of this Safe", signer_addr),
+        ));
+    }
+
+    let now = Utc::now();
+    if tx_state.expire_if_overdue(now) {
+        warn!(
+            "Expiring {}: {}",
+            tx_id,
+            tx_state.invalidation_reason.as_deref().unwrap_or_default()
+        );
+        state
+            .store
+            .update(&tx_id, &tx_state)
+            .await
+            .map_err(store_api_error)?;
+    }
+    if tx_state.is_expired(now) {
+        return Err(api_error(
+            StatusCode::GONE,
+            "Signature deadline has passed; the proposal expired",
+        ));
+    }
 
-    let signer_addr =
-        Address::from_str(&req.signer_address).map_err(|_| StatusCode::BAD_REQUEST)?;
+    // Proposals held for AI review can't collect signatures yet, and finished
+    // ones can't take any more
+    match tx_state.status {
+        TransactionStatus::CollectingSignatures | TransactionStatus::ReadyToExecute => {}
+        TransactionStatus::Pending => {
+            return Err(api_error(
+                StatusCode::CONFLICT,
+                "Transaction is awaiting AI review",
+            ))
+        }
+        status => {
+            return Err(api_error(
+                StatusCode::CONFLICT,
+                format!(
+                    "Transaction is {:?} and no longer collecting signatures",
+                    status
+                ),
+            ))
+        }
+    }
 
     // Check if already signed
     if tx_state.signatures.iter().any(|s| s.signer == signer_addr) {
@@ -364,97 +2715,339 @@ async fn sign_transaction(
     }
 
     // All signers provide their own signatures
-    let signature = hex::decode(req.signature.trim_start_matches("0x"))
-        .map(Bytes::from)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let mut signature = hex::decode(req.signature.trim_start_matches("0x"))
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Signature is not valid hex"))?;
+    normalize_v(&mut signature);
+    // Recover once here; every later read uses the cached result. Anything that
+    // doesn't recover to the claimed signer would only fail on-chain.
+    let hash = B256::from_str(&tx_state.tx_hash).map_err(|_| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Stored Safe tx hash is malformed",
+        )
+    })?;
+    let recovered = recover_signer(hash, &signature).map_err(|e| {
+        warn!("Rejecting signature for {}: {}", tx_id, e);
+        api_error(StatusCode::BAD_REQUEST, e)
+    })?;
+    if recovered.signer != signer_addr {
+        warn!(
+            "Signature for {} recovers to {}, not the claimed signer {}",
+            tx_id, recovered.signer, signer_addr
+        );
+        return Err(api_error(
+            StatusCode::BAD_REQUEST,
+            "Signature does not recover to signer_address",
+        ));
+    }
+    let verified_signer = recovered.signer;
+    let recovered = Some(recovered);
+    let signature = Bytes::from(signature);
 
     info!("Signer {} provided signature", req.signer_address);
 
-    tx_state.signatures.push(Signature {
+    let signature = Signature {
         signer: signer_addr,
         signature,
-    });
+        recovered,
+        signed_at: Some(Utc::now()),
+        signed_hash: Some(hash),
+    };
+    tx_state.signatures.push(signature.clone());
+    if let Err(e) = validate_signer_set(&tx_state.signatures) {
+        warn!("Rejecting signature for {}: {}", tx_id, e);
+        return Err(api_error(StatusCode::UNPROCESSABLE_ENTITY, e));
+    }
+    state
+        .store
+        .add_signature(&tx_id, signature)
+        .await
+        .map_err(store_api_error)?;
+    metrics::counter!(
+        "signatures_collected_total",
+        "signer_type" => state.signer_addresses.metric_label(verified_signer),
+    )
+    .increment(1);
 
-    // Update status if we have enough signatures
-    if tx_state.signatures.len() >= 4 {
-        tx_state.status = TransactionStatus::ReadyToExecute;
+    // Update status if we have enough signatures; late signatures on an
+    // executed or cancelled proposal don't reopen it
+    let ready_to_execute = state.can_execute(&tx_state);
+    if ready_to_execute && tx_state.status == TransactionStatus::CollectingSignatures {
+        tx_state
+            .transition(
+                TransactionStatus::CollectingSignatures,
+                TransactionStatus::ReadyToExecute,
+            )
+            .map_err(|e| api_error(StatusCode::CONFLICT, e.to_string()))?;
+        tx_state.ready_at.get_or_insert_with(Utc::now);
+        state
+            .store
+            .update(&tx_id, &tx_state)
+            .await
+            .map_err(store_api_error)?;
     }
 
     // Determine signer type based on known addresses
-    let signer_type = if signer_addr == state.signer_addresses.human1
-        || signer_addr == state.signer_addresses.human2
-    {
-        "Human"
-    } else if signer_addr == state.signer_addresses.ai_cfo
-        || signer_addr == state.signer_addresses.ai_security
-        || signer_addr == state.signer_addresses.ai_analyst
-    {
-        "AI Agent"
-    } else {
-        "Unknown"
-    };
+    let signer_type = state.signer_addresses.signer_type(verified_signer);
 
     Ok(Json(serde_json::json!({
         "success": true,
         "signer_type": signer_type,
         "current_signatures": tx_state.signatures.len(),
         "required_signatures": 4,
-        "ready_to_execute": tx_state.signatures.len() >= 4
+        "missing_human_approvals": state.missing_human_approvals(&tx_state),
+        "ready_to_execute": ready_to_execute
     })))
 }
 
-async fn execute_transaction(
+// Replaces a signer's earlier signature, e.g. one made over a hash that has since
+// changed. Like the first signature, the replacement must recover to the signer
+// against the current hash.
+async fn resign_transaction(
     State(state): State<Arc<AppState>>,
-    Path(tx_id): Path<String>,
-) -> Result<Json<ExecuteTransactionResponse>, StatusCode> {
-    let mut txs = state.transactions.write().await;
-    let tx_state = txs.get_mut(&tx_id).ok_or(StatusCode::NOT_FOUND)?;
+    TxId(tx_id): TxId,
+    Json(req): Json<SignTransactionRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let _guard = state.mutation_lock.lock().await;
+    let mut tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_api_error)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not found"))?;
 
-    if tx_state.signatures.len() < 4 {
-        return Ok(Json(ExecuteTransactionResponse {
-            tx_hash: String::new(),
-            success: false,
-        }));
+    let signer_addr = Address::from_str(&req.signer_address)
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid signer address"))?;
+
+    if !matches!(
+        tx_state.status,
+        TransactionStatus::CollectingSignatures | TransactionStatus::ReadyToExecute
+    ) {
+        return Err(api_error(
+            StatusCode::CONFLICT,
+            format!(
+                "Transaction is {:?} and no longer collecting signatures",
+                tx_state.status
+            ),
+        ));
     }
 
-    info!(
-        "Executing transaction with {} signatures",
-        tx_state.signatures.len()
-    );
+    let position = tx_state
+        .signatures
+        .iter()
+        .position(|s| s.signer == signer_addr)
+        .ok_or_else(|| {
+            api_error(
+                StatusCode::NOT_FOUND,
+                format!("No signature from {} to replace", signer_addr),
+            )
+        })?;
 
-    // Log who signed
-    for (i, sig) in tx_state.signatures.iter().enumerate() {
-        let signer_type = if sig.signer == state.signer_addresses.human1
-            || sig.signer == state.signer_addresses.human2
-        {
-            "Human"
-        } else if sig.signer == state.signer_addresses.ai_cfo
-            || sig.signer == state.signer_addresses.ai_security
-            || sig.signer == state.signer_addresses.ai_analyst
-        {
-            "AI Agent"
-        } else {
-            "Unknown"
-        };
-        info!("  Signature {}: {} ({})", i + 1, sig.signer, signer_type);
+    let mut signature = hex::decode(req.signature.trim_start_matches("0x"))
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Signature is not valid hex"))?;
+    normalize_v(&mut signature);
+    let hash = B256::from_str(&tx_state.tx_hash).map_err(|_| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Stored Safe tx hash is malformed",
+        )
+    })?;
+    let recovered = recover_signer(hash, &signature).map_err(|e| {
+        warn!("Rejecting replacement signature for {}: {}", tx_id, e);
+        api_error(StatusCode::UNPROCESSABLE_ENTITY, e)
+    })?;
+    if recovered.signer != signer_addr {
+        warn!(
+            "Replacement signature for {} recovers to {}, not {}",
+            tx_id, recovered.signer, signer_addr
+        );
+        return Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Signature does not recover to signer_address",
+        ));
     }
 
-    // Execute transaction on blockchain
-    let tx = &tx_state.transaction;
-    let signatures = tx_state.signatures.clone();
+    info!("Signer {} replaced their signature", signer_addr);
 
-    // Call Safe contract's execTransaction
-    match state
-        .safe_executor
-        .execute_transaction(tx.to, tx.value, tx.data.clone(), signatures)
+    tx_state.signatures[position] = Signature {
+        signer: signer_addr,
+        signature: Bytes::from(signature),
+        recovered: Some(recovered),
+        signed_at: Some(Utc::now()),
+        signed_hash: Some(hash),
+    };
+    if let Err(e) = validate_signer_set(&tx_state.signatures) {
+        warn!("Rejecting replacement signature for {}: {}", tx_id, e);
+        return Err(api_error(StatusCode::UNPROCESSABLE_ENTITY, e));
+    }
+
+    let ready_to_execute = state.can_execute(&tx_state);
+    if ready_to_execute && tx_state.status == TransactionStatus::CollectingSignatures {
+        tx_state
+            .transition(
+                TransactionStatus::CollectingSignatures,
+                TransactionStatus::ReadyToExecute,
+            )
+            .map_err(|e| api_error(StatusCode::CONFLICT, e.to_string()))?;
+        tx_state.ready_at.get_or_insert_with(Utc::now);
+    }
+    state
+        .store
+        .update(&tx_id, &tx_state)
         .await
-    {
+        .map_err(store_api_error)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "replaced": true,
+        "signer_type": state.signer_addresses.signer_type(signer_addr),
+        "current_signatures": tx_state.signatures.len(),
+        "required_signatures": 4,
+        "missing_human_approvals": state.missing_human_approvals(&tx_state),
+        "ready_to_execute": ready_to_execute
+    })))
+}
+
+async fn execute_transaction(
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
+) -> Result<Json<ExecuteTransactionResponse>, ApiError> {
+    // Claim the proposal, then release the lock so RPC round-trips don't block the queue
+    let (tx, signatures, safe_tx_hash, proposer, previous_status) = {
+        let _guard = state.mutation_lock.lock().await;
+        let tx_state = state
+            .store
+            .get(&tx_id)
+            .await
+            .map_err(store_api_error)?
+            .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not found"))?;
+
+        if tx_state.status == TransactionStatus::Executing {
+            return Err(api_error(
+                StatusCode::CONFLICT,
+                "Transaction is already being executed",
+            ));
+        }
+
+        if let PolicyDecision::Blocked(violations) = state.policy_decision(&tx_state) {
+            warn!("Refusing to execute {}: {:?}", tx_id, violations);
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({
+                    "error": "Execution policy not satisfied",
+                    "violations": violations,
+                    "missing_human_approvals": state.missing_human_approvals(&tx_state)
+                })),
+            ));
+        }
+
+        if let Some(executable_at) = state.executable_at(&tx_state) {
+            if Utc::now() < executable_at {
+                return Err((
+                    StatusCode::TOO_EARLY,
+                    Json(serde_json::json!({
+                        "error": "Execution grace period has not elapsed",
+                        "executable_at": executable_at.to_rfc3339()
+                    })),
+                ));
+            }
+        }
+
+        info!(
+            "Executing transaction with {} signatures",
+            tx_state.signatures.len()
+        );
+
+        // Log who signed
+        for (i, sig) in tx_state.signatures.iter().enumerate() {
+            let signer_type = sig.verified_signer().map_or("Unverified", |signer| {
+                state.signer_addresses.signer_type(signer)
+            });
+            info!("  Signature {}: {} ({})", i + 1, sig.signer, signer_type);
+        }
+
+        validate_signer_set(&tx_state.signatures).map_err(|e| {
+            warn!("Refusing to execute {}: {}", tx_id, e);
+            api_error(StatusCode::UNPROCESSABLE_ENTITY, e)
+        })?;
+
+        // Reject malformed signature blobs before they cost gas
+        EncodedSignatures::from_signatures(&tx_state.signatures).map_err(|e| {
+            warn!("Refusing to execute {}: {}", tx_id, e);
+            api_error(StatusCode::UNPROCESSABLE_ENTITY, e)
+        })?;
+
+        // Rejects proposals that can't be executed any more, e.g. cancelled ones
+        let previous_status = tx_state.status;
+        state
+            .store
+            .transition_status(&tx_id, previous_status, TransactionStatus::Executing)
+            .await
+            .map_err(store_api_error)?;
+        (
+            tx_state.transaction.clone(),
+            tx_state.signatures.clone(),
+            tx_state.tx_hash.clone(),
+            tx_state.metadata.proposer,
+            previous_status,
+        )
+    };
+
+    let result = match submit_execution(&state, &tx_id, &tx, &signatures).await {
+        Ok(result) => result,
+        Err(refusal) => {
+            // Nothing was submitted, so the proposal goes back to where it was
+            if let Err(e) = state
+                .store
+                .transition_status(&tx_id, TransactionStatus::Executing, previous_status)
+                .await
+            {
+                error!("Failed to restore status of {}: {}", tx_id, e);
+            }
+            return Err(refusal);
+        }
+    };
+
+    let callback = ExecutionResultNotification {
+        event: "execution_result",
+        tx_id: tx_id.clone(),
+        safe_tx_hash,
+        success: result.is_ok(),
+        transaction_hash: result.as_ref().ok().copied(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    if let Some(proposer) = proposer {
+        let notifier = state.notifier.clone();
+        tokio::spawn(async move { notifier.notify_proposer(proposer, &callback).await });
+    }
+
+    let _guard = state.mutation_lock.lock().await;
+    let mut tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_api_error)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not found"))?;
+
+    match result {
         Ok(tx_hash) => {
-            tx_state.status = TransactionStatus::Executed;
+            // Reconcile may already have recorded the execution
+            if let Err(e) =
+                tx_state.transition(TransactionStatus::Executing, TransactionStatus::Executed)
+            {
+                warn!("Execution of {} confirmed, status left as is: {}", tx_id, e);
+            }
+            tx_state.executed_tx_hash = Some(tx_hash);
+            state
+                .store
+                .update(&tx_id, &tx_state)
+                .await
+                .map_err(store_api_error)?;
             info!(
                 "Transaction executed successfully on blockchain: {}",
                 tx_hash
             );
+            metrics::counter!("transactions_executed_total").increment(1);
 
             Ok(Json(ExecuteTransactionResponse {
                 tx_hash: tx_hash.to_string(),
@@ -463,39 +3056,498 @@ async fn execute_transaction(
         }
         Err(e) => {
             error!("Failed to execute transaction: {}", e);
-            tx_state.status = TransactionStatus::Failed;
+            metrics::counter!("transactions_failed_total").increment(1);
+            if let Err(store_err) = state
+                .store
+                .transition_status(
+                    &tx_id,
+                    TransactionStatus::Executing,
+                    TransactionStatus::Failed,
+                )
+                .await
+            {
+                warn!("Failed to mark {} as failed: {}", tx_id, store_err);
+            }
 
-            Ok(Json(ExecuteTransactionResponse {
-                tx_hash: String::new(),
-                success: false,
-            }))
+            Err((
+                StatusCode::BAD_GATEWAY,
+                Json(serde_json::json!({
+                    "success": false,
+                    "error": e.to_string()
+                })),
+            ))
+        }
+    }
+}
+
+// Pre-flight checks followed by submission. The outer error means execution was
+// refused before anything was sent; the inner result is the on-chain outcome.
+async fn submit_execution(
+    state: &AppState,
+    tx_id: &str,
+    tx: &SafeTransaction,
+    signatures: &[Signature],
+) -> Result<anyhow::Result<B256>, ApiError> {
+    let signatures = &current_owner_signatures(state, tx_id, signatures).await?;
+
+    // Don't overpay during gas spikes; the signed transaction stays executable later.
+    // Compared against what execution would actually pay, not the raw network price.
+    if let Some(ceiling) = state.max_gas_price {
+        let gas_price = state
+            .safe_executor
+            .execution_gas_price()
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch gas price: {}", e);
+                api_error(StatusCode::BAD_GATEWAY, "Failed to fetch gas price")
+            })?;
+
+        if gas_price > ceiling {
+            warn!(
+                "Deferring {}: gas price {} above ceiling {}",
+                tx_id, gas_price, ceiling
+            );
+            return Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(serde_json::json!({
+                    "error": "gas price above ceiling, retry later",
+                    "gas_price": gas_price.to_string(),
+                    "max_gas_price": ceiling.to_string()
+                })),
+            ));
         }
     }
+
+    // Dry-run first: a revert found here costs nothing, one found on-chain costs gas
+    match state.safe_executor.simulate_execution(tx, signatures).await {
+        Ok(SimulationResult::Success) => {}
+        Ok(simulation) => {
+            warn!("Refusing to execute {}: simulation reverted", tx_id);
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({
+                    "error": "Execution would revert",
+                    "simulation": simulation
+                })),
+            ));
+        }
+        Err(e) => {
+            error!("Failed to simulate {} before execution: {}", tx_id, e);
+            return Err(api_error(
+                StatusCode::BAD_GATEWAY,
+                "Failed to simulate execution",
+            ));
+        }
+    }
+
+    // Pre-flight: make sure the relayer can actually pay for execution
+    match state
+        .safe_executor
+        .estimate_execution_cost(tx, signatures, state.gas_multiplier)
+        .await
+    {
+        Ok(cost) => {
+            let balance = state.safe_executor.relayer_balance().await.map_err(|e| {
+                error!("Failed to fetch relayer balance: {}", e);
+                api_error(StatusCode::BAD_GATEWAY, "Failed to fetch relayer balance")
+            })?;
+
+            if balance < cost.total_cost {
+                let shortfall = cost.total_cost - balance;
+                warn!(
+                    "Relayer balance {} below estimated cost {} (shortfall {})",
+                    balance, cost.total_cost, shortfall
+                );
+                return Err((
+                    StatusCode::PAYMENT_REQUIRED,
+                    Json(serde_json::json!({
+                        "error": "Relayer balance too low to execute",
+                        "relayer_balance": balance.to_string(),
+                        "estimated_cost": cost.total_cost.to_string(),
+                        "gas_limit": cost.gas_limit,
+                        "gas_price": cost.gas_price.to_string(),
+                        "shortfall": shortfall.to_string()
+                    })),
+                ));
+            }
+        }
+        // The simulation passed, so this is an RPC hiccup rather than a revert
+        Err(e) => warn!(
+            "Skipping relayer balance pre-flight, gas estimation failed: {}",
+            e
+        ),
+    }
+
+    // Call Safe contract's execTransaction
+    Ok(state
+        .safe_executor
+        .execute_transaction(tx, signatures)
+        .await)
+}
+
+// Owners can change between signing and execution, and the Safe reverts on a
+// signature from a non-owner. Re-reads the owner set (bypassing the registry
+// cache), drops signatures from former owners and refuses if the rest fall
+// below the threshold.
+async fn current_owner_signatures(
+    state: &AppState,
+    tx_id: &str,
+    signatures: &[Signature],
+) -> Result<EncodedSignatures, ApiError> {
+    let valid = owner_signatures(state, tx_id, signatures).await?;
+    EncodedSignatures::from_signatures(&valid).map_err(|e| {
+        warn!("Refusing to execute {}: {}", tx_id, e);
+        api_error(StatusCode::UNPROCESSABLE_ENTITY, e)
+    })
+}
+
+// The signatures execution would actually submit
+async fn owner_signatures(
+    state: &AppState,
+    tx_id: &str,
+    signatures: &[Signature],
+) -> Result<Vec<Signature>, ApiError> {
+    let onchain = state.safe_executor.get_safe_state().await.map_err(|e| {
+        error!("Failed to fetch Safe owners: {}", e);
+        api_error(StatusCode::BAD_GATEWAY, "Failed to fetch Safe owners")
+    })?;
+
+    let (valid, rejected): (Vec<Signature>, Vec<Signature>) =
+        signatures.iter().cloned().partition(|sig| {
+            sig.verified_signer()
+                .is_ok_and(|signer| onchain.owners.contains(&signer))
+        });
+    // Unrecovered signatures are reported under the signer they claim
+    let rejected_signers: Vec<String> = rejected
+        .iter()
+        .map(|sig| sig.verified_signer().unwrap_or(sig.signer).to_string())
+        .collect();
+    if !rejected.is_empty() {
+        warn!(
+            "{}: dropping signatures from signers no longer owners of the Safe: {:?}",
+            tx_id, rejected_signers
+        );
+    }
+
+    if U256::from(valid.len()) < onchain.threshold {
+        warn!(
+            "Refusing to execute {}: {} owner signatures, threshold {}",
+            tx_id,
+            valid.len(),
+            onchain.threshold
+        );
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "error": "Not enough signatures from current Safe owners",
+                "rejected_signers": rejected_signers,
+                "owner_signatures": valid.len(),
+                "threshold": onchain.threshold.to_string()
+            })),
+        ));
+    }
+
+    Ok(valid)
+}
+
+async fn get_batch_preview(
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_api_error)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not found"))?;
+
+    let tx = &tx_state.transaction;
+    if !tx.is_multisend() {
+        return Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Transaction is not a MultiSend batch",
+        ));
+    }
+
+    let calls =
+        decode_multisend(&tx.data).map_err(|e| api_error(StatusCode::UNPROCESSABLE_ENTITY, e))?;
+
+    Ok(Json(serde_json::json!({
+        "tx_id": tx_id,
+        "multisend_address": tx.to.to_string(),
+        "calls": calls
+    })))
+}
+
+async fn simulate_transaction(
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let (tx, signatures) = (tx_state.transaction, tx_state.signatures);
+
+    let encoded = EncodedSignatures::from_signatures(&signatures).map_err(|e| {
+        warn!("Cannot simulate {}: {}", tx_id, e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+
+    let simulation = state
+        .safe_executor
+        .simulate_execution(&tx, &encoded)
+        .await
+        .map_err(|e| {
+            error!("Failed to simulate transaction {}: {}", tx_id, e);
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "tx_id": tx_id,
+        "signatures": signatures.len(),
+        "simulation": simulation
+    })))
+}
+
+// Live updates for one proposal: a snapshot, then signatures and status changes
+async fn subscribe_transaction(
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
+    ws: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    // Subscribe before reading so nothing between the snapshot and the stream is lost
+    let events = state.tx_events.subscribe();
+    let tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let snapshot = TxSnapshot::of(&tx_id, &tx_state);
+
+    Ok(ws.on_upgrade(move |socket| tx_events::stream_transaction(socket, events, snapshot)))
+}
+
+async fn get_exec_encoding(
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
+) -> Result<Json<ExecEncoding>, ApiError> {
+    let mut tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_api_error)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not found"))?;
+
+    // Exactly what execution would submit, so former owners are left out
+    tx_state.signatures = owner_signatures(&state, &tx_id, &tx_state.signatures).await?;
+    let encoded = EncodedSignatures::from_signatures(&tx_state.signatures)
+        .map_err(|e| api_error(StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    let signatures = signature_details(&state, &tx_state);
+
+    Ok(Json(ExecEncoding {
+        safe_address: state.safe_address,
+        safe_tx_hash: tx_state.tx_hash.clone(),
+        signer_order: signatures
+            .iter()
+            .filter_map(|s| Address::from_str(&s.signer).ok())
+            .collect(),
+        signatures_blob: encoded.as_bytes().clone(),
+        calldata: safe_contract_abi::encode_exec_transaction(&tx_state.transaction, &encoded),
+        transaction: tx_state.transaction,
+        signatures,
+        tx_id,
+    }))
+}
+
+async fn get_transaction_signatures(
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
+) -> Result<Json<Vec<SignatureDetail>>, StatusCode> {
+    let tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(signature_details(&state, &tx_state)))
+}
+
+fn signature_details(state: &AppState, tx_state: &TransactionState) -> Vec<SignatureDetail> {
+    // Same ordering EncodedSignatures::from_signatures uses for execution
+    let mut ordered: Vec<(usize, &Signature)> = tx_state.signatures.iter().enumerate().collect();
+    ordered.sort_by_key(|(_, sig)| sig.signer);
+
+    ordered
+        .into_iter()
+        .enumerate()
+        .map(|(position, (received_order, sig))| SignatureDetail {
+            position,
+            received_order,
+            signer: sig.signer.to_string(),
+            signer_type: sig
+                .verified_signer()
+                .map_or("Unverified", |signer| {
+                    state.signer_addresses.signer_type(signer)
+                })
+                .to_string(),
+            signature: sig.signature.to_string(),
+            recovered_signer: sig.recovered.as_ref().map(|r| r.signer.to_string()),
+            v_scheme: sig.recovered.as_ref().map(|r| r.v_scheme),
+            low_s: sig.recovered.as_ref().map(|r| r.low_s),
+            matches_signer: sig.verified_signer() == Ok(sig.signer),
+            signed_at: sig.signed_at,
+            signed_hash: sig.signed_hash,
+            signed_digest: sig
+                .signed_hash
+                .zip(sig.recovered.as_ref())
+                .map(|(hash, r)| signed_digest(hash, r.v_scheme)),
+            matches_current_hash: sig
+                .signed_hash
+                .map(|hash| hash.to_string() == tx_state.tx_hash),
+        })
+        .collect()
+}
+
+async fn get_execution_report(
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
+) -> Result<Json<ExecutionReport>, ApiError> {
+    let tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_api_error)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not found"))?;
+
+    let executed_tx_hash = tx_state.executed_tx_hash.ok_or_else(|| {
+        api_error(
+            StatusCode::CONFLICT,
+            "Transaction has not been executed on-chain",
+        )
+    })?;
+
+    let receipt = state
+        .safe_executor
+        .execution_receipt(executed_tx_hash)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch receipt for {}: {}", executed_tx_hash, e);
+            api_error(StatusCode::BAD_GATEWAY, "Failed to fetch execution receipt")
+        })?;
+
+    Ok(Json(ExecutionReport {
+        safe_address: state.safe_address,
+        safe_tx_hash: tx_state.tx_hash.clone(),
+        content_hash: tx_state.transaction.content_hash(),
+        signatures: signature_details(&state, &tx_state),
+        transaction: tx_state.transaction,
+        metadata: tx_state.metadata,
+        status: tx_state.status,
+        created_at: tx_state.created_at,
+        ready_at: tx_state.ready_at,
+        ai_analysis: tx_state.ai_analysis,
+        receipt,
+        generated_at: Utc::now(),
+        tx_id,
+    }))
+}
+
+async fn get_agent_rejections(
+    State(state): State<Arc<AppState>>,
+    Path(agent): Path<String>,
+) -> Result<Json<Vec<AgentRejection>>, StatusCode> {
+    let agent_name = ai_analysis::agent_name_for_role(&agent).ok_or(StatusCode::NOT_FOUND)?;
+
+    let txs = state.store.list().await.map_err(store_error)?;
+    let mut rejections: Vec<(DateTime<Utc>, AgentRejection)> = txs
+        .iter()
+        .filter_map(|(tx_id, tx_state)| {
+            let analysis = tx_state.ai_analysis.as_ref()?;
+            let verdict = analysis
+                .per_agent
+                .iter()
+                .find(|v| v.agent == agent_name && !v.approved)?;
+
+            Some((
+                tx_state.created_at,
+                AgentRejection {
+                    tx_id: tx_id.clone(),
+                    safe_tx_hash: tx_state.tx_hash.clone(),
+                    status: tx_state.status,
+                    risk_score: verdict.risk_score,
+                    reason: verdict.reason.clone(),
+                    reason_codes: verdict.reason_codes.clone(),
+                    analyzed_at: analysis.analyzed_at.clone(),
+                },
+            ))
+        })
+        .collect();
+    rejections.sort_by_key(|(created_at, _)| *created_at);
+
+    Ok(Json(rejections.into_iter().map(|(_, r)| r).collect()))
+}
+
+async fn get_notification_preferences(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<NotificationPreferences>, StatusCode> {
+    let signer = Address::from_str(&address).map_err(|_| StatusCode::BAD_REQUEST)?;
+    notifications::authenticate_signer(&headers, signer)?;
+
+    Ok(Json(state.notifier.preferences(signer).await))
+}
+
+async fn update_notification_preferences(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+    headers: HeaderMap,
+    Json(prefs): Json<NotificationPreferences>,
+) -> Result<Json<NotificationPreferences>, StatusCode> {
+    let signer = Address::from_str(&address).map_err(|_| StatusCode::BAD_REQUEST)?;
+    notifications::authenticate_signer(&headers, signer)?;
+
+    if !state.signer_addresses.all().contains(&signer) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if let Some(url) = &prefs.webhook_url {
+        notifications::validate_webhook_url(url)
+            .await
+            .map_err(|e| {
+                warn!("Rejecting webhook for {}: {}", signer, e);
+                StatusCode::UNPROCESSABLE_ENTITY
+            })?;
+    }
+
+    info!("Updated notification preferences for {}", signer);
+    state.notifier.set_preferences(signer, prefs.clone()).await;
+    Ok(Json(prefs))
 }
 
 async fn get_transaction_status(
     State(state): State<Arc<AppState>>,
-    Path(tx_id): Path<String>,
+    TxId(tx_id): TxId,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let txs = state.transactions.read().await;
-    let tx_state = txs.get(&tx_id).ok_or(StatusCode::NOT_FOUND)?;
+    let tx_state = state
+        .store
+        .get(&tx_id)
+        .await
+        .map_err(store_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let tx_state = expire_on_read(&state, &tx_id, tx_state).await?;
 
     let signers: Vec<serde_json::Value> = tx_state
         .signatures
         .iter()
         .map(|s| {
-            let signer_type = if s.signer == state.signer_addresses.human1
-                || s.signer == state.signer_addresses.human2
-            {
-                "Human"
-            } else if s.signer == state.signer_addresses.ai_cfo
-                || s.signer == state.signer_addresses.ai_security
-                || s.signer == state.signer_addresses.ai_analyst
-            {
-                "AI Agent"
-            } else {
-                "Unknown"
-            };
+            let signer_type = s.verified_signer().map_or("Unverified", |signer| {
+                state.signer_addresses.signer_type(signer)
+            });
 
             serde_json::json!({
                 "address": s.signer.to_string(),
@@ -509,34 +3561,98 @@ async fn get_transaction_status(
         "status": tx_state.status,
         "signatures_collected": tx_state.signatures.len(),
         "required_signatures": 4,
-        "signers": signers
+        "signers": signers,
+        "executable_at": state.executable_at(&tx_state).map(|t| t.to_rfc3339()),
+        "admin_operation": tx_state.metadata.admin_operation,
+        "missing_human_approvals": state.missing_human_approvals(&tx_state),
+        "policy": state.policy_decision(&tx_state),
+        "deadline": tx_state.deadline.map(|t| t.to_rfc3339()),
+        "deadline_extensions": tx_state.deadline_extensions,
+        "invalidation_reason": tx_state.invalidation_reason
     })))
 }
 
 async fn ai_analyze_transaction(
-    State(_state): State<Arc<AppState>>,
-    Path(tx_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    TxId(tx_id): TxId,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    // Simulate AI agent analysis
+    let analysis = refresh_ai_analysis(&state, &tx_id).await?;
+    let recommendation = analysis.recommendation();
+
     Ok(Json(serde_json::json!({
         "tx_id": tx_id,
-        "analysis": {
-            "cfo_agent": {
-                "approved": true,
-                "reason": "Transaction within budget limits",
-                "risk_score": 0.2
-            },
-            "security_agent": {
-                "approved": true,
-                "reason": "Recipient address not in blacklist",
-                "risk_score": 0.1
-            },
-            "analyst_agent": {
-                "approved": true,
-                "reason": "Standard transfer, no complex interactions",
-                "risk_score": 0.15
-            }
-        },
-        "recommendation": "Safe to execute"
+        "analysis": analysis,
+        "recommendation": recommendation
     })))
 }
+
+async fn verify_signature(
+    Json(req): Json<VerifySignatureRequest>,
+) -> Result<Json<VerifySignatureResponse>, StatusCode> {
+    let hash = B256::from_str(&req.hash).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let signature =
+        hex::decode(req.signature.trim_start_matches("0x")).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let expected_signer = req
+        .expected_signer
+        .map(|s| Address::from_str(&s))
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let recovered = recover_signer(hash, &signature).map_err(|e| {
+        info!("Signature verification failed: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    Ok(Json(VerifySignatureResponse {
+        recovered_signer: recovered.signer.to_string(),
+        low_s: recovered.low_s,
+        v_scheme: recovered.v_scheme,
+        matches_expected: expected_signer.map(|addr| addr == recovered.signer),
+    }))
+}
+
+async fn get_queue_metrics(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<QueueMetricsQuery>,
+) -> Result<Json<QueueMetricsResponse>, StatusCode> {
+    let stale_after_secs = query
+        .stale_after_secs
+        .unwrap_or(state.queue_stale_after_secs);
+    let now = Utc::now();
+    let txs = state.store.list().await.map_err(store_error)?;
+
+    let pending_ages: Vec<i64> = txs
+        .iter()
+        .map(|(_, tx)| tx)
+        .filter(|tx| matches!(tx.status, TransactionStatus::CollectingSignatures))
+        .map(|tx| (now - tx.created_at).num_seconds())
+        .collect();
+
+    let mut collection_times: Vec<i64> = txs
+        .iter()
+        .map(|(_, tx)| tx)
+        .filter_map(|tx| {
+            tx.ready_at
+                .map(|ready| (ready - tx.created_at).num_seconds())
+        })
+        .collect();
+    collection_times.sort_unstable();
+
+    let median_collection_secs = match collection_times.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(collection_times[n / 2]),
+        n => Some((collection_times[n / 2 - 1] + collection_times[n / 2]) / 2),
+    };
+
+    Ok(Json(QueueMetricsResponse {
+        pending_count: pending_ages.len(),
+        stale_after_secs,
+        stale_count: pending_ages
+            .iter()
+            .filter(|age| **age > stale_after_secs)
+            .count(),
+        oldest_pending_age_secs: pending_ages.iter().copied().max(),
+        median_collection_secs,
+    }))
+}
@@ -0,0 +1,299 @@
+use alloy_primitives::{Address, Signature as EcdsaSignature, B256, U256};
+use axum::http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+    time::Duration,
+};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+// Signed preference requests older than this are rejected to limit replay
+const AUTH_MAX_AGE_SECS: i64 = 300;
+
+// Proposer callbacks are retried with exponential backoff starting at this delay
+const CALLBACK_ATTEMPTS: u32 = 4;
+const CALLBACK_BASE_DELAY_MS: u64 = 500;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub min_value: Option<U256>, // Skip proposals moving less than this (wei)
+    #[serde(default)]
+    pub tags: Vec<String>, // Only proposals carrying one of these tags; empty means all
+    pub webhook_url: Option<String>, // Falls back to NOTIFICATION_WEBHOOK_URL
+}
+
+impl NotificationPreferences {
+    pub fn wants(&self, value: U256, tags: &[String]) -> bool {
+        if self.min_value.is_some_and(|floor| value < floor) {
+            return false;
+        }
+        self.tags.is_empty() || self.tags.iter().any(|t| tags.contains(t))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProposalNotification {
+    pub event: &'static str,
+    pub tx_id: String,
+    pub safe_tx_hash: String,
+    pub to: Address,
+    pub value: U256,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionResultNotification {
+    pub event: &'static str,
+    pub tx_id: String,
+    pub safe_tx_hash: String,
+    pub success: bool,
+    pub transaction_hash: Option<B256>,
+    pub error: Option<String>,
+}
+
+pub struct Notifier {
+    client: reqwest::Client,
+    default_webhook: Option<String>,
+    alert_webhook: Option<String>, // Operator alerts, e.g. on-chain owner drift
+    preferences: RwLock<HashMap<Address, NotificationPreferences>>,
+}
+
+impl Notifier {
+    pub fn new(default_webhook: Option<String>, alert_webhook: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            default_webhook,
+            alert_webhook,
+            preferences: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn preferences(&self, signer: Address) -> NotificationPreferences {
+        self.preferences
+            .read()
+            .await
+            .get(&signer)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub async fn set_preferences(&self, signer: Address, prefs: NotificationPreferences) {
+        self.preferences.write().await.insert(signer, prefs);
+    }
+
+    // Sends the notification to every signer whose preferences match it
+    pub async fn notify_signers(&self, signers: &[Address], notification: &ProposalNotification) {
+        for signer in signers {
+            let prefs = self.preferences(*signer).await;
+            if !prefs.wants(notification.value, &notification.tags) {
+                continue;
+            }
+
+            let Some(url) = prefs.webhook_url.as_ref().or(self.default_webhook.as_ref()) else {
+                continue;
+            };
+
+            let body = serde_json::json!({
+                "recipient": signer,
+                "notification": notification,
+            });
+            match self.client.post(url).json(&body).send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    info!("Notified {} of {}", signer, notification.tx_id)
+                }
+                Ok(resp) => warn!("Webhook for {} returned {}", signer, resp.status()),
+                Err(e) => warn!("Failed to notify {}: {}", signer, e),
+            }
+        }
+    }
+
+    // Best-effort post to ALERT_WEBHOOK_URL; alerts are always logged by the caller
+    pub async fn send_alert(&self, alert: &impl Serialize) {
+        let Some(url) = &self.alert_webhook else {
+            return;
+        };
+        match self.client.post(url).json(alert).send().await {
+            Ok(resp) if resp.status().is_success() => {}
+            Ok(resp) => warn!("Alert webhook returned {}", resp.status()),
+            Err(e) => warn!("Failed to send alert: {}", e),
+        }
+    }
+
+    // Tells the proposer how execution went, if they registered a webhook
+    pub async fn notify_proposer(
+        &self,
+        proposer: Address,
+        notification: &ExecutionResultNotification,
+    ) {
+        let Some(url) = self.preferences(proposer).await.webhook_url else {
+            return;
+        };
+
+        for attempt in 1..=CALLBACK_ATTEMPTS {
+            match self.client.post(&url).json(notification).send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    info!(
+                        "Sent execution result for {} to proposer {}",
+                        notification.tx_id, proposer
+                    );
+                    return;
+                }
+                Ok(resp) => warn!(
+                    "Proposer callback attempt {} returned {}",
+                    attempt,
+                    resp.status()
+                ),
+                Err(e) => warn!("Proposer callback attempt {} failed: {}", attempt, e),
+            }
+
+            if attempt < CALLBACK_ATTEMPTS {
+                let delay = CALLBACK_BASE_DELAY_MS << (attempt - 1);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+        }
+
+        warn!(
+            "Giving up on execution callback for {} to {}",
+            notification.tx_id, proposer
+        );
+    }
+}
+
+// Signers prove who they are by personal_sign-ing
+// "sentinel-safe notification preferences <address> <timestamp>" and sending the
+// signature and timestamp in the x-signer-signature / x-signer-timestamp headers
+pub fn authenticate_signer(headers: &HeaderMap, signer: Address) -> Result<(), StatusCode> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or(StatusCode::UNAUTHORIZED)
+    };
+
+    let timestamp: i64 = header("x-signer-timestamp")?
+        .parse()
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if (chrono::Utc::now().timestamp() - timestamp).abs() > AUTH_MAX_AGE_SECS {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let signature = EcdsaSignature::from_str(header("x-signer-signature")?)
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let message = format!(
+        "sentinel-safe notification preferences {} {}",
+        signer.to_checksum(None),
+        timestamp
+    );
+    let recovered = signature
+        .recover_address_from_msg(message)
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    if recovered != signer {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+// Webhooks are posted to from inside the deployment, so a signer-supplied URL
+// must not reach internal services: https only, and every address the host
+// resolves to has to be public
+pub async fn validate_webhook_url(raw: &str) -> Result<(), String> {
+    let url = reqwest::Url::parse(raw).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    if url.scheme() != "https" {
+        return Err("Webhook URL must use https".to_string());
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| "Webhook URL has no host".to_string())?;
+
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<IpAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => {
+            let lowered = host.to_ascii_lowercase();
+            if lowered == "localhost" || lowered.ends_with(".localhost") {
+                return Err(format!("Webhook host {} is not public", host));
+            }
+            tokio::net::lookup_host((host, url.port_or_known_default().unwrap_or(443)))
+                .await
+                .map_err(|e| format!("Webhook host {} does not resolve: {}", host, e))?
+                .map(|addr| addr.ip())
+                .collect()
+        }
+    };
+
+    match addresses.iter().find(|ip| !is_public(**ip)) {
+        Some(ip) => Err(format!(
+            "Webhook host {} resolves to non-public {}",
+            host, ip
+        )),
+        None if addresses.is_empty() => Err(format!("Webhook host {} does not resolve", host)),
+        None => Ok(()),
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_v4(v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(v6),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))) // Carrier-grade NAT
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || (first & 0xfe00) == 0xfc00 // Unique local
+        || (first & 0xffc0) == 0xfe80) // Link-local
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn webhook_url_must_be_https() {
+        assert!(validate_webhook_url("http://93.184.216.34/hook")
+            .await
+            .is_err());
+        assert!(validate_webhook_url("https://93.184.216.34/hook")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn webhook_url_rejects_internal_hosts() {
+        for url in [
+            "https://127.0.0.1/hook",
+            "https://localhost:8443/hook",
+            "https://10.0.0.5/hook",
+            "https://192.168.1.1/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://100.64.0.1/hook",
+            "https://[::1]/hook",
+            "https://[fe80::1]/hook",
+            "https://[fd00::1]/hook",
+            "https://[::ffff:127.0.0.1]/hook",
+        ] {
+            assert!(validate_webhook_url(url).await.is_err(), "{} accepted", url);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{sol, Eip712Domain, SolStruct};
+use shared::signature::{recover_signer, VScheme};
+use std::borrow::Cow;
+
+// Proposals older (or further in the future) than this are treated as replays
+const PROPOSAL_MAX_AGE_SECS: u64 = 600;
+
+sol! {
+    // Signed by whoever creates a proposal. It only proves authorship; Safe
+    // approvals still go through the SafeTx signing flow.
+    struct Proposal {
+        address safe;
+        address to;
+        uint256 value;
+        bytes data;
+        address proposer;
+        uint256 timestamp;
+    }
+}
+
+// Named domain so a Proposal signature can never be confused with a SafeTx one
+pub fn proposal_domain(chain_id: u64, safe_address: Address) -> Eip712Domain {
+    Eip712Domain::new(
+        Some(Cow::Borrowed("Sentinel Safe Proposal")),
+        Some(Cow::Borrowed("1")),
+        Some(U256::from(chain_id)),
+        Some(safe_address),
+        None,
+    )
+}
+
+pub struct ProposalClaim {
+    pub safe: Address,
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub proposer: Address,
+    pub timestamp: i64,
+}
+
+impl ProposalClaim {
+    // Checks the proposer signed exactly these parameters via eth_signTypedData_v4
+    pub fn verify(&self, chain_id: u64, signature: &[u8]) -> Result<(), String> {
+        // abs_diff can't overflow, whatever timestamp the client sent
+        let skew = chrono::Utc::now().timestamp().abs_diff(self.timestamp);
+        if skew > PROPOSAL_MAX_AGE_SECS {
+            return Err(format!("Proposal timestamp is {}s off", skew));
+        }
+
+        let proposal = Proposal {
+            safe: self.safe,
+            to: self.to,
+            value: self.value,
+            data: self.data.clone(),
+            proposer: self.proposer,
+            timestamp: U256::from(self.timestamp),
+        };
+        let hash = proposal.eip712_signing_hash(&proposal_domain(chain_id, self.safe));
+
+        let recovered = recover_signer(hash, signature)?;
+        if recovered.v_scheme != VScheme::TypedData {
+            return Err("Proposal must be signed as EIP-712 typed data".to_string());
+        }
+        if recovered.signer != self.proposer {
+            return Err(format!(
+                "Proposal signed by {}, not the claimed proposer {}",
+                recovered.signer, self.proposer
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::{local::PrivateKeySigner, SignerSync};
+    use alloy_primitives::B256;
+
+    const CHAIN_ID: u64 = 1001;
+
+    fn key(n: u8) -> PrivateKeySigner {
+        PrivateKeySigner::from_bytes(&B256::with_last_byte(n)).unwrap()
+    }
+
+    fn claim(proposer: Address, timestamp: i64) -> ProposalClaim {
+        ProposalClaim {
+            safe: Address::repeat_byte(0x5a),
+            to: Address::repeat_byte(0x11),
+            value: U256::from(1_000u64),
+            data: Bytes::new(),
+            proposer,
+            timestamp,
+        }
+    }
+
+    fn signing_hash(claim: &ProposalClaim) -> B256 {
+        Proposal {
+            safe: claim.safe,
+            to: claim.to,
+            value: claim.value,
+            data: claim.data.clone(),
+            proposer: claim.proposer,
+            timestamp: U256::from(claim.timestamp),
+        }
+        .eip712_signing_hash(&proposal_domain(CHAIN_ID, claim.safe))
+    }
+
+    #[test]
+    fn proposer_typed_data_signature_verifies() {
+        let claim = claim(key(1).address(), chrono::Utc::now().timestamp());
+        let signature = key(1).sign_hash_sync(&signing_hash(&claim)).unwrap();
+
+        assert_eq!(claim.verify(CHAIN_ID, &signature.as_bytes()), Ok(()));
+    }
+
+    #[test]
+    fn signature_from_someone_else_is_rejected() {
+        let claim = claim(key(1).address(), chrono::Utc::now().timestamp());
+        let forged = key(2).sign_hash_sync(&signing_hash(&claim)).unwrap();
+
+        let err = claim.verify(CHAIN_ID, &forged.as_bytes()).unwrap_err();
+        assert!(err.contains("not the claimed proposer"), "{}", err);
+    }
+
+    #[test]
+    fn signature_for_other_parameters_is_rejected() {
+        let signed = claim(key(1).address(), chrono::Utc::now().timestamp());
+        let signature = key(1).sign_hash_sync(&signing_hash(&signed)).unwrap();
+        let mut tampered = claim(key(1).address(), signed.timestamp);
+        tampered.value = U256::from(1_000_000u64);
+
+        assert!(tampered.verify(CHAIN_ID, &signature.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn eth_sign_signature_is_the_wrong_scheme() {
+        let claim = claim(key(1).address(), chrono::Utc::now().timestamp());
+        let mut signature = key(1)
+            .sign_message_sync(signing_hash(&claim).as_slice())
+            .unwrap()
+            .as_bytes();
+        signature[64] += 4;
+
+        let err = claim.verify(CHAIN_ID, &signature).unwrap_err();
+        assert!(err.contains("EIP-712"), "{}", err);
+    }
+
+    #[test]
+    fn stale_and_extreme_timestamps_are_rejected_without_overflow() {
+        let now = chrono::Utc::now().timestamp();
+        for timestamp in [now - 3_600, now + 3_600, i64::MIN, i64::MIN + 1, i64::MAX] {
+            let claim = claim(key(1).address(), timestamp);
+            let err = claim.verify(CHAIN_ID, &[0u8; 65]).unwrap_err();
+            assert!(err.contains("timestamp"), "{}: {}", timestamp, err);
+        }
+    }
+}
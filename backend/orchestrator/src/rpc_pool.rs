@@ -0,0 +1,333 @@
+use alloy::{
+    providers::{MulticallError, PendingTransactionError, RootProvider},
+    transports::{http::reqwest::Url, RpcError, TransportError},
+};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use shared::observability;
+use std::{
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+// A failed endpoint is skipped for this long before the pool tries it again
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
+pub const DEFAULT_RPC_MAX_ATTEMPTS: u32 = 3;
+pub const DEFAULT_RPC_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Default)]
+struct EndpointState {
+    consecutive_failures: u32,
+    last_failure: Option<Instant>,
+    last_error: Option<String>,
+}
+
+// The provider is built once and cloned per call, so its HTTP client and
+// connection pool are shared across requests
+struct Endpoint {
+    url: Url,
+    provider: RootProvider,
+    state: Mutex<EndpointState>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EndpointHealth {
+    pub url: String,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+// Prioritized RPC endpoints: requests go to the first healthy one and fail over
+// down the list on transport errors. A failed endpoint is retried after a
+// cooldown, so traffic returns to the primary once it recovers. When every
+// endpoint fails, the whole round is retried with exponential backoff.
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    max_attempts: u32,
+    retry_base_delay: Duration,
+}
+
+impl RpcPool {
+    pub fn new(urls: &[String]) -> Result<Self> {
+        let endpoints = urls
+            .iter()
+            .map(|u| u.trim())
+            .filter(|u| !u.is_empty())
+            .map(|u| {
+                let url: Url = u.parse()?;
+                Ok(Endpoint {
+                    provider: RootProvider::new_http(url.clone()),
+                    url,
+                    state: Mutex::new(EndpointState::default()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if endpoints.is_empty() {
+            return Err(anyhow!("At least one RPC URL is required"));
+        }
+        Ok(Self {
+            endpoints,
+            max_attempts: DEFAULT_RPC_MAX_ATTEMPTS,
+            retry_base_delay: DEFAULT_RPC_RETRY_BASE_DELAY,
+        })
+    }
+
+    // Rounds over the endpoint list before giving up (at least one), and the
+    // delay before the second; each further round waits twice as long
+    pub fn with_retry(mut self, max_attempts: u32, base_delay: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let state = endpoint.state.lock().unwrap_or_else(|e| e.into_inner());
+                EndpointHealth {
+                    url: endpoint.url.to_string(),
+                    healthy: state.consecutive_failures == 0,
+                    consecutive_failures: state.consecutive_failures,
+                    last_error: state.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+
+    // Provider for calls that must reach the chain at most once, i.e. broadcasts:
+    // the first endpoint not cooling down, with no retry or failover. A timeout
+    // after submission can't tell whether the node accepted the transaction.
+    pub fn primary(&self) -> RootProvider {
+        self.primary_endpoint().provider.clone()
+    }
+
+    fn primary_endpoint(&self) -> &Endpoint {
+        self.endpoints
+            .iter()
+            .find(|e| e.is_available())
+            .unwrap_or(&self.endpoints[0])
+    }
+
+    // Runs `op` against endpoints in priority order until one doesn't fail at
+    // the transport level (connection errors, timeouts, HTTP 429/5xx). RPC-level
+    // answers (reverts, errors) are permanent and returned as-is. `op` may run
+    // several times, so it must be a read; broadcasts go through `primary`.
+    pub async fn run<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: Fn(RootProvider) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let error = match self.run_round(&op).await {
+                Err(e) if is_transport_failure(&e) && attempt < self.max_attempts => e,
+                result => return result,
+            };
+            let delay = self.retry_base_delay * 2u32.saturating_pow(attempt - 1);
+            warn!(
+                "All RPC endpoints failed (attempt {}/{}), retrying in {:?}: {}",
+                attempt, self.max_attempts, delay, error
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn run_round<T, F, Fut>(&self, op: &F) -> Result<T>
+    where
+        F: Fn(RootProvider) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut candidates: Vec<&Endpoint> =
+            self.endpoints.iter().filter(|e| e.is_available()).collect();
+        // Everything is cooling down; trying beats failing without a request
+        if candidates.is_empty() {
+            candidates = self.endpoints.iter().collect();
+        }
+
+        let mut last_error = None;
+        for endpoint in candidates {
+            let started = Instant::now();
+            let result = op(endpoint.provider.clone()).await;
+            // Reverts and RPC errors are answers; only transport failures count as errors
+            observability::record_rpc(
+                "safe_rpc",
+                started.elapsed(),
+                !matches!(&result, Err(e) if is_transport_failure(e)),
+            );
+            match result {
+                Ok(value) => {
+                    endpoint.record_success();
+                    return Ok(value);
+                }
+                Err(e) if is_transport_failure(&e) => {
+                    warn!("RPC endpoint {} failed, failing over: {}", endpoint.url, e);
+                    endpoint.record_failure(&e);
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    endpoint.record_success();
+                    return Err(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("No RPC endpoint available")))
+    }
+}
+
+impl Endpoint {
+    fn is_available(&self) -> bool {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .last_failure
+            .is_none_or(|at| state.consecutive_failures == 0 || at.elapsed() >= UNHEALTHY_COOLDOWN)
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures = 0;
+    }
+
+    fn record_failure(&self, error: &anyhow::Error) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures += 1;
+        state.last_failure = Some(Instant::now());
+        state.last_error = Some(error.to_string());
+    }
+}
+
+// Only connection-level failures justify another endpoint; an RPC error
+// response would come back the same from any node
+fn is_transport_failure(error: &anyhow::Error) -> bool {
+    let rpc_error: Option<&TransportError> = if let Some(e) = error.downcast_ref::<TransportError>()
+    {
+        Some(e)
+    } else if let Some(alloy::contract::Error::TransportError(e)) = error.downcast_ref() {
+        Some(e)
+    } else if let Some(PendingTransactionError::TransportError(e)) = error.downcast_ref() {
+        Some(e)
+    } else if let Some(MulticallError::TransportError(e)) = error.downcast_ref() {
+        Some(e)
+    } else {
+        None
+    };
+
+    matches!(rpc_error, Some(RpcError::Transport(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::transports::TransportErrorKind;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn pool(endpoints: usize, max_attempts: u32, base_delay: Duration) -> RpcPool {
+        let urls: Vec<String> = (0..endpoints)
+            .map(|i| format!("http://127.0.0.1:{}", 9 + i))
+            .collect();
+        RpcPool::new(&urls)
+            .unwrap()
+            .with_retry(max_attempts, base_delay)
+    }
+
+    fn transport_error() -> anyhow::Error {
+        TransportErrorKind::custom_str("connection reset").into()
+    }
+
+    #[tokio::test]
+    async fn transport_failures_are_retried_with_exponential_backoff() {
+        let pool = pool(1, 3, Duration::from_millis(40));
+        let calls = AtomicU32::new(0);
+
+        let started = Instant::now();
+        let result: Result<()> = pool
+            .run(|_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err(transport_error()) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        // 40ms before the second round, 80ms before the third
+        assert!(started.elapsed() >= Duration::from_millis(120));
+    }
+
+    #[tokio::test]
+    async fn transient_failure_then_success() {
+        let pool = pool(1, 3, Duration::from_millis(1));
+        let calls = AtomicU32::new(0);
+
+        let result = pool
+            .run(|_| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if call < 2 {
+                        Err(transport_error())
+                    } else {
+                        Ok(call)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn rpc_errors_are_not_retried() {
+        let pool = pool(2, 3, Duration::from_millis(1));
+        let calls = AtomicU32::new(0);
+
+        let result: Result<()> = pool
+            .run(|_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err(anyhow!("execution reverted: GS026")) }
+            })
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("GS026"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(pool.health().iter().all(|e| e.healthy));
+    }
+
+    #[tokio::test]
+    async fn reads_fail_over_to_the_secondary() {
+        let pool = pool(2, 1, Duration::ZERO);
+        let calls = AtomicU32::new(0);
+
+        let result = pool
+            .run(|_| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if call == 0 {
+                        Err(transport_error())
+                    } else {
+                        Ok("served by secondary")
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), "served by secondary");
+        let health = pool.health();
+        assert!(!health[0].healthy);
+        assert!(health[1].healthy);
+    }
+
+    #[tokio::test]
+    async fn broadcasts_use_one_endpoint_and_skip_failed_ones() {
+        let pool = pool(2, 1, Duration::ZERO);
+        assert_eq!(pool.primary_endpoint().url.port(), Some(9));
+
+        pool.endpoints[0].record_failure(&transport_error());
+        assert_eq!(pool.primary_endpoint().url.port(), Some(10));
+    }
+}
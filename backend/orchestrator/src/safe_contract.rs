@@ -1,12 +1,51 @@
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolStruct};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shared::{
+    signature::RecoveredSignature,
+    utils::{self, SafeTx},
+};
+
+sol! {
+    function multiSend(bytes transactions);
+}
+
+// Safe `Enum.Operation`: serialized as its integer value, anything else is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Operation {
+    Call,
+    DelegateCall,
+}
+
+impl From<Operation> for u8 {
+    fn from(operation: Operation) -> Self {
+        match operation {
+            Operation::Call => 0,
+            Operation::DelegateCall => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Operation {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Operation::Call),
+            1 => Ok(Operation::DelegateCall),
+            other => Err(format!("Invalid Safe operation: {}", other)),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafeTransaction {
     pub to: Address,
     pub value: U256,
     pub data: Bytes,
-    pub operation: u8,
+    pub operation: Operation,
     pub safe_tx_gas: U256,
     pub base_gas: U256,
     pub gas_price: U256,
@@ -19,18 +58,538 @@ pub struct SafeTransaction {
 pub struct Signature {
     pub signer: Address,
     pub signature: Bytes,
+    // Recovered once when the signature is ingested so reads never re-run ecrecover
+    #[serde(default)]
+    pub recovered: Option<RecoveredSignature>,
+    #[serde(default)]
+    pub signed_at: Option<DateTime<Utc>>, // When the orchestrator accepted it
+    #[serde(default)]
+    pub signed_hash: Option<B256>, // Safe tx hash it was verified against on submission
+}
+
+impl Signature {
+    // The address that actually produced the signature. A signature that was
+    // never recovered only has the signer the client claimed, which isn't trusted.
+    pub fn verified_signer(&self) -> Result<Address, String> {
+        self.recovered.as_ref().map(|r| r.signer).ok_or_else(|| {
+            format!(
+                "Signature claimed by {} was never recovered to a signer",
+                self.signer
+            )
+        })
+    }
+}
+
+const ECDSA_SIGNATURE_LENGTH: usize = 65;
+
+// Distinct keys can still be crafted to recover to one owner, so the whole set is
+// rejected if any recovered signer repeats or is the zero address
+pub fn validate_signer_set(signatures: &[Signature]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    for sig in signatures {
+        let signer = sig.verified_signer()?;
+        if signer == Address::ZERO {
+            return Err(format!(
+                "Signature claimed by {} recovers to the zero address",
+                sig.signer
+            ));
+        }
+        if !seen.insert(signer) {
+            return Err(format!(
+                "Duplicate recovered signer {} in signature set",
+                signer
+            ));
+        }
+    }
+    Ok(())
+}
+
+// One sub-call of a MultiSend batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiSendCall {
+    pub operation: Operation,
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+// Unpacks multiSend(bytes) calldata: each entry is
+// operation (1) || to (20) || value (32) || data length (32) || data
+pub fn decode_multisend(calldata: &[u8]) -> Result<Vec<MultiSendCall>, String> {
+    let call =
+        multiSendCall::abi_decode(calldata).map_err(|e| format!("Not a multiSend call: {}", e))?;
+    let packed = call.transactions.as_ref();
+
+    let mut calls = Vec::new();
+    let mut cursor = 0;
+    while cursor < packed.len() {
+        let header_end = cursor + 1 + 20 + 32 + 32;
+        if header_end > packed.len() {
+            return Err(format!("Truncated MultiSend entry at byte {}", cursor));
+        }
+
+        let operation = Operation::try_from(packed[cursor])?;
+        let to = Address::from_slice(&packed[cursor + 1..cursor + 21]);
+        let value = U256::from_be_slice(&packed[cursor + 21..cursor + 53]);
+        let data_len = usize::try_from(U256::from_be_slice(&packed[cursor + 53..header_end]))
+            .map_err(|_| "MultiSend data length out of range".to_string())?;
+
+        let data_end = header_end
+            .checked_add(data_len)
+            .filter(|end| *end <= packed.len())
+            .ok_or_else(|| format!("Truncated MultiSend data at byte {}", header_end))?;
+
+        calls.push(MultiSendCall {
+            operation,
+            to,
+            value,
+            data: Bytes::copy_from_slice(&packed[header_end..data_end]),
+        });
+        cursor = data_end;
+    }
+
+    Ok(calls)
+}
+
+// Inverse of decode_multisend: full multiSend(bytes) calldata for the batch
+pub fn encode_multisend(calls: &[MultiSendCall]) -> Bytes {
+    let mut packed = Vec::new();
+    for call in calls {
+        packed.push(u8::from(call.operation));
+        packed.extend_from_slice(call.to.as_slice());
+        packed.extend_from_slice(&call.value.to_be_bytes::<32>());
+        packed.extend_from_slice(&U256::from(call.data.len()).to_be_bytes::<32>());
+        packed.extend_from_slice(&call.data);
+    }
+    multiSendCall {
+        transactions: packed.into(),
+    }
+    .abi_encode()
+    .into()
+}
+
+// Bundles sub-calls into one Safe transaction that delegatecalls the MultiSend
+// contract, so they execute atomically under a single set of signatures
+pub struct MultiSendBuilder {
+    multisend: Address,
+    calls: Vec<MultiSendCall>,
+}
+
+impl MultiSendBuilder {
+    pub fn new(
+        multisend: Address,
+        calls: impl IntoIterator<Item = (Operation, Address, U256, Bytes)>,
+    ) -> Self {
+        Self {
+            multisend,
+            calls: calls
+                .into_iter()
+                .map(|(operation, to, value, data)| MultiSendCall {
+                    operation,
+                    to,
+                    value,
+                    data,
+                })
+                .collect(),
+        }
+    }
+
+    // Value travels inside the sub-calls; the outer delegatecall carries none.
+    // Nonce, gas and refund fields are left at zero for the caller to fill in.
+    pub fn build(&self) -> SafeTransaction {
+        SafeTransaction {
+            to: self.multisend,
+            value: U256::ZERO,
+            data: encode_multisend(&self.calls),
+            operation: Operation::DelegateCall,
+            safe_tx_gas: U256::ZERO,
+            base_gas: U256::ZERO,
+            gas_price: U256::ZERO,
+            gas_token: Address::ZERO,
+            refund_receiver: Address::ZERO,
+            nonce: U256::ZERO,
+        }
+    }
+}
+
+// Packed signature blob as execTransaction receives it, validated on construction
+#[derive(Debug, Clone)]
+pub struct EncodedSignatures {
+    bytes: Bytes,
+    signer_count: usize,
+}
+
+impl EncodedSignatures {
+    // Sorts by signer (Safe requires ascending owners) and packs r || s || v
+    pub fn from_signatures(signatures: &[Signature]) -> Result<Self, String> {
+        let mut sorted = signatures.to_vec();
+        sorted.sort_by_key(|s| s.signer);
+
+        let mut packed = Vec::with_capacity(sorted.len() * ECDSA_SIGNATURE_LENGTH);
+        for sig in &sorted {
+            if sig.signature.len() != ECDSA_SIGNATURE_LENGTH {
+                return Err(format!(
+                    "Signature from {} is {} bytes, expected {}",
+                    sig.signer,
+                    sig.signature.len(),
+                    ECDSA_SIGNATURE_LENGTH
+                ));
+            }
+            packed.extend_from_slice(&sig.signature);
+        }
+
+        Self::new(Bytes::from(packed), sorted.len())
+    }
+
+    // Checks the blob holds exactly `signer_count` static parts plus the dynamic
+    // data referenced by any contract (v = 0) signatures
+    pub fn new(bytes: Bytes, signer_count: usize) -> Result<Self, String> {
+        let static_len = signer_count * ECDSA_SIGNATURE_LENGTH;
+        if bytes.len() < static_len {
+            return Err(format!(
+                "Signature blob is {} bytes, too short for {} signers ({} bytes)",
+                bytes.len(),
+                signer_count,
+                static_len
+            ));
+        }
+
+        let mut expected_len = static_len;
+        for part in bytes[..static_len].chunks_exact(ECDSA_SIGNATURE_LENGTH) {
+            if part[64] != 0 {
+                continue;
+            }
+            // Contract signature: s holds the offset of a length-prefixed dynamic part
+            let offset = U256::from_be_slice(&part[32..64]);
+            let offset = usize::try_from(offset)
+                .map_err(|_| "Contract signature offset out of range".to_string())?;
+            let data_start = offset
+                .checked_add(32)
+                .filter(|&start| offset >= static_len && start <= bytes.len())
+                .ok_or_else(|| format!("Contract signature offset {} out of bounds", offset))?;
+            let data_len = U256::from_be_slice(&bytes[offset..data_start]);
+            let data_end = usize::try_from(data_len)
+                .ok()
+                .and_then(|data_len| data_start.checked_add(data_len))
+                .ok_or_else(|| "Contract signature length out of range".to_string())?;
+            expected_len = expected_len.max(data_end);
+        }
+
+        if bytes.len() != expected_len {
+            return Err(format!(
+                "Signature blob is {} bytes, expected {} for {} signers",
+                bytes.len(),
+                expected_len,
+                signer_count
+            ));
+        }
+
+        Ok(Self {
+            bytes,
+            signer_count,
+        })
+    }
+
+    pub fn signer_count(&self) -> usize {
+        self.signer_count
+    }
+
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.bytes
+    }
 }
 
 impl SafeTransaction {
-    pub fn encode_for_signing(&self) -> Vec<u8> {
-        // EIP-712 encoding would go here
-        // For demo, we'll use a simplified version
-        let mut data = Vec::new();
-        data.extend_from_slice(self.to.as_slice());
-        data.extend_from_slice(&self.value.to_be_bytes::<32>());
-        data.extend_from_slice(self.data.as_ref());
-        data.push(self.operation);
-        data.extend_from_slice(&self.nonce.to_be_bytes::<32>());
-        data
+    fn as_typed(&self) -> SafeTx {
+        SafeTx {
+            to: self.to,
+            value: self.value,
+            data: self.data.clone(),
+            operation: self.operation.into(),
+            safeTxGas: self.safe_tx_gas,
+            baseGas: self.base_gas,
+            gasPrice: self.gas_price,
+            gasToken: self.gas_token,
+            refundReceiver: self.refund_receiver,
+            nonce: self.nonce,
+        }
+    }
+
+    // EIP-712 hashStruct(SafeTx): covers every signed field, independent of chain and Safe,
+    // so clients can recompute it from the returned transaction to detect tampering
+    pub fn content_hash(&self) -> B256 {
+        self.as_typed().eip712_hash_struct()
+    }
+
+    // MultiSend batches are delegatecalls into multiSend(bytes)
+    pub fn is_multisend(&self) -> bool {
+        self.operation == Operation::DelegateCall && self.data.starts_with(&multiSendCall::SELECTOR)
+    }
+
+    // eth_signTypedData_v4 payload for wallets signing the SafeTx directly
+    pub fn typed_data(&self, chain_id: u64, safe_address: Address) -> serde_json::Value {
+        serde_json::json!({
+            "domain": {
+                "chainId": chain_id,
+                "verifyingContract": safe_address.to_string()
+            },
+            "types": {
+                "EIP712Domain": [
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "SafeTx": [
+                    { "name": "to", "type": "address" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "data", "type": "bytes" },
+                    { "name": "operation", "type": "uint8" },
+                    { "name": "safeTxGas", "type": "uint256" },
+                    { "name": "baseGas", "type": "uint256" },
+                    { "name": "gasPrice", "type": "uint256" },
+                    { "name": "gasToken", "type": "address" },
+                    { "name": "refundReceiver", "type": "address" },
+                    { "name": "nonce", "type": "uint256" }
+                ]
+            },
+            "primaryType": "SafeTx",
+            "message": {
+                "to": self.to.to_string(),
+                "value": self.value.to_string(),
+                "data": self.data.to_string(),
+                "operation": u8::from(self.operation),
+                "safeTxGas": self.safe_tx_gas.to_string(),
+                "baseGas": self.base_gas.to_string(),
+                "gasPrice": self.gas_price.to_string(),
+                "gasToken": self.gas_token.to_string(),
+                "refundReceiver": self.refund_receiver.to_string(),
+                "nonce": self.nonce.to_string()
+            }
+        })
+    }
+
+    // Same value as Safe.getTransactionHash, computed without an RPC round trip
+    pub fn safe_tx_hash(&self, chain_id: u64, safe_address: Address) -> B256 {
+        utils::safe_tx_hash(&self.as_typed(), chain_id, safe_address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer() -> SafeTransaction {
+        SafeTransaction {
+            to: Address::repeat_byte(0x11),
+            value: U256::from(1_000u64),
+            data: Bytes::new(),
+            operation: Operation::Call,
+            safe_tx_gas: U256::ZERO,
+            base_gas: U256::ZERO,
+            gas_price: U256::ZERO,
+            gas_token: Address::ZERO,
+            refund_receiver: Address::ZERO,
+            nonce: U256::from(7u64),
+        }
+    }
+
+    #[test]
+    fn every_gas_field_changes_the_hash() {
+        let safe = Address::repeat_byte(0x5a);
+        let base = transfer().safe_tx_hash(1001, safe);
+        let variants: [fn(&mut SafeTransaction); 5] = [
+            |tx| tx.safe_tx_gas = U256::from(50_000u64),
+            |tx| tx.base_gas = U256::from(21_000u64),
+            |tx| tx.gas_price = U256::from(25_000_000_000u64),
+            |tx| tx.gas_token = Address::repeat_byte(0x70),
+            |tx| tx.refund_receiver = Address::repeat_byte(0x99),
+        ];
+
+        for (i, set) in variants.into_iter().enumerate() {
+            let mut tx = transfer();
+            set(&mut tx);
+            assert_ne!(tx.safe_tx_hash(1001, safe), base, "gas field {}", i);
+            assert_ne!(
+                tx.content_hash(),
+                transfer().content_hash(),
+                "gas field {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn typed_data_carries_the_gas_fields() {
+        let mut tx = transfer();
+        tx.base_gas = U256::from(21_000u64);
+        tx.gas_price = U256::from(3u64);
+        tx.refund_receiver = Address::repeat_byte(0x99);
+
+        let message = &tx.typed_data(1001, Address::repeat_byte(0x5a))["message"];
+
+        assert_eq!(message["baseGas"], "21000");
+        assert_eq!(message["gasPrice"], "3");
+        assert_eq!(
+            message["refundReceiver"],
+            Address::repeat_byte(0x99).to_string()
+        );
+    }
+
+    fn signature(claimed: Address, recovered: Option<Address>) -> Signature {
+        Signature {
+            signer: claimed,
+            signature: Bytes::from(vec![0u8; ECDSA_SIGNATURE_LENGTH]),
+            recovered: recovered.map(|signer| RecoveredSignature {
+                signer,
+                v_scheme: shared::signature::VScheme::TypedData,
+                low_s: true,
+            }),
+            signed_at: None,
+            signed_hash: None,
+        }
+    }
+
+    #[test]
+    fn unrecovered_signature_has_no_verified_signer() {
+        let owner = Address::repeat_byte(0x01);
+
+        assert_eq!(
+            signature(Address::repeat_byte(0x02), Some(owner)).verified_signer(),
+            Ok(owner)
+        );
+        assert!(signature(owner, None).verified_signer().is_err());
+    }
+
+    #[test]
+    fn signer_set_rejects_unrecovered_signatures() {
+        let (a, b) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+
+        assert!(validate_signer_set(&[signature(a, Some(a)), signature(b, Some(b))]).is_ok());
+        let err = validate_signer_set(&[signature(a, Some(a)), signature(b, None)]).unwrap_err();
+        assert!(err.contains("never recovered"));
+    }
+
+    #[test]
+    fn signer_set_rejects_repeated_and_zero_signers() {
+        let (a, b) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+
+        assert!(validate_signer_set(&[signature(a, Some(a)), signature(b, Some(a))]).is_err());
+        assert!(validate_signer_set(&[signature(a, Some(Address::ZERO))]).is_err());
+    }
+
+    // Static part for a contract signature whose dynamic data sits at `offset`
+    fn contract_signature(owner: Address, offset: U256) -> Vec<u8> {
+        let mut part = owner.into_word().to_vec();
+        part.extend_from_slice(&offset.to_be_bytes::<32>());
+        part.push(0);
+        part
+    }
+
+    #[test]
+    fn blob_with_a_contract_signature_is_accepted() {
+        let mut blob = vec![0x11; 64];
+        blob.push(27);
+        blob.extend(contract_signature(Address::repeat_byte(2), U256::from(130)));
+        blob.extend_from_slice(&U256::from(3).to_be_bytes::<32>());
+        blob.extend_from_slice(&[0xaa; 3]);
+
+        let encoded = EncodedSignatures::new(Bytes::from(blob), 2).unwrap();
+
+        assert_eq!(encoded.signer_count(), 2);
+        assert_eq!(encoded.as_bytes().len(), 130 + 32 + 3);
+    }
+
+    #[test]
+    fn truncated_blobs_are_rejected() {
+        let mut blob = vec![0x11; 64];
+        blob.push(27);
+        assert!(EncodedSignatures::new(Bytes::from(blob[..64].to_vec()), 1).is_err());
+        assert!(EncodedSignatures::new(Bytes::from(blob), 2).is_err());
+
+        // The dynamic part promises 3 bytes but carries 2
+        let mut blob = contract_signature(Address::repeat_byte(2), U256::from(65));
+        blob.extend_from_slice(&U256::from(3).to_be_bytes::<32>());
+        blob.extend_from_slice(&[0xaa; 2]);
+        assert!(EncodedSignatures::new(Bytes::from(blob), 1).is_err());
+    }
+
+    #[test]
+    fn huge_offsets_and_lengths_do_not_overflow() {
+        let blob = contract_signature(Address::repeat_byte(2), U256::from(usize::MAX - 8));
+        let err = EncodedSignatures::new(Bytes::from(blob), 1).unwrap_err();
+        assert!(err.contains("out of bounds"));
+
+        let mut blob = contract_signature(Address::repeat_byte(2), U256::from(65));
+        blob.extend_from_slice(&U256::from(usize::MAX - 8).to_be_bytes::<32>());
+        let err = EncodedSignatures::new(Bytes::from(blob), 1).unwrap_err();
+        assert!(err.contains("length out of range"));
+    }
+
+    #[test]
+    fn operation_outside_call_and_delegatecall_is_rejected() {
+        let mut json = serde_json::to_value(transfer()).unwrap();
+        assert_eq!(json["operation"], 0);
+
+        json["operation"] = serde_json::json!(1);
+        let parsed: SafeTransaction = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(parsed.operation, Operation::DelegateCall);
+
+        json["operation"] = serde_json::json!(2);
+        let err = serde_json::from_value::<SafeTransaction>(json).unwrap_err();
+        assert!(err.to_string().contains("Invalid Safe operation: 2"));
+    }
+
+    #[test]
+    fn multisend_packs_calls_into_a_known_vector() {
+        let calls = [
+            (
+                Operation::Call,
+                Address::repeat_byte(0x11),
+                U256::from(1u64),
+                Bytes::new(),
+            ),
+            (
+                Operation::Call,
+                Address::repeat_byte(0x22),
+                U256::ZERO,
+                Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+            ),
+        ];
+        let multisend = Address::repeat_byte(0x40);
+
+        let tx = MultiSendBuilder::new(multisend, calls.clone()).build();
+
+        let expected = concat!(
+            // multiSend(bytes), offset 0x20, 174 packed bytes
+            "8d80ff0a",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "00000000000000000000000000000000000000000000000000000000000000ae",
+            // operation || to || value || data length
+            "00",
+            "1111111111111111111111111111111111111111",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // operation || to || value || data length || data
+            "00",
+            "2222222222222222222222222222222222222222",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "deadbeef",
+            // ABI padding up to 192 bytes
+            "000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(&tx.data), expected);
+        assert_eq!(tx.data.len(), 4 + 32 + 32 + 192);
+        assert_eq!(tx.to, multisend);
+        assert_eq!(tx.operation, Operation::DelegateCall);
+        assert_eq!(tx.value, U256::ZERO);
+
+        let decoded = decode_multisend(&tx.data).unwrap();
+        assert_eq!(
+            decoded
+                .into_iter()
+                .map(|c| (c.operation, c.to, c.value, c.data))
+                .collect::<Vec<_>>(),
+            calls
+        );
     }
 }
@@ -1,14 +1,32 @@
 use alloy::{
+    consensus::Transaction as _,
+    contract::SolCallBuilder,
+    eips::BlockNumberOrTag,
     network::EthereumWallet,
-    primitives::{Address, Bytes, B256, U256},
-    providers::ProviderBuilder,
+    primitives::{b256, utils::format_units, Address, Bytes, B256, U256},
+    providers::{CallItemBuilder, Provider, ProviderBuilder, RootProvider},
+    rpc::types::{Filter, TransactionReceipt},
     signers::local::PrivateKeySigner,
     sol,
+    sol_types::{decode_revert_reason, Revert, SolCall, SolError, SolEvent},
+};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use shared::{
+    deployment::DeploymentMode,
+    tokens::{TokenInfo, TokenRegistry},
+};
+use std::{
+    ops::Range,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use anyhow::Result;
-use std::str::FromStr;
 
-use crate::safe_contract::Signature;
+use crate::{
+    rpc_pool::{EndpointHealth, RpcPool},
+    safe_contract::{EncodedSignatures, Operation, SafeTransaction},
+};
 
 // Define Safe interface using sol! macro
 sol!(
@@ -19,144 +37,954 @@ sol!(
     "src/ISafe.json"
 );
 
+// keccak256("guard_manager.guard.address"), where Safe stores its transaction guard
+const GUARD_STORAGE_SLOT: B256 =
+    b256!("4a204f620c8c5ccdca3fd54d003badd85ba500436a431f0cbda4f558c93c34c8");
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevertSource {
+    // Safe core checks, which revert with GSxxx codes
+    Safe,
+    // The transaction guard's checkTransaction hook
+    Guard,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum SimulationResult {
+    Success,
+    Reverted {
+        source: RevertSource,
+        reason: String,
+        guard: Option<Address>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct OnchainSafeState {
+    pub owners: Vec<Address>,
+    pub threshold: U256,
+    pub nonce: U256,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionCost {
+    pub gas_limit: u64,
+    pub gas_price: U256,
+    pub total_cost: U256,
+}
+
+// How long a broadcast execTransaction is polled for before giving up on it
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub const DEFAULT_PRIORITY_FEE_TIP: u128 = 1_000_000_000; // 1 gwei
+
+// How execTransaction is priced. The overrides pin either EIP-1559 field;
+// otherwise they're derived from the next block's base fee plus the tip.
+#[derive(Debug, Clone)]
+pub struct FeeConfig {
+    pub priority_fee_tip: u128,
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            priority_fee_tip: DEFAULT_PRIORITY_FEE_TIP,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FeeQuote {
+    Eip1559 {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+    // Networks whose fee history reports no base fee
+    Legacy {
+        gas_price: u128,
+    },
+}
+
+impl FeeQuote {
+    // Most the relayer can pay per gas under this quote
+    pub fn max_price(&self) -> u128 {
+        match self {
+            Self::Eip1559 {
+                max_fee_per_gas, ..
+            } => *max_fee_per_gas,
+            Self::Legacy { gas_price } => *gas_price,
+        }
+    }
+}
+
+// Execution events aren't part of the ISafe ABI file
+sol! {
+    event ExecutionSuccess(bytes32 txHash, uint256 payment);
+    event ExecutionFailure(bytes32 txHash, uint256 payment);
+}
+
+// Only the read side of ERC20 needed to report holdings
+sol! {
+    #[sol(rpc)]
+    interface IERC20 {
+        function balanceOf(address owner) external view returns (uint256);
+        function decimals() external view returns (uint8);
+        function symbol() external view returns (string);
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
+}
+
+// KAIA, like ETH, has 18 decimals
+const NATIVE_DECIMALS: u8 = 18;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenBalance {
+    pub token: Address,
+    pub symbol: Option<String>,
+    pub balance: U256,
+    pub decimals: u8,
+    pub formatted: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SafeBalances {
+    pub native: TokenBalance,
+    pub tokens: Vec<TokenBalance>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionReceipt {
+    pub transaction_hash: B256,
+    pub block_number: Option<u64>,
+    pub gas_used: u64,
+    pub effective_gas_price: u128,
+    // The outer transaction succeeded and the Safe didn't emit ExecutionFailure
+    pub success: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionEvent {
+    pub safe_tx_hash: B256,
+    pub success: bool,
+    pub transaction_hash: Option<B256>,
+}
+
 pub struct SafeExecutor {
-    rpc_url: String,
+    rpc: Arc<RpcPool>,
     safe_address: Address,
+    fees: FeeConfig,
+    // Relayer that submits execTransaction; without one nothing can be executed
+    signer: Option<PrivateKeySigner>,
 }
 
 impl SafeExecutor {
-    pub async fn new(rpc_url: &str, safe_address: &str) -> Result<Self> {
+    pub async fn new(rpc: Arc<RpcPool>, safe_address: &str) -> Result<Self> {
         let safe_addr = Address::from_str(safe_address)?;
 
         Ok(Self {
-            rpc_url: rpc_url.to_string(),
+            rpc,
             safe_address: safe_addr,
+            fees: FeeConfig::default(),
+            signer: None,
+        })
+    }
+
+    pub fn with_fees(mut self, fees: FeeConfig) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    pub fn with_signer(mut self, signer: Option<PrivateKeySigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    fn signer(&self) -> Result<&PrivateKeySigner> {
+        self.signer
+            .as_ref()
+            .ok_or_else(|| anyhow!("No relayer key configured; set EXECUTOR_PRIVATE_KEY"))
+    }
+
+    // Base fee from eth_feeHistory doubled (headroom for a few full blocks) plus
+    // the tip; legacy eth_gasPrice when the network reports no base fee
+    async fn fee_quote(&self, provider: &RootProvider) -> Result<FeeQuote> {
+        let base_fee = match provider
+            .get_fee_history(1, BlockNumberOrTag::Latest, &[])
+            .await
+        {
+            Ok(history) => history.next_block_base_fee().filter(|fee| *fee > 0),
+            Err(e) => {
+                tracing::debug!("eth_feeHistory unavailable, using legacy pricing: {}", e);
+                None
+            }
+        };
+
+        let Some(base_fee) = base_fee else {
+            return Ok(FeeQuote::Legacy {
+                gas_price: provider.get_gas_price().await?,
+            });
+        };
+        let max_priority_fee_per_gas = self
+            .fees
+            .max_priority_fee_per_gas
+            .unwrap_or(self.fees.priority_fee_tip);
+        let max_fee_per_gas = self
+            .fees
+            .max_fee_per_gas
+            .unwrap_or(
+                base_fee
+                    .saturating_mul(2)
+                    .saturating_add(max_priority_fee_per_gas),
+            )
+            .max(max_priority_fee_per_gas);
+        Ok(FeeQuote::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
         })
     }
 
     pub async fn get_nonce(&self) -> Result<U256> {
-        // Create provider for this call
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
+        self.rpc
+            .run(|provider| async move {
+                let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
+
+                Ok(safe.nonce().call().await?)
+            })
+            .await
+    }
+
+    pub async fn get_safe_state(&self) -> Result<OnchainSafeState> {
+        self.rpc
+            .run(|provider| async move {
+                let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
+
+                let owners = safe.getOwners().call().await?;
+                let threshold = safe.getThreshold().call().await?;
+                let nonce = safe.nonce().call().await?;
 
-        let nonce = safe.nonce().call().await?;
-        Ok(nonce)
+                Ok(OnchainSafeState {
+                    owners,
+                    threshold,
+                    nonce,
+                })
+            })
+            .await
     }
 
-    pub async fn get_transaction_hash(
+    pub async fn get_transaction_hash(&self, tx: &SafeTransaction) -> Result<B256> {
+        self.rpc
+            .run(|provider| async move {
+                let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
+
+                let tx_hash = safe
+                    .getTransactionHash(
+                        tx.to,
+                        tx.value,
+                        tx.data.clone(),
+                        tx.operation.into(),
+                        tx.safe_tx_gas,
+                        tx.base_gas,
+                        tx.gas_price,
+                        tx.gas_token,
+                        tx.refund_receiver,
+                        tx.nonce,
+                    )
+                    .call()
+                    .await?;
+
+                Ok(tx_hash)
+            })
+            .await
+    }
+
+    // Dry-runs execTransaction via eth_call so reverts surface before any gas is spent
+    pub async fn simulate_execution(
         &self,
-        to: Address,
-        value: U256,
-        data: Bytes,
-        nonce: U256,
-    ) -> Result<B256> {
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
+        tx: &SafeTransaction,
+        signatures: &EncodedSignatures,
+    ) -> Result<SimulationResult> {
+        let relayer = self.relayer_address()?;
 
-        let tx_hash = safe
-            .getTransactionHash(
-                to,
-                value,
-                data,
-                0u8,           // operation: 0 = Call
-                U256::ZERO,    // safeTxGas
-                U256::ZERO,    // baseGas
-                U256::ZERO,    // gasPrice
-                Address::ZERO, // gasToken
-                Address::ZERO, // refundReceiver
-                nonce,
-            )
-            .call()
-            .await?;
+        self.rpc
+            .run(|provider| async move {
+                let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
 
-        Ok(tx_hash)
+                let result = exec_transaction_call(&safe, tx, signatures)
+                    .from(relayer)
+                    .call()
+                    .await;
+
+                let revert_data = match result {
+                    Ok(true) => return Ok(SimulationResult::Success),
+                    // With safeTxGas/gasPrice set a failing inner call doesn't revert;
+                    // execTransaction returns false and emits ExecutionFailure
+                    Ok(false) => {
+                        return Ok(SimulationResult::Reverted {
+                            source: RevertSource::Unknown,
+                            reason: "Inner call failed (ExecutionFailure)".to_string(),
+                            guard: None,
+                        })
+                    }
+                    Err(e) => match e.as_revert_data() {
+                        Some(data) => data,
+                        // Not a revert (transport/RPC failure), so nothing was simulated
+                        None => return Err(e.into()),
+                    },
+                };
+
+                // A plain Error(string) is kept bare, since decode_revert_reason
+                // prefixes it with "revert: " and would hide the GSxxx code
+                let reason = Revert::abi_decode(&revert_data)
+                    .map(|revert| revert.reason)
+                    .ok()
+                    .or_else(|| decode_revert_reason(&revert_data))
+                    .unwrap_or_else(|| format!("0x{}", hex::encode(&revert_data)));
+
+                let guard_slot = provider
+                    .get_storage_at(self.safe_address, GUARD_STORAGE_SLOT.into())
+                    .await?;
+                let guard = Address::from_word(guard_slot.into());
+                let guard = (guard != Address::ZERO).then_some(guard);
+
+                // Safe core reverts always carry a GSxxx code; anything else with a guard
+                // installed came from the guard's checkTransaction
+                let source = if reason.starts_with("GS") {
+                    RevertSource::Safe
+                } else if guard.is_some() {
+                    RevertSource::Guard
+                } else {
+                    RevertSource::Unknown
+                };
+
+                Ok(SimulationResult::Reverted {
+                    source,
+                    reason,
+                    guard,
+                })
+            })
+            .await
     }
 
-    pub async fn execute_transaction(
+    // Worst-case relayer spend for execTransaction, with headroom from the gas multiplier
+    pub async fn estimate_execution_cost(
         &self,
-        to: Address,
-        value: U256,
-        data: Bytes,
-        signatures: Vec<Signature>,
-    ) -> Result<B256> {
-        // Get executor private key from environment or use a default one
-        // In production, this should be a proper relayer account with gas
-        let executor_key = std::env::var("EXECUTOR_PRIVATE_KEY")
-            .or_else(|_| std::env::var("DEPLOYER_PRIVATE_KEY"))
-            .unwrap_or_else(|_| {
-                // Default test key - should have some KAIA for gas
-                tracing::warn!("No EXECUTOR_PRIVATE_KEY found, using test key");
-                "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string()
-            });
+        tx: &SafeTransaction,
+        signatures: &EncodedSignatures,
+        gas_multiplier: f64,
+    ) -> Result<ExecutionCost> {
+        let relayer = self.relayer_address()?;
 
-        // Create signer from private key
-        let signer = PrivateKeySigner::from_str(&executor_key)?;
-        let wallet = EthereumWallet::from(signer);
+        self.rpc
+            .run(|provider| async move {
+                let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
 
-        // Create provider with wallet
-        let provider = ProviderBuilder::new()
-            .wallet(wallet)
-            .connect_http(self.rpc_url.parse()?);
+                let estimated_gas = exec_transaction_call(&safe, tx, signatures)
+                    .from(relayer)
+                    .estimate_gas()
+                    .await?;
+                let gas_limit = (estimated_gas as f64 * gas_multiplier).ceil() as u64;
+                let gas_price = self.fee_quote(&provider).await?.max_price();
 
-        let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
+                Ok(ExecutionCost {
+                    gas_limit,
+                    gas_price: U256::from(gas_price),
+                    total_cost: U256::from(gas_limit) * U256::from(gas_price),
+                })
+            })
+            .await
+    }
+
+    // Most execute_transaction would pay per gas right now: the max fee of the
+    // quote it prices execTransaction with, not the raw network gas price
+    pub async fn execution_gas_price(&self) -> Result<U256> {
+        self.rpc
+            .run(|provider| async move {
+                Ok(U256::from(self.fee_quote(&provider).await?.max_price()))
+            })
+            .await
+    }
+
+    // Native balance plus each tracked token's balanceOf, batched through
+    // Multicall3. Metadata comes from the registry; tokens it doesn't know yet get
+    // their decimals/symbol read in a second batch and cached. Tokens whose calls
+    // revert are left out.
+    pub async fn balances(
+        &self,
+        tokens: &[Address],
+        registry: &TokenRegistry,
+    ) -> Result<SafeBalances> {
+        self.rpc
+            .run(|provider| async move {
+                let native = provider.get_balance(self.safe_address).await?;
+
+                let balances = provider
+                    .multicall()
+                    .dynamic::<IERC20::balanceOfCall>()
+                    .extend_calls(tokens.iter().map(|t| {
+                        CallItemBuilder::new(
+                            IERC20::new(*t, &provider).balanceOf(self.safe_address),
+                        )
+                        .allow_failure(true)
+                    }))
+                    .aggregate3()
+                    .await?;
+
+                let unknown: Vec<Address> = tokens
+                    .iter()
+                    .filter(|t| registry.get(**t).is_none())
+                    .copied()
+                    .collect();
+                if !unknown.is_empty() {
+                    let decimals = provider
+                        .multicall()
+                        .dynamic::<IERC20::decimalsCall>()
+                        .extend_calls(unknown.iter().map(|t| {
+                            CallItemBuilder::new(IERC20::new(*t, &provider).decimals())
+                                .allow_failure(true)
+                        }))
+                        .aggregate3()
+                        .await?;
+                    let symbols = provider
+                        .multicall()
+                        .dynamic::<IERC20::symbolCall>()
+                        .extend_calls(unknown.iter().map(|t| {
+                            CallItemBuilder::new(IERC20::new(*t, &provider).symbol())
+                                .allow_failure(true)
+                        }))
+                        .aggregate3()
+                        .await?;
+
+                    for ((token, decimals), symbol) in unknown.iter().zip(decimals).zip(symbols) {
+                        if let Ok(decimals) = decimals {
+                            let symbol = symbol.unwrap_or_else(|_| token.to_string());
+                            registry.insert(*token, TokenInfo { symbol, decimals });
+                        }
+                    }
+                }
+
+                let tokens = tokens
+                    .iter()
+                    .zip(balances)
+                    .filter_map(|(token, balance)| {
+                        let info = registry.get(*token)?;
+                        Some(token_balance(
+                            *token,
+                            Some(info.symbol),
+                            balance.ok()?,
+                            info.decimals,
+                        ))
+                    })
+                    .collect();
 
-        // Sort signatures by signer address (required by Safe)
-        let mut sorted_sigs = signatures.clone();
-        sorted_sigs.sort_by_key(|s| s.signer);
-
-        // Log signature details for debugging
-        tracing::info!("Executing with {} signatures", sorted_sigs.len());
-        for (i, sig) in sorted_sigs.iter().enumerate() {
-            tracing::info!(
-                "Signature {}: signer={}, sig_len={}",
-                i,
-                sig.signer,
-                sig.signature.len()
-            );
+                Ok(SafeBalances {
+                    native: token_balance(Address::ZERO, None, native, NATIVE_DECIMALS),
+                    tokens,
+                })
+            })
+            .await
+    }
+
+    pub async fn relayer_balance(&self) -> Result<U256> {
+        let relayer = self.relayer_address()?;
+
+        self.rpc
+            .run(|provider| async move { Ok(provider.get_balance(relayer).await?) })
+            .await
+    }
+
+    // ExecutionSuccess/ExecutionFailure events from the last `lookback_blocks` blocks
+    pub async fn scan_execution_events(&self, lookback_blocks: u64) -> Result<Vec<ExecutionEvent>> {
+        self.rpc
+            .run(|provider| async move {
+                let latest = provider.get_block_number().await?;
+                self.execution_events(&provider, latest.saturating_sub(lookback_blocks), latest)
+                    .await
+            })
+            .await
+    }
+
+    // The nonce and the execution events of the last `lookback_blocks` blocks, both
+    // as of the same block. Events don't say which nonce they consumed; see
+    // execution_nonce.
+    pub async fn execution_history(
+        &self,
+        lookback_blocks: u64,
+    ) -> Result<(U256, Vec<ExecutionEvent>)> {
+        self.rpc
+            .run(|provider| async move {
+                let latest = provider.get_block_number().await?;
+                let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
+                let nonce = safe.nonce().block(latest.into()).call().await?;
+                let events = self
+                    .execution_events(&provider, latest.saturating_sub(lookback_blocks), latest)
+                    .await?;
+                Ok((nonce, events))
+            })
+            .await
+    }
+
+    async fn execution_events(
+        &self,
+        provider: &RootProvider,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<ExecutionEvent>> {
+        let filter = Filter::new()
+            .address(self.safe_address)
+            .from_block(from_block)
+            .to_block(to_block)
+            .event_signature(vec![
+                ExecutionSuccess::SIGNATURE_HASH,
+                ExecutionFailure::SIGNATURE_HASH,
+            ]);
+
+        let mut events = Vec::new();
+        for log in provider.get_logs(&filter).await? {
+            let (safe_tx_hash, success) = if let Ok(event) = log.log_decode::<ExecutionSuccess>() {
+                (event.inner.data.txHash, true)
+            } else if let Ok(event) = log.log_decode::<ExecutionFailure>() {
+                (event.inner.data.txHash, false)
+            } else {
+                continue;
+            };
+
+            events.push(ExecutionEvent {
+                safe_tx_hash,
+                success,
+                transaction_hash: log.transaction_hash,
+            });
         }
 
-        // Encode signatures for Safe (r + s + v format)
-        let encoded_signatures = encode_signatures(&sorted_sigs);
+        Ok(events)
+    }
+
+    // Nonce an execution event consumed, searched among `candidates`. The SafeTx
+    // is decoded from the calldata of the transaction that emitted the event and
+    // hashed with each candidate until one reproduces the event's safeTxHash.
+    // None if that transaction didn't call execTransaction on this Safe directly
+    // (a module or relay contract in between) or no candidate matches.
+    pub async fn execution_nonce(
+        &self,
+        event: &ExecutionEvent,
+        chain_id: u64,
+        candidates: Range<u64>,
+    ) -> Result<Option<u64>> {
+        let Some(tx_hash) = event.transaction_hash else {
+            return Ok(None);
+        };
+        let Some(tx) = self
+            .rpc
+            .run(|provider| async move { Ok(provider.get_transaction_by_hash(tx_hash).await?) })
+            .await?
+        else {
+            return Ok(None);
+        };
+        if tx.to() != Some(self.safe_address) {
+            return Ok(None);
+        }
+        let Ok(call) = ISafe::execTransactionCall::abi_decode(tx.input()) else {
+            return Ok(None);
+        };
+        let Ok(operation) = Operation::try_from(call.operation) else {
+            return Ok(None);
+        };
+
+        let mut safe_tx = SafeTransaction {
+            to: call.to,
+            value: call.value,
+            data: call.data,
+            operation,
+            safe_tx_gas: call.safeTxGas,
+            base_gas: call.baseGas,
+            gas_price: call.gasPrice,
+            gas_token: call.gasToken,
+            refund_receiver: call.refundReceiver,
+            nonce: U256::ZERO,
+        };
+        Ok(candidates.into_iter().rev().find(|nonce| {
+            safe_tx.nonce = U256::from(*nonce);
+            safe_tx.safe_tx_hash(chain_id, self.safe_address) == event.safe_tx_hash
+        }))
+    }
+
+    pub async fn execution_receipt(&self, tx_hash: B256) -> Result<Option<ExecutionReceipt>> {
+        self.rpc
+            .run(|provider| async move {
+                let Some(receipt) = provider.get_transaction_receipt(tx_hash).await? else {
+                    return Ok(None);
+                };
+
+                let inner_failed = receipt
+                    .inner
+                    .logs()
+                    .iter()
+                    .any(|log| log.log_decode::<ExecutionFailure>().is_ok());
+
+                Ok(Some(ExecutionReceipt {
+                    transaction_hash: tx_hash,
+                    block_number: receipt.block_number,
+                    gas_used: receipt.gas_used,
+                    effective_gas_price: receipt.effective_gas_price,
+                    success: receipt.status() && !inner_failed,
+                }))
+            })
+            .await
+    }
+
+    // Address of the account that submits execTransaction and pays its gas
+    pub fn relayer_address(&self) -> Result<Address> {
+        Ok(self.signer()?.address())
+    }
+
+    pub fn rpc_health(&self) -> Vec<EndpointHealth> {
+        self.rpc.health()
+    }
+
+    pub async fn execute_transaction(
+        &self,
+        tx: &SafeTransaction,
+        signatures: &EncodedSignatures,
+    ) -> Result<B256> {
+        let signer = self.signer()?.clone();
+
         tracing::info!(
-            "Total encoded signatures length: {} bytes",
-            encoded_signatures.len()
+            "Executing with {} signatures ({} bytes encoded)",
+            signatures.signer_count(),
+            signatures.as_bytes().len()
         );
 
-        // Execute the transaction
-        let pending_tx = safe
-            .execTransaction(
-                to,
-                value,
-                data,
-                0u8,           // operation: 0 = Call
-                U256::ZERO,    // safeTxGas
-                U256::ZERO,    // baseGas
-                U256::ZERO,    // gasPrice
-                Address::ZERO, // gasToken
-                Address::ZERO, // refundReceiver
-                Bytes::from(encoded_signatures),
-            )
-            .send()
+        // Fees are a read and may fail over; the broadcast itself goes to a single
+        // endpoint exactly once, since a retry after a lost response would
+        // resubmit a transaction the node may already have accepted
+        let fees = self
+            .rpc
+            .run(|provider| async move { self.fee_quote(&provider).await })
             .await?;
+        tracing::info!("Pricing execTransaction with {:?}", fees);
+
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_provider(self.rpc.primary());
+        let safe = ISafe::ISafeInstance::new(self.safe_address, &provider);
 
-        // Get transaction hash before moving pending_tx
-        let tx_hash = *pending_tx.tx_hash();
+        let call = exec_transaction_call(&safe, tx, signatures);
+        let call = match fees {
+            FeeQuote::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => call
+                .max_fee_per_gas(max_fee_per_gas)
+                .max_priority_fee_per_gas(max_priority_fee_per_gas),
+            FeeQuote::Legacy { gas_price } => call.gas_price(gas_price),
+        };
+        let tx_hash = *call.send().await?.tx_hash();
 
-        // Wait for confirmation
-        let _receipt = pending_tx.get_receipt().await?;
+        // From here on only the receipt is fetched, by hash, so polling can use the
+        // whole pool without any risk of executing twice
+        let receipt = self.wait_for_receipt(tx_hash).await?;
+        if !receipt.status() {
+            return Err(anyhow!("execTransaction {} reverted", tx_hash));
+        }
+        // With safeTxGas/gasPrice set the Safe doesn't revert when the inner
+        // call fails; it emits ExecutionFailure instead
+        if receipt
+            .inner
+            .logs()
+            .iter()
+            .any(|log| log.log_decode::<ExecutionFailure>().is_ok())
+        {
+            return Err(anyhow!(
+                "Safe transaction failed in {} (ExecutionFailure)",
+                tx_hash
+            ));
+        }
 
         Ok(tx_hash)
     }
+
+    // Polls for the receipt of a broadcast transaction until RECEIPT_TIMEOUT.
+    // Errors here never mean "not sent", so they're wrapped to stay non-retryable.
+    async fn wait_for_receipt(&self, tx_hash: B256) -> Result<TransactionReceipt> {
+        let deadline = Instant::now() + RECEIPT_TIMEOUT;
+        loop {
+            match self
+                .rpc
+                .run(|provider| async move { Ok(provider.get_transaction_receipt(tx_hash).await?) })
+                .await
+            {
+                Ok(Some(receipt)) => return Ok(receipt),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Receipt poll for {} failed: {}", tx_hash, e),
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Broadcast {} but no receipt after {:?}",
+                    tx_hash,
+                    RECEIPT_TIMEOUT
+                ));
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
 }
 
-fn encode_signatures(signatures: &[Signature]) -> Vec<u8> {
-    let mut encoded = Vec::new();
+fn token_balance(
+    token: Address,
+    symbol: Option<String>,
+    balance: U256,
+    decimals: u8,
+) -> TokenBalance {
+    TokenBalance {
+        token,
+        symbol,
+        balance,
+        decimals,
+        formatted: format_units(balance, decimals).unwrap_or_else(|_| balance.to_string()),
+    }
+}
 
-    for sig in signatures {
-        // Safe expects signatures in format: r (32 bytes) + s (32 bytes) + v (1 byte)
-        // The signature bytes should already be in this format from the client
-        encoded.extend_from_slice(&sig.signature);
+fn exec_transaction_call<'a, P: Provider>(
+    safe: &'a ISafe::ISafeInstance<P>,
+    tx: &SafeTransaction,
+    signatures: &EncodedSignatures,
+) -> SolCallBuilder<&'a P, ISafe::execTransactionCall> {
+    safe.execTransaction(
+        tx.to,
+        tx.value,
+        tx.data.clone(),
+        tx.operation.into(),
+        tx.safe_tx_gas,
+        tx.base_gas,
+        tx.gas_price,
+        tx.gas_token,
+        tx.refund_receiver,
+        signatures.as_bytes().clone(),
+    )
+}
+
+// The exact calldata execute_transaction sends to the Safe
+pub fn encode_exec_transaction(tx: &SafeTransaction, signatures: &EncodedSignatures) -> Bytes {
+    ISafe::execTransactionCall {
+        to: tx.to,
+        value: tx.value,
+        data: tx.data.clone(),
+        operation: tx.operation.into(),
+        safeTxGas: tx.safe_tx_gas,
+        baseGas: tx.base_gas,
+        gasPrice: tx.gas_price,
+        gasToken: tx.gas_token,
+        refundReceiver: tx.refund_receiver,
+        signatures: signatures.as_bytes().clone(),
+    }
+    .abi_encode()
+    .into()
+}
+
+pub fn executor_key_configured() -> bool {
+    std::env::var("EXECUTOR_PRIVATE_KEY").is_ok() || std::env::var("DEPLOYER_PRIVATE_KEY").is_ok()
+}
+
+// Hardhat/Anvil account #0. Its key is public, so it's only used on explicit request.
+const DEV_EXECUTOR_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+// The relayer key from EXECUTOR_PRIVATE_KEY (or DEPLOYER_PRIVATE_KEY). Without one
+// there's no relayer unless USE_DEV_EXECUTOR_KEY=true opts into the dev key, which
+// production refuses.
+pub fn executor_signer_from_env() -> Result<Option<PrivateKeySigner>> {
+    executor_signer(
+        std::env::var("EXECUTOR_PRIVATE_KEY")
+            .or_else(|_| std::env::var("DEPLOYER_PRIVATE_KEY"))
+            .ok(),
+        std::env::var("USE_DEV_EXECUTOR_KEY").is_ok_and(|v| v == "true"),
+        DeploymentMode::from_env(),
+    )
+}
+
+fn executor_signer(
+    configured_key: Option<String>,
+    use_dev_key: bool,
+    deployment_mode: DeploymentMode,
+) -> Result<Option<PrivateKeySigner>> {
+    if let Some(key) = configured_key {
+        return Ok(Some(PrivateKeySigner::from_str(&key)?));
     }
+    if use_dev_key {
+        if deployment_mode.is_production() {
+            return Err(anyhow!(
+                "USE_DEV_EXECUTOR_KEY is not allowed in production mode"
+            ));
+        }
+        tracing::warn!("No EXECUTOR_PRIVATE_KEY found, using the public dev key");
+        return Ok(Some(PrivateKeySigner::from_str(DEV_EXECUTOR_KEY)?));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{abi_result, called_selector, MockRpc};
+    use serde_json::Value;
+
+    async fn executor() -> SafeExecutor {
+        executor_at("http://127.0.0.1:9").await
+    }
+
+    async fn executor_at(rpc_url: &str) -> SafeExecutor {
+        let rpc = Arc::new(RpcPool::new(&[rpc_url.to_string()]).unwrap());
+        SafeExecutor::new(rpc, "0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn executor_without_a_key_has_no_relayer() {
+        let err = executor().await.relayer_address().unwrap_err();
+        assert!(err.to_string().contains("EXECUTOR_PRIVATE_KEY"));
+    }
+
+    #[test]
+    fn production_refuses_the_dev_key_fallback() {
+        let dev = PrivateKeySigner::from_str(DEV_EXECUTOR_KEY).unwrap();
+        let configured = PrivateKeySigner::random();
+        let configured_key = Some(configured.to_bytes().to_string());
 
-    encoded
+        let err = executor_signer(None, true, DeploymentMode::Production).unwrap_err();
+        assert!(err.to_string().contains("USE_DEV_EXECUTOR_KEY"));
+        let demo = executor_signer(None, true, DeploymentMode::Demo).unwrap();
+        assert_eq!(demo.map(|s| s.address()), Some(dev.address()));
+        // An explicit key never falls back, and nothing is used unless asked for
+        let explicit = executor_signer(configured_key, true, DeploymentMode::Production).unwrap();
+        assert_eq!(explicit.map(|s| s.address()), Some(configured.address()));
+        assert!(executor_signer(None, false, DeploymentMode::Demo)
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn relayer_is_the_configured_signer() {
+        let signer = PrivateKeySigner::random();
+        let executor = executor().await.with_signer(Some(signer.clone()));
+        assert_eq!(executor.relayer_address().unwrap(), signer.address());
+    }
+
+    #[tokio::test]
+    async fn sequential_reads_reuse_one_connection() {
+        let rpc = MockRpc::start(|method, params| match method {
+            "eth_call" if called_selector(params) == ISafe::nonceCall::SELECTOR => {
+                abi_result(ISafe::nonceCall::abi_encode_returns(&U256::from(7)))
+            }
+            _ => Value::Null,
+        })
+        .await;
+        let executor = executor_at(&rpc.url).await;
+
+        for _ in 0..3 {
+            assert_eq!(executor.get_nonce().await.unwrap(), U256::from(7));
+        }
+        assert_eq!(rpc.calls("eth_call").len(), 3);
+        assert_eq!(rpc.connections(), 1);
+    }
+
+    // Node that accepts execTransaction: fills chain id, nonce and gas, reports
+    // `base_fees` from eth_feeHistory and mines whatever is broadcast
+    async fn execution_rpc(base_fees: [&'static str; 2]) -> MockRpc {
+        MockRpc::start(move |method, params| match method {
+            "eth_chainId" => Value::from("0x3e9"),
+            "eth_getTransactionCount" => Value::from("0x0"),
+            "eth_estimateGas" => Value::from("0x30d40"),
+            "eth_gasPrice" => Value::from("0x5d21dba00"),
+            "eth_feeHistory" => serde_json::json!({
+                "oldestBlock": "0x1",
+                "baseFeePerGas": base_fees,
+                "gasUsedRatio": [0.5],
+            }),
+            "eth_sendRawTransaction" => {
+                let raw = Bytes::from_str(params[0].as_str().unwrap()).unwrap();
+                Value::from(alloy::primitives::keccak256(raw).to_string())
+            }
+            "eth_getTransactionReceipt" => serde_json::json!({
+                "transactionHash": params[0],
+                "transactionIndex": "0x0",
+                "blockHash": B256::repeat_byte(0xbb),
+                "blockNumber": "0x2",
+                "from": Address::ZERO,
+                "to": Address::repeat_byte(0x5a),
+                "cumulativeGasUsed": "0x30d40",
+                "gasUsed": "0x30d40",
+                "effectiveGasPrice": "0x3b9aca00",
+                "contractAddress": null,
+                "logs": [],
+                "logsBloom": alloy::primitives::Bloom::ZERO,
+                "status": "0x1",
+                "type": "0x2",
+            }),
+            _ => Value::Null,
+        })
+        .await
+    }
+
+    async fn execute(rpc: &MockRpc) -> (B256, alloy::consensus::TxEnvelope) {
+        use alloy::eips::eip2718::Decodable2718;
+
+        let executor = executor_at(&rpc.url)
+            .await
+            .with_signer(Some(PrivateKeySigner::random()));
+        let tx = SafeTransaction {
+            to: Address::repeat_byte(0x11),
+            value: U256::from(1000),
+            data: Bytes::new(),
+            operation: crate::safe_contract::Operation::Call,
+            safe_tx_gas: U256::ZERO,
+            base_gas: U256::ZERO,
+            gas_price: U256::ZERO,
+            gas_token: Address::ZERO,
+            refund_receiver: Address::ZERO,
+            nonce: U256::ZERO,
+        };
+        let signatures =
+            EncodedSignatures::new(Bytes::from([[0x11; 64].as_slice(), &[27]].concat()), 1)
+                .unwrap();
+
+        let tx_hash = executor
+            .execute_transaction(&tx, &signatures)
+            .await
+            .unwrap();
+        let sent = rpc.calls("eth_sendRawTransaction");
+        assert_eq!(sent.len(), 1, "broadcast exactly once");
+        let raw = Bytes::from_str(sent[0][0].as_str().unwrap()).unwrap();
+        let envelope = alloy::consensus::TxEnvelope::decode_2718(&mut raw.as_ref()).unwrap();
+        (tx_hash, envelope)
+    }
+
+    #[tokio::test]
+    async fn execution_is_priced_from_the_next_base_fee() {
+        // 2 gwei next base fee, doubled, plus the default 1 gwei tip
+        let rpc = execution_rpc(["0x3b9aca00", "0x77359400"]).await;
+        let (tx_hash, sent) = execute(&rpc).await;
+
+        assert_eq!(tx_hash, *sent.tx_hash());
+        let sent = sent.as_eip1559().expect("EIP-1559 transaction");
+        assert_eq!(sent.tx().max_fee_per_gas, 5_000_000_000);
+        assert_eq!(sent.tx().max_priority_fee_per_gas, DEFAULT_PRIORITY_FEE_TIP);
+        assert_eq!(sent.tx().to, Address::repeat_byte(0x5a).into());
+    }
+
+    #[tokio::test]
+    async fn execution_without_a_base_fee_uses_legacy_pricing() {
+        let rpc = execution_rpc(["0x0", "0x0"]).await;
+        let (_, sent) = execute(&rpc).await;
+
+        let sent = sent.as_legacy().expect("legacy transaction");
+        assert_eq!(sent.tx().gas_price, 25_000_000_000);
+        assert_eq!(rpc.calls("eth_gasPrice").len(), 1);
+    }
 }
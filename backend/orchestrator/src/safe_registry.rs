@@ -0,0 +1,296 @@
+use alloy::primitives::{Address, U256};
+use anyhow::Result;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{Mutex, RwLock};
+use tracing::warn;
+
+use crate::{
+    rpc_pool::RpcPool,
+    safe_contract_abi::{OnchainSafeState, SafeExecutor},
+};
+
+const SAFE_STATE_TTL: Duration = Duration::from_secs(30);
+
+// Owners/threshold that differ from the registry's baseline for the Safe
+#[derive(Debug, Clone, Serialize)]
+pub struct SafeDrift {
+    pub safe: Address,
+    pub added_owners: Vec<Address>,
+    pub removed_owners: Vec<Address>,
+    pub previous_threshold: U256,
+    pub threshold: U256,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SafeSummary {
+    pub address: String,
+    pub default: bool,
+    pub threshold: Option<String>,
+    pub owner_count: Option<usize>,
+    pub nonce: Option<String>,
+}
+
+// Owners and threshold a Safe is expected to have
+#[derive(Debug, Clone)]
+struct Baseline {
+    owners: Vec<Address>,
+    threshold: U256,
+}
+
+// All Safes this orchestrator knows about, with lazily fetched on-chain state.
+// The cache only serves reads; drift is judged against a separate baseline,
+// seeded from the configured signers for the default Safe (other Safes take
+// their first read), and every fetch is compared against it.
+pub struct SafeRegistry {
+    default_safe: Address,
+    executors: Vec<(Address, Arc<SafeExecutor>)>,
+    cache: RwLock<HashMap<Address, (Instant, OnchainSafeState)>>,
+    baselines: Mutex<HashMap<Address, Baseline>>,
+    // Drift seen by a fetch, until check_drift reports it
+    pending_drift: Mutex<Vec<SafeDrift>>,
+}
+
+impl SafeRegistry {
+    pub async fn new(
+        rpc: Arc<RpcPool>,
+        default_safe: Address,
+        default_executor: Arc<SafeExecutor>,
+        additional: &[Address],
+        configured_owners: &[Address],
+        configured_threshold: U256,
+    ) -> Result<Self> {
+        let mut executors = vec![(default_safe, default_executor)];
+        for address in additional {
+            if executors.iter().any(|(a, _)| a == address) {
+                continue;
+            }
+            let executor = SafeExecutor::new(rpc.clone(), &address.to_string()).await?;
+            executors.push((*address, Arc::new(executor)));
+        }
+
+        let baseline = Baseline {
+            owners: configured_owners.to_vec(),
+            threshold: configured_threshold,
+        };
+        Ok(Self {
+            default_safe,
+            executors,
+            cache: RwLock::new(HashMap::new()),
+            baselines: Mutex::new(HashMap::from([(default_safe, baseline)])),
+            pending_drift: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn executor(&self, address: Address) -> Option<&Arc<SafeExecutor>> {
+        self.executors
+            .iter()
+            .find(|(a, _)| *a == address)
+            .map(|(_, executor)| executor)
+    }
+
+    pub async fn state_of(&self, address: Address) -> Option<OnchainSafeState> {
+        if let Some((fetched_at, cached)) = self.cache.read().await.get(&address) {
+            if fetched_at.elapsed() < SAFE_STATE_TTL {
+                return Some(cached.clone());
+            }
+        }
+
+        let (_, executor) = self.executors.iter().find(|(a, _)| *a == address)?;
+        match executor.get_safe_state().await {
+            Ok(fresh) => {
+                self.observe(address, &fresh).await;
+                Some(fresh)
+            }
+            Err(e) => {
+                warn!("Failed to fetch state for Safe {}: {}", address, e);
+                None
+            }
+        }
+    }
+
+    // Caches a fresh read and compares it against the baseline, which then moves
+    // to the new state so each change is reported once
+    async fn observe(&self, address: Address, fresh: &OnchainSafeState) {
+        self.cache
+            .write()
+            .await
+            .insert(address, (Instant::now(), fresh.clone()));
+
+        let current = Baseline {
+            owners: fresh.owners.clone(),
+            threshold: fresh.threshold,
+        };
+        let Some(previous) = self.baselines.lock().await.insert(address, current) else {
+            return;
+        };
+
+        let added_owners: Vec<Address> = fresh
+            .owners
+            .iter()
+            .filter(|o| !previous.owners.contains(o))
+            .copied()
+            .collect();
+        let removed_owners: Vec<Address> = previous
+            .owners
+            .iter()
+            .filter(|o| !fresh.owners.contains(o))
+            .copied()
+            .collect();
+
+        if !added_owners.is_empty()
+            || !removed_owners.is_empty()
+            || previous.threshold != fresh.threshold
+        {
+            self.pending_drift.lock().await.push(SafeDrift {
+                safe: address,
+                added_owners,
+                removed_owners,
+                previous_threshold: previous.threshold,
+                threshold: fresh.threshold,
+            });
+        }
+    }
+
+    // Re-reads every Safe from the chain, bypassing the TTL, and returns every
+    // owner or threshold change seen since the last call, including those picked
+    // up by regular reads in between
+    pub async fn check_drift(&self) -> Vec<SafeDrift> {
+        for (address, executor) in &self.executors {
+            match executor.get_safe_state().await {
+                Ok(fresh) => self.observe(*address, &fresh).await,
+                Err(e) => warn!("Drift check skipped for Safe {}: {}", address, e),
+            }
+        }
+        std::mem::take(&mut *self.pending_drift.lock().await)
+    }
+
+    pub async fn list(&self) -> Vec<SafeSummary> {
+        let mut summaries = Vec::with_capacity(self.executors.len());
+        for (address, _) in &self.executors {
+            let onchain = self.state_of(*address).await;
+            summaries.push(SafeSummary {
+                address: address.to_string(),
+                default: *address == self.default_safe,
+                threshold: onchain.as_ref().map(|s| s.threshold.to_string()),
+                owner_count: onchain.as_ref().map(|s| s.owners.len()),
+                nonce: onchain.as_ref().map(|s| s.nonce.to_string()),
+            });
+        }
+        summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAFE: Address = Address::repeat_byte(0x5a);
+
+    async fn registry(configured: &[Address]) -> SafeRegistry {
+        registry_with(&[], configured).await
+    }
+
+    async fn registry_with(additional: &[Address], configured: &[Address]) -> SafeRegistry {
+        let rpc = Arc::new(RpcPool::new(&["http://127.0.0.1:9".to_string()]).unwrap());
+        let executor = Arc::new(
+            SafeExecutor::new(rpc.clone(), &SAFE.to_string())
+                .await
+                .unwrap(),
+        );
+        SafeRegistry::new(rpc, SAFE, executor, additional, configured, U256::from(2))
+            .await
+            .unwrap()
+    }
+
+    fn onchain(owners: &[Address], threshold: u64) -> OnchainSafeState {
+        OnchainSafeState {
+            owners: owners.to_vec(),
+            threshold: U256::from(threshold),
+            nonce: U256::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn first_read_is_compared_with_configured_signers() {
+        let (a, b, c) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+        let registry = registry(&[a, b]).await;
+
+        registry.observe(SAFE, &onchain(&[a, c], 2)).await;
+
+        let drift = std::mem::take(&mut *registry.pending_drift.lock().await);
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].added_owners, vec![c]);
+        assert_eq!(drift[0].removed_owners, vec![b]);
+    }
+
+    #[tokio::test]
+    async fn cached_reads_do_not_swallow_drift() {
+        let (a, b, c) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+        let registry = registry(&[a, b]).await;
+        registry.observe(SAFE, &onchain(&[a, b], 2)).await;
+        assert!(registry.pending_drift.lock().await.is_empty());
+
+        // An owner is added and a page load refreshes the cache before the drift loop runs
+        registry.observe(SAFE, &onchain(&[a, b, c], 3)).await;
+        registry.observe(SAFE, &onchain(&[a, b, c], 3)).await;
+
+        let drift = std::mem::take(&mut *registry.pending_drift.lock().await);
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].added_owners, vec![c]);
+        assert_eq!(drift[0].previous_threshold, U256::from(2));
+        assert_eq!(drift[0].threshold, U256::from(3));
+    }
+
+    #[tokio::test]
+    async fn every_configured_safe_is_listed() {
+        let other = Address::repeat_byte(0x6b);
+        let owners: Vec<Address> = (1..=3).map(Address::repeat_byte).collect();
+        // The default Safe repeated in the extra list isn't listed twice
+        let registry = registry_with(&[other, SAFE], &owners[..2]).await;
+        registry.observe(SAFE, &onchain(&owners[..2], 2)).await;
+        registry.observe(other, &onchain(&owners, 3)).await;
+
+        let listed = registry.list().await;
+
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].address, SAFE.to_string());
+        assert!(listed[0].default);
+        assert_eq!(listed[0].owner_count, Some(2));
+        assert_eq!(listed[0].threshold.as_deref(), Some("2"));
+        assert_eq!(listed[1].address, other.to_string());
+        assert!(!listed[1].default);
+        assert_eq!(listed[1].owner_count, Some(3));
+        assert_eq!(listed[1].threshold.as_deref(), Some("3"));
+    }
+
+    #[tokio::test]
+    async fn unreadable_safe_is_listed_without_state() {
+        let other = Address::repeat_byte(0x6b);
+        let registry = registry_with(&[other], &[]).await;
+        registry
+            .observe(SAFE, &onchain(&[Address::repeat_byte(1)], 1))
+            .await;
+
+        let listed = registry.list().await;
+
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].owner_count, Some(1));
+        assert_eq!(listed[1].address, other.to_string());
+        assert_eq!(listed[1].owner_count, None);
+        assert_eq!(listed[1].threshold, None);
+        assert_eq!(listed[1].nonce, None);
+    }
+}
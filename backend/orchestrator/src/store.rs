@@ -0,0 +1,667 @@
+use alloy_primitives::B256;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use shared::types::TransactionStatus;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePool},
+    Row,
+};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::{safe_contract::Signature, TransactionState};
+
+// Persistence for proposals. Handlers only see `dyn Store`, so a new backend
+// (e.g. Postgres) is just another implementation selected in `from_env`.
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn insert(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()>;
+    async fn get(&self, tx_id: &str) -> Result<Option<TransactionState>>;
+    // Replaces the whole stored record
+    async fn update(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()>;
+    async fn add_signature(&self, tx_id: &str, signature: Signature) -> Result<()>;
+    // Status-only change, applied only if the stored status is still `from_expected`
+    // and the state machine allows it
+    async fn transition_status(
+        &self,
+        tx_id: &str,
+        from_expected: TransactionStatus,
+        to: TransactionStatus,
+    ) -> Result<()>;
+    // All proposals, oldest first
+    async fn list(&self) -> Result<Vec<(String, TransactionState)>>;
+    // The in-flight proposal for the same SafeTx (see SafeTransaction::content_hash)
+    async fn find_in_flight(
+        &self,
+        content_hash: B256,
+    ) -> Result<Option<(String, TransactionState)>> {
+        Ok(self.list().await?.into_iter().find(|(_, tx)| {
+            tx.status.is_in_flight() && tx.transaction.content_hash() == content_hash
+        }))
+    }
+}
+
+// STORAGE_BACKEND=memory (default) or sqlite, the latter at SQLITE_DATABASE_URL.
+// LEGACY_IMPORT_PATH optionally seeds the store from an in-memory era dump.
+pub async fn from_env() -> Result<Arc<dyn Store>> {
+    let store = open_from_env().await?;
+    if let Some(path) = std::env::var("LEGACY_IMPORT_PATH")
+        .ok()
+        .filter(|p| !p.is_empty())
+    {
+        import_legacy_dump(store.as_ref(), &path).await?;
+    }
+    Ok(store)
+}
+
+async fn open_from_env() -> Result<Arc<dyn Store>> {
+    match std::env::var("STORAGE_BACKEND").as_deref() {
+        Ok("sqlite") => {
+            let url = std::env::var("SQLITE_DATABASE_URL")
+                .unwrap_or_else(|_| "sqlite://orchestrator.db".to_string());
+            let store = SqliteStore::connect(&url).await?;
+
+            let in_flight = store
+                .list()
+                .await?
+                .iter()
+                .filter(|(_, tx)| tx.status.is_in_flight())
+                .count();
+            info!(
+                "Using SQLite transaction store at {} ({} in-flight proposals restored)",
+                url, in_flight
+            );
+            Ok(Arc::new(store))
+        }
+        Ok("memory") | Err(_) => {
+            warn!("Using in-memory transaction store; proposals are lost on restart");
+            Ok(Arc::new(MemoryStore::default()))
+        }
+        Ok(other) => Err(anyhow!("Unknown STORAGE_BACKEND: {}", other)),
+    }
+}
+
+// Loads a JSON object of tx_id -> transaction state, as the in-memory store kept
+// it. Ids already in the store are left alone, so the import is safe to rerun.
+pub async fn import_legacy_dump(store: &dyn Store, path: &str) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read legacy dump {}", path))?;
+    let dump: HashMap<String, TransactionState> = serde_json::from_str(&contents)
+        .with_context(|| format!("Malformed legacy dump {}", path))?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    for (tx_id, tx_state) in dump {
+        if store.get(&tx_id).await?.is_some() {
+            skipped += 1;
+            continue;
+        }
+        store.insert(&tx_id, &tx_state).await?;
+        imported += 1;
+    }
+
+    info!(
+        "Imported {} proposals from legacy dump {} ({} already present)",
+        imported, path, skipped
+    );
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    transactions: RwLock<HashMap<String, TransactionState>>,
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn insert(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()> {
+        let mut txs = self.transactions.write().await;
+        if txs.contains_key(tx_id) {
+            return Err(anyhow!("Transaction {} already exists", tx_id));
+        }
+        txs.insert(tx_id.to_string(), tx_state.clone());
+        Ok(())
+    }
+
+    async fn get(&self, tx_id: &str) -> Result<Option<TransactionState>> {
+        Ok(self.transactions.read().await.get(tx_id).cloned())
+    }
+
+    async fn update(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()> {
+        let mut txs = self.transactions.write().await;
+        let stored = txs
+            .get_mut(tx_id)
+            .ok_or_else(|| anyhow!("Transaction {} not found", tx_id))?;
+        *stored = tx_state.clone();
+        Ok(())
+    }
+
+    async fn add_signature(&self, tx_id: &str, signature: Signature) -> Result<()> {
+        let mut txs = self.transactions.write().await;
+        let stored = txs
+            .get_mut(tx_id)
+            .ok_or_else(|| anyhow!("Transaction {} not found", tx_id))?;
+        stored.signatures.push(signature);
+        Ok(())
+    }
+
+    async fn transition_status(
+        &self,
+        tx_id: &str,
+        from_expected: TransactionStatus,
+        to: TransactionStatus,
+    ) -> Result<()> {
+        let mut txs = self.transactions.write().await;
+        let stored = txs
+            .get_mut(tx_id)
+            .ok_or_else(|| anyhow!("Transaction {} not found", tx_id))?;
+        stored.status = stored.status.transition(from_expected, to)?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<(String, TransactionState)>> {
+        let mut all: Vec<_> = self
+            .transactions
+            .read()
+            .await
+            .iter()
+            .map(|(id, tx)| (id.clone(), tx.clone()))
+            .collect();
+        all.sort_by_key(|(_, tx)| tx.created_at);
+        Ok(all)
+    }
+}
+
+// Each proposal is one row: the full state as JSON, plus status, created_at and
+// content_hash columns so they can be queried without decoding
+pub struct SqliteStore {
+    pool: SqlitePool,
+}
+
+impl SqliteStore {
+    pub async fn connect(url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS transactions (
+                tx_id TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                state TEXT NOT NULL,
+                content_hash TEXT
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        // Databases created before the content_hash column get it added
+        let has_content_hash = sqlx::query(
+            "SELECT 1 FROM pragma_table_info('transactions') WHERE name = 'content_hash'",
+        )
+        .fetch_optional(&pool)
+        .await?
+        .is_some();
+        if !has_content_hash {
+            sqlx::query("ALTER TABLE transactions ADD COLUMN content_hash TEXT")
+                .execute(&pool)
+                .await?;
+        }
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS transactions_content_hash ON transactions (content_hash)",
+        )
+        .execute(&pool)
+        .await?;
+
+        let store = Self { pool };
+        store.rebuild_index_columns().await?;
+        Ok(store)
+    }
+
+    // The status and content_hash columns are derived from the stored state, and
+    // are recomputed on startup so rows written by older versions (former status
+    // names, no content hash) are found by the indexed lookups
+    async fn rebuild_index_columns(&self) -> Result<()> {
+        let mut db_tx = self.pool.begin().await?;
+        let rows = sqlx::query("SELECT tx_id, status, content_hash, state FROM transactions")
+            .fetch_all(&mut *db_tx)
+            .await?;
+
+        let mut rebuilt = 0;
+        for row in &rows {
+            let tx_state: TransactionState = serde_json::from_str(row.get("state"))?;
+            let status = status_column(tx_state.status);
+            let content_hash = content_hash_column(&tx_state);
+            if row.get::<String, _>("status") == status
+                && row.get::<Option<String>, _>("content_hash").as_ref() == Some(&content_hash)
+            {
+                continue;
+            }
+            sqlx::query("UPDATE transactions SET status = ?, content_hash = ? WHERE tx_id = ?")
+                .bind(status)
+                .bind(content_hash)
+                .bind(row.get::<String, _>("tx_id"))
+                .execute(&mut *db_tx)
+                .await?;
+            rebuilt += 1;
+        }
+
+        db_tx.commit().await?;
+        if rebuilt > 0 {
+            info!("Rebuilt index columns of {} stored proposals", rebuilt);
+        }
+        Ok(())
+    }
+
+    // Read-modify-write inside one SQLite transaction. IMMEDIATE takes the write
+    // lock before the read, so concurrent writers queue up instead of failing
+    // when they try to upgrade a shared lock.
+    async fn modify(
+        &self,
+        tx_id: &str,
+        f: impl FnOnce(&mut TransactionState) -> Result<()>,
+    ) -> Result<()> {
+        let mut db_tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+
+        let row = sqlx::query("SELECT state FROM transactions WHERE tx_id = ?")
+            .bind(tx_id)
+            .fetch_optional(&mut *db_tx)
+            .await?
+            .ok_or_else(|| anyhow!("Transaction {} not found", tx_id))?;
+        let mut tx_state: TransactionState = serde_json::from_str(row.get("state"))?;
+        f(&mut tx_state)?;
+
+        sqlx::query(
+            "UPDATE transactions SET status = ?, content_hash = ?, state = ? WHERE tx_id = ?",
+        )
+        .bind(status_column(tx_state.status))
+        .bind(content_hash_column(&tx_state))
+        .bind(serde_json::to_string(&tx_state)?)
+        .bind(tx_id)
+        .execute(&mut *db_tx)
+        .await?;
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+}
+
+fn status_column(status: TransactionStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn content_hash_column(tx_state: &TransactionState) -> String {
+    tx_state.transaction.content_hash().to_string()
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn insert(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO transactions (tx_id, status, created_at, state, content_hash)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(tx_id)
+        .bind(status_column(tx_state.status))
+        .bind(tx_state.created_at.to_rfc3339())
+        .bind(serde_json::to_string(tx_state)?)
+        .bind(content_hash_column(tx_state))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get(&self, tx_id: &str) -> Result<Option<TransactionState>> {
+        let row = sqlx::query("SELECT state FROM transactions WHERE tx_id = ?")
+            .bind(tx_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|r| Ok(serde_json::from_str(r.get("state"))?))
+            .transpose()
+    }
+
+    async fn update(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()> {
+        self.modify(tx_id, |stored| {
+            *stored = tx_state.clone();
+            Ok(())
+        })
+        .await
+    }
+
+    async fn add_signature(&self, tx_id: &str, signature: Signature) -> Result<()> {
+        self.modify(tx_id, |tx_state| {
+            tx_state.signatures.push(signature);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn transition_status(
+        &self,
+        tx_id: &str,
+        from_expected: TransactionStatus,
+        to: TransactionStatus,
+    ) -> Result<()> {
+        self.modify(tx_id, |tx_state| {
+            tx_state.status = tx_state.status.transition(from_expected, to)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn list(&self) -> Result<Vec<(String, TransactionState)>> {
+        let rows = sqlx::query("SELECT tx_id, state FROM transactions ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|r| Ok((r.get("tx_id"), serde_json::from_str(r.get("state"))?)))
+            .collect()
+    }
+
+    async fn find_in_flight(
+        &self,
+        content_hash: B256,
+    ) -> Result<Option<(String, TransactionState)>> {
+        let rows = sqlx::query(
+            "SELECT tx_id, state FROM transactions WHERE content_hash = ? ORDER BY created_at",
+        )
+        .bind(content_hash.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        for row in rows {
+            let tx_state: TransactionState = serde_json::from_str(row.get("state"))?;
+            if tx_state.status.is_in_flight() {
+                return Ok(Some((row.get("tx_id"), tx_state)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::proposal;
+    use alloy_primitives::{Address, Bytes};
+
+    fn database_url() -> String {
+        let path = std::env::temp_dir().join(format!("store-{}.db", uuid::Uuid::new_v4()));
+        format!("sqlite://{}", path.display())
+    }
+
+    #[tokio::test]
+    async fn sqlite_store_reloads_state_after_restart() {
+        let url = database_url();
+        let pending = proposal(0);
+        let mut executed = proposal(1);
+        executed.status = TransactionStatus::Executed;
+
+        {
+            let store = SqliteStore::connect(&url).await.unwrap();
+            store.insert("a", &pending).await.unwrap();
+            store.insert("b", &executed).await.unwrap();
+            store.add_signature("a", signature(1)).await.unwrap();
+            store
+                .transition_status(
+                    "a",
+                    TransactionStatus::CollectingSignatures,
+                    TransactionStatus::ReadyToExecute,
+                )
+                .await
+                .unwrap();
+            store.pool.close().await;
+        }
+
+        let store = SqliteStore::connect(&url).await.unwrap();
+
+        let reloaded = store.get("a").await.unwrap().unwrap();
+        assert_eq!(reloaded.status, TransactionStatus::ReadyToExecute);
+        assert_eq!(reloaded.signatures.len(), 1);
+        assert_eq!(reloaded.tx_hash, pending.tx_hash);
+        let ids: Vec<_> = store
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, ["a", "b"]);
+
+        let (tx_id, _) = store
+            .find_in_flight(pending.transaction.content_hash())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tx_id, "a");
+        // Executed proposals no longer hold their SafeTx
+        assert!(store
+            .find_in_flight(executed.transaction.content_hash())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    // Every backend, fresh, so the trait's contract is checked against each of them
+    async fn backends() -> Vec<(&'static str, Arc<dyn Store>)> {
+        vec![
+            ("memory", Arc::new(MemoryStore::default())),
+            (
+                "sqlite",
+                Arc::new(SqliteStore::connect(&database_url()).await.unwrap()),
+            ),
+        ]
+    }
+
+    fn signature(n: u8) -> Signature {
+        Signature {
+            signer: Address::repeat_byte(n),
+            signature: Bytes::from(vec![0u8; 65]),
+            recovered: None,
+            signed_at: None,
+            signed_hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_writes_are_not_lost() {
+        for (backend, store) in backends().await {
+            store.insert("a", &proposal(0)).await.unwrap();
+
+            // No handler-level lock here; the store has to serialize these itself
+            let writers: Vec<_> = (1..=8u8)
+                .map(|n| {
+                    let store = store.clone();
+                    tokio::spawn(async move { store.add_signature("a", signature(n)).await })
+                })
+                .collect();
+            for writer in writers {
+                writer.await.unwrap().unwrap();
+            }
+
+            let stored = store.get("a").await.unwrap().unwrap();
+            assert_eq!(stored.signatures.len(), 8, "{}", backend);
+        }
+    }
+
+    #[tokio::test]
+    async fn update_replaces_an_existing_record_only() {
+        for (backend, store) in backends().await {
+            let mut tx_state = proposal(0);
+            store.insert("a", &tx_state).await.unwrap();
+
+            tx_state.status = TransactionStatus::Cancelled;
+            tx_state.metadata.reference = Some("INV-7".to_string());
+            store.update("a", &tx_state).await.unwrap();
+
+            let stored = store.get("a").await.unwrap().unwrap();
+            assert_eq!(stored.status, TransactionStatus::Cancelled, "{}", backend);
+            assert_eq!(stored.metadata.reference.as_deref(), Some("INV-7"));
+            assert!(store
+                .find_in_flight(tx_state.transaction.content_hash())
+                .await
+                .unwrap()
+                .is_none());
+            assert!(
+                store.update("missing", &tx_state).await.is_err(),
+                "{}",
+                backend
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn insert_refuses_an_existing_id() {
+        for (backend, store) in backends().await {
+            store.insert("a", &proposal(0)).await.unwrap();
+
+            assert!(
+                store.insert("a", &proposal(1)).await.is_err(),
+                "{}",
+                backend
+            );
+            let stored = store.get("a").await.unwrap().unwrap();
+            assert_eq!(stored.transaction.nonce, proposal(0).transaction.nonce);
+            assert!(store.get("missing").await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn transition_requires_the_expected_status() {
+        for (backend, store) in backends().await {
+            store.insert("a", &proposal(0)).await.unwrap();
+
+            // Stale expectation: nothing changes
+            assert!(
+                store
+                    .transition_status(
+                        "a",
+                        TransactionStatus::ReadyToExecute,
+                        TransactionStatus::Executing,
+                    )
+                    .await
+                    .is_err(),
+                "{}",
+                backend
+            );
+            // Not an edge of the state machine
+            assert!(store
+                .transition_status(
+                    "a",
+                    TransactionStatus::CollectingSignatures,
+                    TransactionStatus::Executing,
+                )
+                .await
+                .is_err());
+            assert!(store
+                .transition_status(
+                    "missing",
+                    TransactionStatus::CollectingSignatures,
+                    TransactionStatus::Cancelled,
+                )
+                .await
+                .is_err());
+            let stored = store.get("a").await.unwrap().unwrap();
+            assert_eq!(stored.status, TransactionStatus::CollectingSignatures);
+
+            store
+                .transition_status(
+                    "a",
+                    TransactionStatus::CollectingSignatures,
+                    TransactionStatus::Cancelled,
+                )
+                .await
+                .unwrap();
+            let stored = store.get("a").await.unwrap().unwrap();
+            assert_eq!(stored.status, TransactionStatus::Cancelled, "{}", backend);
+        }
+    }
+
+    #[tokio::test]
+    async fn list_is_oldest_first_and_finds_in_flight_proposals() {
+        for (backend, store) in backends().await {
+            let newer = proposal(0);
+            let mut older = proposal(1);
+            older.created_at = newer.created_at - chrono::Duration::minutes(5);
+            store.insert("newer", &newer).await.unwrap();
+            store.insert("older", &older).await.unwrap();
+
+            let ids: Vec<_> = store
+                .list()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            assert_eq!(ids, ["older", "newer"], "{}", backend);
+
+            let (tx_id, _) = store
+                .find_in_flight(older.transaction.content_hash())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(tx_id, "older", "{}", backend);
+            assert!(store
+                .find_in_flight(B256::repeat_byte(0xcd))
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn reconnect_rebuilds_index_columns_of_old_rows() {
+        let url = database_url();
+        let tx_state = proposal(0);
+
+        // Table as written before the content_hash column and snake_case statuses
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::from_str(&url)
+                .unwrap()
+                .create_if_missing(true),
+        )
+        .await
+        .unwrap();
+        sqlx::query(
+            "CREATE TABLE transactions (
+                tx_id TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                state TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO transactions VALUES ('old', 'CollectingSignatures', ?, ?)")
+            .bind(tx_state.created_at.to_rfc3339())
+            .bind(serde_json::to_string(&tx_state).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let store = SqliteStore::connect(&url).await.unwrap();
+
+        let row = sqlx::query("SELECT status, content_hash FROM transactions WHERE tx_id = 'old'")
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("status"), "collecting_signatures");
+        assert_eq!(
+            row.get::<Option<String>, _>("content_hash"),
+            Some(tx_state.transaction.content_hash().to_string())
+        );
+        let (tx_id, _) = store
+            .find_in_flight(tx_state.transaction.content_hash())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tx_id, "old");
+    }
+}
@@ -0,0 +1,2994 @@
+// Handler-level tests against an in-memory store. The RPC pool points at a
+// closed local port, so anything that reaches the chain fails fast.
+use super::*;
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use shared::types::AdminOperation;
+use std::time::Duration;
+use store::MemoryStore;
+
+const SAFE: Address = Address::repeat_byte(0x5a);
+const MULTISEND: Address = Address::repeat_byte(0x3d);
+
+// Deterministic keys: 1 and 2 are the humans, 3..=5 the AI agents
+fn key(n: u8) -> PrivateKeySigner {
+    PrivateKeySigner::from_bytes(&B256::with_last_byte(n)).unwrap()
+}
+
+// JSON-RPC endpoint on a local port. Every call is recorded with the peer it
+// came from, and answered with whatever `respond` returns for its method and params.
+pub(crate) struct MockRpc {
+    pub url: String,
+    calls: Arc<std::sync::Mutex<Vec<(std::net::SocketAddr, serde_json::Value)>>>,
+}
+
+impl MockRpc {
+    pub async fn start(
+        respond: impl Fn(&str, &serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    ) -> Self {
+        Self::start_fallible(move |method, params| Ok(respond(method, params))).await
+    }
+
+    // Like `start`, but `Err` is sent back as the JSON-RPC error object
+    pub async fn start_fallible(
+        respond: impl Fn(&str, &serde_json::Value) -> Result<serde_json::Value, serde_json::Value>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        use axum::extract::ConnectInfo;
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let respond = Arc::new(respond);
+        let app = Router::new().route(
+            "/",
+            post(
+                move |ConnectInfo(peer): ConnectInfo<std::net::SocketAddr>,
+                      Json(call): Json<serde_json::Value>| async move {
+                    let result =
+                        respond(call["method"].as_str().unwrap_or_default(), &call["params"]);
+                    let id = call["id"].clone();
+                    recorded.lock().unwrap().push((peer, call));
+                    Json(match result {
+                        Ok(result) => {
+                            serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result })
+                        }
+                        Err(error) => {
+                            serde_json::json!({ "jsonrpc": "2.0", "id": id, "error": error })
+                        }
+                    })
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+        Self { url, calls }
+    }
+
+    // Params of every call to `method`, in order
+    pub fn calls(&self, method: &str) -> Vec<serde_json::Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, call)| call["method"] == method)
+            .map(|(_, call)| call["params"].clone())
+            .collect()
+    }
+
+    // Distinct TCP connections the calls arrived on
+    pub fn connections(&self) -> usize {
+        let calls = self.calls.lock().unwrap();
+        calls
+            .iter()
+            .map(|(peer, _)| *peer)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+// Hex string a node sends back for ABI-encoded return data
+pub(crate) fn abi_result(encoded: Vec<u8>) -> serde_json::Value {
+    serde_json::Value::from(Bytes::from(encoded).to_string())
+}
+
+// Selector of the function an eth_call invokes
+pub(crate) fn called_selector(params: &serde_json::Value) -> [u8; 4] {
+    let input = params[0]["input"].as_str().or(params[0]["data"].as_str());
+    let input = Bytes::from_str(input.unwrap_or_default()).unwrap();
+    input[..4].try_into().unwrap()
+}
+
+async fn test_state() -> AppState {
+    test_state_with_rpc("http://127.0.0.1:9").await
+}
+
+async fn test_state_with_rpc(rpc_url: &str) -> AppState {
+    test_state_with_relayer(rpc_url, None).await
+}
+
+async fn test_state_with_relayer(rpc_url: &str, relayer: Option<PrivateKeySigner>) -> AppState {
+    let rpc = Arc::new(
+        RpcPool::new(&[rpc_url.to_string()])
+            .unwrap()
+            .with_retry(1, Duration::ZERO),
+    );
+    let safe_executor = Arc::new(
+        SafeExecutor::new(rpc.clone(), &SAFE.to_string())
+            .await
+            .unwrap()
+            .with_signer(relayer),
+    );
+    let safe_registry = SafeRegistry::new(
+        rpc,
+        SAFE,
+        safe_executor.clone(),
+        &[],
+        &(1..=5).map(|n| key(n).address()).collect::<Vec<_>>(),
+        U256::from(REQUIRED_SIGNATURES),
+    )
+    .await
+    .unwrap();
+    let store = Arc::new(EventingStore::new(Arc::new(MemoryStore::default())));
+
+    AppState {
+        safe_address: SAFE,
+        store: store.clone(),
+        tx_events: store,
+        mutation_lock: Mutex::new(()),
+        signer_addresses: SignerAddresses {
+            human1: key(1).address(),
+            human2: key(2).address(),
+            ai_cfo: key(3).address(),
+            ai_security: key(4).address(),
+            ai_analyst: key(5).address(),
+        },
+        safe_executor,
+        queue_stale_after_secs: 900,
+        execution_grace_secs: 0,
+        signature_timeout_secs: SIGNATURE_COLLECTION_TIMEOUT_SECS as i64,
+        auto_extend: None,
+        tracked_tokens: Vec::new(),
+        tokens: Arc::new(TokenRegistry::new(Default::default())),
+        gas_refund: GasRefundConfig {
+            gas_price: U256::ZERO,
+            gas_token: Address::ZERO,
+        },
+        chain_id: KAIA_TESTNET_CHAIN_ID,
+        verify_hash_onchain: false,
+        safe_registry,
+        notifier: Arc::new(Notifier::new(None, None)),
+        ai_agents: AiAgentsClient::new("http://127.0.0.1:9".to_string(), Duration::from_secs(1))
+            .unwrap(),
+        ai_failure_policy: AiFailurePolicy::Hold,
+        require_ai_approval: false,
+        require_proposer_signature: false,
+        allow_zero_value_calls: true,
+        mandatory_review: HashSet::new(),
+        gas_multiplier: 1.2,
+        max_gas_price: None,
+        health_gas_price: Mutex::new(None),
+        max_proposal_value: None,
+        multisend_address: Some(MULTISEND),
+        delegatecall_targets: HashSet::from([MULTISEND]),
+        admin_token: Some("admin-secret".to_string()),
+        preferred_signature_scheme: SignatureScheme::TypedDataV4,
+        deployment_mode: DeploymentMode::Demo,
+    }
+}
+
+// Headers proving control of `signer` for the notification preference endpoints
+fn preference_auth(signer: &PrivateKeySigner) -> HeaderMap {
+    let timestamp = Utc::now().timestamp();
+    let message = format!(
+        "sentinel-safe notification preferences {} {}",
+        signer.address().to_checksum(None),
+        timestamp
+    );
+    let signature = signer.sign_message_sync(message.as_bytes()).unwrap();
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-signer-timestamp", timestamp.to_string().parse().unwrap());
+    headers.insert(
+        "x-signer-signature",
+        format!("0x{}", hex::encode(signature.as_bytes()))
+            .parse()
+            .unwrap(),
+    );
+    headers
+}
+
+#[tokio::test]
+async fn non_signer_cannot_set_notification_preferences() {
+    let state = Arc::new(test_state().await);
+    let outsider = key(9);
+
+    let result = update_notification_preferences(
+        State(state),
+        Path(outsider.address().to_string()),
+        preference_auth(&outsider),
+        Json(NotificationPreferences {
+            webhook_url: Some("https://93.184.216.34/hook".to_string()),
+            ..Default::default()
+        }),
+    )
+    .await;
+
+    assert_eq!(result.err(), Some(StatusCode::FORBIDDEN));
+}
+
+#[tokio::test]
+async fn signer_webhook_must_be_public() {
+    let state = Arc::new(test_state().await);
+    let human = key(1);
+    let prefs = |url: &str| {
+        Json(NotificationPreferences {
+            webhook_url: Some(url.to_string()),
+            ..Default::default()
+        })
+    };
+
+    let internal = update_notification_preferences(
+        State(state.clone()),
+        Path(human.address().to_string()),
+        preference_auth(&human),
+        prefs("https://169.254.169.254/latest/meta-data"),
+    )
+    .await;
+    assert_eq!(internal.err(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+
+    let public = update_notification_preferences(
+        State(state),
+        Path(human.address().to_string()),
+        preference_auth(&human),
+        prefs("https://93.184.216.34/hook"),
+    )
+    .await;
+    assert!(public.is_ok());
+}
+
+// A minimal proposal collecting signatures, as create_proposal would store it
+pub(crate) fn proposal(nonce: u64) -> TransactionState {
+    let transaction = SafeTransaction {
+        to: Address::repeat_byte(0x11),
+        value: U256::from(1_000u64),
+        data: Bytes::new(),
+        operation: Operation::Call,
+        safe_tx_gas: U256::ZERO,
+        base_gas: U256::ZERO,
+        gas_price: U256::ZERO,
+        gas_token: Address::ZERO,
+        refund_receiver: Address::ZERO,
+        nonce: U256::from(nonce),
+    };
+    TransactionState {
+        tx_hash: transaction
+            .safe_tx_hash(KAIA_TESTNET_CHAIN_ID, SAFE)
+            .to_string(),
+        transaction,
+        signatures: Vec::new(),
+        status: TransactionStatus::CollectingSignatures,
+        ai_analysis: None,
+        created_at: Utc::now(),
+        ready_at: None,
+        invalidation_reason: None,
+        executed_tx_hash: None,
+        metadata: ProposalMetadata::default(),
+        deadline: None,
+        deadline_extensions: 0,
+        audit_log: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn reconcile_requires_admin_token() {
+    let state = Arc::new(test_state().await);
+
+    let result = reconcile(
+        State(state),
+        HeaderMap::new(),
+        Query(ReconcileQuery {
+            lookback_blocks: None,
+        }),
+    )
+    .await;
+
+    assert_eq!(result.err(), Some(StatusCode::UNAUTHORIZED));
+}
+
+#[test]
+fn execution_event_marks_proposal_executed() {
+    let mut tx_state = proposal(0);
+    let event = ExecutionEvent {
+        safe_tx_hash: B256::from_str(&tx_state.tx_hash).unwrap(),
+        success: true,
+        transaction_hash: Some(B256::repeat_byte(0xee)),
+    };
+
+    assert_eq!(tx_state.apply_execution_event(&event), Ok(true));
+    assert_eq!(tx_state.status, TransactionStatus::Executed);
+    assert_eq!(tx_state.executed_tx_hash, Some(B256::repeat_byte(0xee)));
+    // Seeing the same event again changes nothing
+    assert_eq!(tx_state.apply_execution_event(&event), Ok(false));
+}
+
+fn create_request(to: Address, value: u64, data: &[u8]) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        to: to.to_string(),
+        value: value.to_string(),
+        data: Some(hex::encode(data)),
+        reference: None,
+        tags: Vec::new(),
+        proposer: None,
+        proposer_signature: None,
+        proposed_at: None,
+        allow_large_value: false,
+        signature_timeout_secs: None,
+        operation: None,
+        gas: GasParams::default(),
+    }
+}
+
+fn list_query(
+    status: Option<TransactionStatus>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Query<ListTransactionsQuery> {
+    Query(ListTransactionsQuery {
+        reference: None,
+        status,
+        limit,
+        offset,
+    })
+}
+
+#[tokio::test]
+async fn listing_filters_by_status_newest_first() {
+    let state = Arc::new(test_state().await);
+    let now = Utc::now();
+    // tx-0 is the oldest; tx-1 and tx-3 are ready
+    for n in 0..5u64 {
+        let mut tx_state = proposal(n);
+        tx_state.created_at = now - chrono::Duration::minutes(10 - n as i64);
+        if n % 2 == 1 {
+            tx_state.status = TransactionStatus::ReadyToExecute;
+        }
+        state
+            .store
+            .insert(&format!("tx-{}", n), &tx_state)
+            .await
+            .unwrap();
+    }
+    let ids = |list: &TransactionListResponse| -> Vec<String> {
+        list.transactions.iter().map(|t| t.tx_id.clone()).collect()
+    };
+
+    let Json(ready) = list_transactions(
+        State(state.clone()),
+        list_query(Some(TransactionStatus::ReadyToExecute), None, None),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ready.total, 2);
+    assert_eq!(ids(&ready), ["tx-3", "tx-1"]);
+    assert!(ready
+        .transactions
+        .iter()
+        .all(|t| t.status == TransactionStatus::ReadyToExecute));
+
+    let Json(page) = list_transactions(State(state.clone()), list_query(None, Some(2), Some(1)))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 5);
+    assert_eq!((page.limit, page.offset), (2, 1));
+    assert_eq!(ids(&page), ["tx-3", "tx-2"]);
+}
+
+#[tokio::test]
+async fn listing_limit_is_capped_and_offset_may_run_past_the_end() {
+    let state = Arc::new(test_state().await);
+    state.store.insert("tx", &proposal(0)).await.unwrap();
+
+    let Json(defaulted) = list_transactions(State(state.clone()), list_query(None, None, None))
+        .await
+        .unwrap();
+    let Json(capped) = list_transactions(State(state.clone()), list_query(None, Some(1_000), None))
+        .await
+        .unwrap();
+    let Json(past_end) = list_transactions(State(state), list_query(None, None, Some(5)))
+        .await
+        .unwrap();
+
+    assert_eq!(defaulted.limit, 20);
+    assert_eq!(capped.limit, 100);
+    assert_eq!(capped.transactions.len(), 1);
+    assert_eq!(past_end.total, 1);
+    assert!(past_end.transactions.is_empty());
+}
+
+#[tokio::test]
+async fn proposals_are_found_by_reference() {
+    let state = Arc::new(test_state().await);
+    let mut proposals = Vec::new();
+    for (nonce, reference) in [(0, "INV-1001"), (1, "INV-1002")] {
+        let mut req = create_request(Address::repeat_byte(0x11), 1_000, &[]);
+        req.reference = Some(reference.to_string());
+        let created = create_proposal(&state, req, Operation::Call, Some(U256::from(nonce)))
+            .await
+            .unwrap();
+        proposals.push(created);
+    }
+
+    let Json(found) = list_transactions(
+        State(state.clone()),
+        Query(ListTransactionsQuery {
+            reference: Some("INV-1001".to_string()),
+            status: None,
+            limit: None,
+            offset: None,
+        }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found.total, 1);
+    assert_eq!(found.transactions[0].tx_id, proposals[0].tx_id);
+    assert_eq!(
+        found.transactions[0].metadata.reference.as_deref(),
+        Some("INV-1001")
+    );
+    // Metadata only: the signed hash is the same as without a reference
+    assert_eq!(proposals[0].safe_tx_hash, proposal(0).tx_hash);
+}
+
+#[tokio::test]
+async fn delegatecall_outside_allow_list_is_rejected() {
+    let state = Arc::new(test_state().await);
+
+    let result = create_proposal(
+        &state,
+        create_request(Address::repeat_byte(0x66), 0, &[0xde, 0xad, 0xbe, 0xef]),
+        Operation::DelegateCall,
+        Some(U256::ZERO),
+    )
+    .await;
+
+    assert_eq!(result.err(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+}
+
+#[tokio::test]
+async fn listed_delegatecall_needs_every_human() {
+    let target = Address::repeat_byte(0x66);
+    let mut state = test_state().await;
+    state.delegatecall_targets.insert(target);
+    let state = Arc::new(state);
+
+    let created = create_proposal(
+        &state,
+        create_request(target, 0, &[0xde, 0xad, 0xbe, 0xef]),
+        Operation::DelegateCall,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+
+    let stored = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.metadata.admin_operation,
+        Some(AdminOperation::DelegateCall { target })
+    );
+    assert_eq!(state.missing_human_approvals(&stored).len(), 2);
+}
+
+#[tokio::test]
+async fn multisend_batch_is_an_ordinary_proposal() {
+    let state = Arc::new(test_state().await);
+    let batch = MultiSendBuilder::new(
+        MULTISEND,
+        [(
+            Operation::Call,
+            Address::repeat_byte(0x11),
+            U256::from(5u64),
+            Bytes::new(),
+        )],
+    )
+    .build();
+
+    let created = create_proposal(
+        &state,
+        create_request(MULTISEND, 0, &batch.data),
+        Operation::DelegateCall,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+
+    let stored = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    assert_eq!(stored.metadata.admin_operation, None);
+}
+
+#[tokio::test]
+async fn batch_preview_reconstructs_the_sub_calls() {
+    let state = Arc::new(test_state().await);
+    let calls = vec![
+        MultiSendCall {
+            operation: Operation::Call,
+            to: Address::repeat_byte(0x11),
+            value: U256::from(5u64),
+            data: Bytes::new(),
+        },
+        MultiSendCall {
+            operation: Operation::Call,
+            to: Address::repeat_byte(0x7c),
+            value: U256::ZERO,
+            data: Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb, 0x01, 0x02]),
+        },
+    ];
+    let batch = MultiSendBuilder::new(
+        MULTISEND,
+        calls
+            .iter()
+            .map(|c| (c.operation, c.to, c.value, c.data.clone())),
+    )
+    .build();
+    let created = create_proposal(
+        &state,
+        create_request(MULTISEND, 0, &batch.data),
+        Operation::DelegateCall,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+    state.store.insert("single", &proposal(1)).await.unwrap();
+
+    let Json(preview) = get_batch_preview(State(state.clone()), TxId(created.tx_id))
+        .await
+        .unwrap();
+    let (status, _) = get_batch_preview(State(state), TxId("single".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(preview["multisend_address"], MULTISEND.to_string());
+    assert_eq!(preview["calls"], serde_json::to_value(&calls).unwrap());
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn csv_rows_become_proposals_or_a_batch() {
+    let rpc = chain_node(|_, _| None).await;
+    let state = Arc::new(test_state_with_rpc(&rpc.url).await);
+    let (alice, bob, token) = (
+        Address::repeat_byte(0x11),
+        Address::repeat_byte(0x22),
+        Address::repeat_byte(0x7c),
+    );
+    let valid = format!(
+        "to,value,token,reference\n{},1000,,inv-1\n{},250,{},inv-2\n",
+        alice, bob, token
+    );
+    let import = |mode, body: String| {
+        let state = state.clone();
+        async move {
+            import_csv(State(state), Query(ImportCsvQuery { mode }), body)
+                .await
+                .unwrap()
+        }
+    };
+
+    // Individual: the bad row is reported, the rest land on consecutive nonces
+    let (status, Json(individual)) = import(
+        ImportMode::Individual,
+        format!("{}{},5,,inv-3\n", valid, Address::ZERO),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((individual.created, individual.failed), (2, 1));
+    assert!(individual.results[2]
+        .error
+        .as_ref()
+        .unwrap()
+        .contains("zero address"));
+    let mut nonces = Vec::new();
+    for result in &individual.results[..2] {
+        let tx = state
+            .store
+            .get(result.tx_id.as_ref().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        nonces.push(tx.transaction.nonce);
+    }
+    assert_eq!(nonces, [U256::ZERO, U256::from(1)]);
+
+    // Batch: one MultiSend carrying both payments
+    let (status, Json(batch)) = import(ImportMode::Batch, valid.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let tx = state
+        .store
+        .get(&batch.batch.unwrap().tx_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tx.transaction.to, MULTISEND);
+    assert_eq!(tx.metadata.reference.as_deref(), Some("inv-1,inv-2"));
+    let calls = decode_multisend(&tx.transaction.data).unwrap();
+    assert_eq!(
+        calls.iter().map(|c| (c.to, c.value)).collect::<Vec<_>>(),
+        [(alice, U256::from(1000)), (token, U256::ZERO)]
+    );
+    assert_eq!(
+        calls[1].data,
+        Bytes::from(
+            IERC20::transferCall {
+                to: bob,
+                amount: U256::from(250)
+            }
+            .abi_encode()
+        )
+    );
+
+    // Batch is all-or-nothing
+    let (status, Json(rejected)) = import(ImportMode::Batch, format!("{}nope,1,,\n", valid)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(rejected.batch.is_none());
+    assert_eq!((rejected.created, rejected.failed), (0, 1));
+}
+
+#[tokio::test]
+async fn finished_proposals_reject_signatures() {
+    let state = Arc::new(test_state().await);
+    let signer = key(1);
+
+    for status in [
+        TransactionStatus::Executing,
+        TransactionStatus::Executed,
+        TransactionStatus::Failed,
+        TransactionStatus::Cancelled,
+        TransactionStatus::Invalidated,
+    ] {
+        let mut tx_state = proposal(0);
+        tx_state.status = status;
+        let tx_id = format!("tx-{:?}", status);
+        state.store.insert(&tx_id, &tx_state).await.unwrap();
+        let signature = signer
+            .sign_hash_sync(&B256::from_str(&tx_state.tx_hash).unwrap())
+            .unwrap();
+
+        let result = sign_with(&state, &tx_id, signer.address(), &signature.as_bytes()).await;
+
+        assert_eq!(
+            result.err().map(|(status, _)| status),
+            Some(StatusCode::CONFLICT)
+        );
+    }
+}
+
+#[tokio::test]
+async fn collecting_proposal_accepts_a_signature() {
+    let state = Arc::new(test_state().await);
+    let signer = key(1);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let signature = signer
+        .sign_hash_sync(&B256::from_str(&tx_state.tx_hash).unwrap())
+        .unwrap();
+
+    let result = sign_with(&state, "tx", signer.address(), &signature.as_bytes()).await;
+
+    assert!(result.is_ok());
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert_eq!(stored.signatures.len(), 1);
+}
+
+#[tokio::test]
+async fn value_above_the_ceiling_needs_an_explicit_override() {
+    let mut state = test_state().await;
+    state.max_proposal_value = Some(U256::from(1_000u64));
+    let state = Arc::new(state);
+    let to = Address::repeat_byte(0x11);
+
+    let at_ceiling = create_proposal(
+        &state,
+        create_request(to, 1_000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await;
+    let above = create_proposal(
+        &state,
+        create_request(to, 1_001, &[]),
+        Operation::Call,
+        Some(U256::from(1)),
+    )
+    .await;
+    let mut overridden = create_request(to, 1_001, &[]);
+    overridden.allow_large_value = true;
+    let overridden =
+        create_proposal(&state, overridden, Operation::Call, Some(U256::from(1))).await;
+
+    assert!(at_ceiling.is_ok());
+    assert_eq!(above.err(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+    let stored = state
+        .store
+        .get(&overridden.unwrap().tx_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.transaction.value, U256::from(1_001u64));
+}
+
+#[tokio::test]
+async fn zero_value_is_allowed_only_for_contract_calls() {
+    let token = Address::repeat_byte(0x7c);
+    // approve(spender, type(uint256).max)
+    let approve = [
+        [0x09, 0x5e, 0xa7, 0xb3].as_slice(),
+        Address::repeat_byte(0x22).into_word().as_slice(),
+        &U256::MAX.to_be_bytes::<32>(),
+    ]
+    .concat();
+    let create = |state: &Arc<AppState>, data: &[u8], nonce: u64| {
+        let (state, req) = (state.clone(), create_request(token, 0, data));
+        async move { create_proposal(&state, req, Operation::Call, Some(U256::from(nonce))).await }
+    };
+
+    let state = Arc::new(test_state().await);
+    assert!(create(&state, &approve, 0).await.is_ok());
+    assert_eq!(
+        create(&state, &[], 1).await.err(),
+        Some(StatusCode::UNPROCESSABLE_ENTITY)
+    );
+
+    let mut strict = test_state().await;
+    strict.allow_zero_value_calls = false;
+    let strict = Arc::new(strict);
+    assert_eq!(
+        create(&strict, &approve, 0).await.err(),
+        Some(StatusCode::UNPROCESSABLE_ENTITY)
+    );
+}
+
+#[tokio::test]
+async fn zero_address_recipient_is_unprocessable() {
+    let state = Arc::new(test_state().await);
+
+    let result = create_transaction(
+        State(state.clone()),
+        Json(create_request(Address::ZERO, 1_000, &[])),
+    )
+    .await;
+
+    assert_eq!(result.err(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+    assert!(state.store.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn listed_recipient_forces_ai_review() {
+    let listed = Address::repeat_byte(0x11);
+    let agents = MockAiAgents::start(false, Duration::ZERO).await;
+    let mut state = test_state().await;
+    state.require_ai_approval = false;
+    state.mandatory_review.insert(listed);
+    state.ai_agents = agents.client();
+    let state = Arc::new(state);
+
+    let reviewed = create_proposal(
+        &state,
+        create_request(listed, 1_000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+    let unreviewed = create_proposal(
+        &state,
+        create_request(Address::repeat_byte(0x22), 1_000, &[]),
+        Operation::Call,
+        Some(U256::from(1)),
+    )
+    .await
+    .unwrap();
+
+    let requests = agents.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["to"], listed.to_string());
+    assert!(reviewed.ai_review_required);
+    // Rejected by the agents, so it never opens for signatures
+    let stored = state.store.get(&reviewed.tx_id).await.unwrap().unwrap();
+    assert_eq!(stored.status, TransactionStatus::Pending);
+    assert!(!stored.ai_analysis.unwrap().approved);
+    assert!(!unreviewed.ai_review_required);
+    let stored = state.store.get(&unreviewed.tx_id).await.unwrap().unwrap();
+    assert_eq!(stored.status, TransactionStatus::CollectingSignatures);
+}
+
+#[tokio::test]
+async fn duplicate_proposal_skips_ai_review() {
+    let to = Address::repeat_byte(0x11);
+    let mut state = test_state().await;
+    // The agents are unreachable, so reaching the AI call would fail the request
+    state.mandatory_review.insert(to);
+    state.ai_failure_policy = AiFailurePolicy::Closed;
+    let state = Arc::new(state);
+    state.store.insert("existing", &proposal(0)).await.unwrap();
+
+    let created = create_proposal(
+        &state,
+        create_request(to, 1_000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+
+    assert!(created.deduplicated);
+    assert_eq!(created.tx_id, "existing");
+}
+
+#[tokio::test]
+async fn admin_token_must_match_exactly() {
+    let state = test_state().await;
+    let bearer = |token: &str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        headers
+    };
+
+    assert_eq!(require_admin(&state, &bearer("admin-secret")), Ok(()));
+    assert_eq!(
+        require_admin(&state, &bearer("admin-secre")),
+        Err(StatusCode::UNAUTHORIZED)
+    );
+    assert_eq!(
+        require_admin(&state, &bearer("admin-secret2")),
+        Err(StatusCode::UNAUTHORIZED)
+    );
+}
+
+fn interrupted(nonce: u64) -> TransactionState {
+    let mut tx_state = proposal(nonce);
+    tx_state.status = TransactionStatus::Executing;
+    tx_state
+}
+
+#[test]
+fn interrupted_execution_follows_its_event() {
+    let mut tx_state = interrupted(3);
+    let event = ExecutionEvent {
+        safe_tx_hash: B256::from_str(&tx_state.tx_hash).unwrap(),
+        success: true,
+        transaction_hash: Some(B256::repeat_byte(0xee)),
+    };
+
+    assert_eq!(
+        tx_state.recover_interrupted_execution(U256::from(4u64), &[event]),
+        Ok(true)
+    );
+    assert_eq!(tx_state.status, TransactionStatus::Executed);
+    assert_eq!(tx_state.executed_tx_hash, Some(B256::repeat_byte(0xee)));
+}
+
+#[test]
+fn interrupted_execution_is_retryable_while_nonce_is_unused() {
+    let mut tx_state = interrupted(3);
+
+    assert_eq!(
+        tx_state.recover_interrupted_execution(U256::from(3u64), &[]),
+        Ok(true)
+    );
+    assert_eq!(tx_state.status, TransactionStatus::ReadyToExecute);
+}
+
+#[test]
+fn interrupted_execution_fails_once_nonce_moves_on() {
+    let mut tx_state = interrupted(3);
+
+    assert_eq!(
+        tx_state.recover_interrupted_execution(U256::from(4u64), &[]),
+        Ok(true)
+    );
+    assert_eq!(tx_state.status, TransactionStatus::Failed);
+    assert!(tx_state.invalidation_reason.is_some());
+}
+
+#[test]
+fn recovery_leaves_other_statuses_alone() {
+    let mut tx_state = proposal(3);
+
+    assert_eq!(
+        tx_state.recover_interrupted_execution(U256::from(4u64), &[]),
+        Ok(false)
+    );
+    assert_eq!(tx_state.status, TransactionStatus::CollectingSignatures);
+}
+
+fn erc20_transfer(token: &str, recipient: &str, amount: &str) -> Erc20TransferRequest {
+    Erc20TransferRequest {
+        token: token.to_string(),
+        recipient: recipient.to_string(),
+        amount: amount.to_string(),
+        reference: None,
+        tags: Vec::new(),
+        proposer: None,
+        proposer_signature: None,
+        proposed_at: None,
+        signature_timeout_secs: None,
+    }
+}
+
+#[test]
+fn erc20_transfer_encodes_transfer_calldata() {
+    let token = Address::repeat_byte(0x70);
+    let recipient = Address::repeat_byte(0x42);
+
+    let create = erc20_transfer(&token.to_string(), &recipient.to_string(), "1500")
+        .into_create_request()
+        .unwrap();
+
+    assert_eq!(Address::from_str(&create.to).unwrap(), token);
+    assert_eq!(create.value, "0");
+    let data = hex::decode(create.data.unwrap()).unwrap();
+    // transfer(address,uint256)
+    assert_eq!(data[..4], [0xa9, 0x05, 0x9c, 0xbb]);
+    let call = IERC20::transferCall::abi_decode(&data).unwrap();
+    assert_eq!(call.to, recipient);
+    assert_eq!(call.amount, U256::from(1500u64));
+}
+
+#[test]
+fn erc20_transfer_rejects_bad_input() {
+    let token = Address::repeat_byte(0x70).to_string();
+    let recipient = Address::repeat_byte(0x42).to_string();
+
+    for (token, recipient, amount) in [
+        ("not-an-address", recipient.as_str(), "1"),
+        (token.as_str(), "0x1234", "1"),
+        (token.as_str(), &Address::ZERO.to_string(), "1"),
+        (token.as_str(), recipient.as_str(), "0"),
+        (token.as_str(), recipient.as_str(), "-5"),
+        (token.as_str(), recipient.as_str(), "1.5"),
+    ] {
+        let result = erc20_transfer(token, recipient, amount).into_create_request();
+        assert_eq!(
+            result.err().map(|(status, _)| status),
+            Some(StatusCode::BAD_REQUEST),
+            "{} {} {}",
+            token,
+            recipient,
+            amount
+        );
+    }
+}
+
+#[tokio::test]
+async fn gas_params_are_part_of_the_signed_hash() {
+    let state = Arc::new(test_state().await);
+    let mut req = create_request(Address::repeat_byte(0x11), 1_000, &[]);
+    req.gas = GasParams {
+        safe_tx_gas: Some("50000".to_string()),
+        base_gas: Some("21000".to_string()),
+        gas_price: Some("25000000000".to_string()),
+        gas_token: None,
+        refund_receiver: Some(Address::repeat_byte(0x99)),
+    };
+
+    let created = create_proposal(&state, req, Operation::Call, Some(U256::ZERO))
+        .await
+        .unwrap();
+
+    let stored = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    assert_eq!(stored.transaction.base_gas, U256::from(21_000u64));
+    assert_eq!(
+        stored.transaction.refund_receiver,
+        Address::repeat_byte(0x99)
+    );
+    assert_eq!(
+        created.safe_tx_hash,
+        stored
+            .transaction
+            .safe_tx_hash(KAIA_TESTNET_CHAIN_ID, SAFE)
+            .to_string()
+    );
+    assert_ne!(created.safe_tx_hash, proposal(0).tx_hash);
+}
+
+#[tokio::test]
+async fn refund_fields_without_gas_price_are_rejected() {
+    let state = Arc::new(test_state().await);
+    let mut req = create_request(Address::repeat_byte(0x11), 1_000, &[]);
+    req.gas.base_gas = Some("21000".to_string());
+
+    let result = create_proposal(&state, req, Operation::Call, Some(U256::ZERO)).await;
+
+    assert_eq!(result.err(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+}
+
+fn verify_request(
+    hash: B256,
+    signature: &[u8],
+    expected: Option<Address>,
+) -> VerifySignatureRequest {
+    VerifySignatureRequest {
+        hash: hash.to_string(),
+        signature: format!("0x{}", hex::encode(signature)),
+        expected_signer: expected.map(|a| a.to_string()),
+    }
+}
+
+#[tokio::test]
+async fn verify_signature_recovers_a_valid_signature() {
+    let signer = key(1);
+    let hash = B256::repeat_byte(0xab);
+    let signature = signer.sign_hash_sync(&hash).unwrap().as_bytes();
+
+    let Json(response) = verify_signature(Json(verify_request(
+        hash,
+        &signature,
+        Some(signer.address()),
+    )))
+    .await
+    .unwrap();
+
+    assert_eq!(
+        Address::from_str(&response.recovered_signer).unwrap(),
+        signer.address()
+    );
+    assert!(response.low_s);
+    assert_eq!(response.v_scheme, VScheme::TypedData);
+    assert_eq!(response.matches_expected, Some(true));
+}
+
+#[tokio::test]
+async fn verify_signature_flags_high_s() {
+    let signer = key(1);
+    let hash = B256::repeat_byte(0xab);
+    let signature = signer.sign_hash_sync(&hash).unwrap();
+    // (r, n - s) with the parity flipped is the same signature in high-s form
+    let n = U256::from_str_radix(
+        "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        16,
+    )
+    .unwrap();
+    let high_s =
+        alloy::primitives::Signature::new(signature.r(), n - signature.s(), !signature.v());
+
+    let Json(response) = verify_signature(Json(verify_request(
+        hash,
+        &high_s.as_bytes(),
+        Some(signer.address()),
+    )))
+    .await
+    .unwrap();
+
+    assert!(!response.low_s);
+    assert_eq!(response.matches_expected, Some(true));
+}
+
+#[tokio::test]
+async fn verify_signature_reports_a_mismatch() {
+    let hash = B256::repeat_byte(0xab);
+    let signature = key(1).sign_hash_sync(&hash).unwrap().as_bytes();
+
+    let Json(response) = verify_signature(Json(verify_request(
+        hash,
+        &signature,
+        Some(key(2).address()),
+    )))
+    .await
+    .unwrap();
+
+    assert_eq!(response.matches_expected, Some(false));
+}
+
+#[tokio::test]
+async fn verify_signature_rejects_malformed_input() {
+    let hash = B256::repeat_byte(0xab);
+
+    let short = verify_signature(Json(verify_request(hash, &[0u8; 64], None))).await;
+    let mut unknown_v = [1u8; 65];
+    unknown_v[64] = 42;
+    let bad_v = verify_signature(Json(verify_request(hash, &unknown_v, None))).await;
+
+    assert_eq!(short.err(), Some(StatusCode::BAD_REQUEST));
+    assert_eq!(bad_v.err(), Some(StatusCode::BAD_REQUEST));
+}
+
+async fn sign_with(
+    state: &Arc<AppState>,
+    tx_id: &str,
+    signer: Address,
+    signature: &[u8],
+) -> Result<Json<serde_json::Value>, ApiError> {
+    sign_transaction(
+        State(state.clone()),
+        TxId(tx_id.to_string()),
+        Json(SignTransactionRequest {
+            signer_address: signer.to_string(),
+            signature: format!("0x{}", hex::encode(signature)),
+        }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn eth_sign_signature_is_accepted() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    let mut signature = key(1)
+        .sign_message_sync(hash.as_slice())
+        .unwrap()
+        .as_bytes();
+    signature[64] += 4;
+
+    assert!(sign_with(&state, "tx", key(1).address(), &signature)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn signature_from_another_key_is_rejected() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    // Owner 2's signature submitted as owner 1's
+    let signature = key(2).sign_hash_sync(&hash).unwrap().as_bytes();
+
+    let result = sign_with(&state, "tx", key(1).address(), &signature).await;
+
+    assert_eq!(
+        result.err().map(|(status, _)| status),
+        Some(StatusCode::BAD_REQUEST)
+    );
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert!(stored.signatures.is_empty());
+}
+
+#[tokio::test]
+async fn garbage_signature_is_rejected() {
+    let state = Arc::new(test_state().await);
+    state.store.insert("tx", &proposal(0)).await.unwrap();
+
+    let result = sign_with(&state, "tx", key(1).address(), &[0x42; 65]).await;
+
+    assert_eq!(
+        result.err().map(|(status, _)| status),
+        Some(StatusCode::BAD_REQUEST)
+    );
+}
+
+#[tokio::test]
+async fn non_owner_signature_is_refused() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let outsider = key(9);
+    // Valid for the hash, but from an address that isn't a configured signer
+    let signature = outsider
+        .sign_hash_sync(&B256::from_str(&tx_state.tx_hash).unwrap())
+        .unwrap()
+        .as_bytes();
+
+    let (status, Json(body)) = sign_with(&state, "tx", outsider.address(), &signature)
+        .await
+        .unwrap_err();
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("is not a signer of this Safe"));
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert!(stored.signatures.is_empty());
+}
+
+// Node serving the Safe's owners, threshold and nonce; owners can change between calls
+async fn safe_rpc(
+    owners: Arc<std::sync::Mutex<Vec<Address>>>,
+    threshold: u64,
+    nonce: u64,
+) -> MockRpc {
+    use safe_contract_abi::ISafe;
+
+    MockRpc::start(move |method, params| {
+        if method != "eth_call" {
+            return serde_json::Value::Null;
+        }
+        let selector = called_selector(params);
+        if selector == ISafe::getOwnersCall::SELECTOR {
+            let owners = owners.lock().unwrap().clone();
+            abi_result(ISafe::getOwnersCall::abi_encode_returns(&owners))
+        } else if selector == ISafe::getThresholdCall::SELECTOR {
+            abi_result(ISafe::getThresholdCall::abi_encode_returns(&U256::from(
+                threshold,
+            )))
+        } else if selector == ISafe::nonceCall::SELECTOR {
+            abi_result(ISafe::nonceCall::abi_encode_returns(&U256::from(nonce)))
+        } else {
+            serde_json::Value::Null
+        }
+    })
+    .await
+}
+
+#[tokio::test]
+async fn safe_info_reports_onchain_owners_from_cache() {
+    let stranger = Address::repeat_byte(0x99);
+    let owners = vec![key(1).address(), key(3).address(), stranger];
+    let rpc = safe_rpc(Arc::new(std::sync::Mutex::new(owners)), 2, 9).await;
+    let state = Arc::new(test_state_with_rpc(&rpc.url).await);
+
+    let Json(info) = get_safe_info(State(state.clone())).await.unwrap();
+    assert_eq!(info["threshold"], 2);
+    assert_eq!(info["nonce"], 9);
+    assert_eq!(
+        info["owners"]["humans"],
+        serde_json::json!([key(1).address().to_string()])
+    );
+    assert_eq!(
+        info["owners"]["ai_agents"],
+        serde_json::json!([key(3).address().to_string()])
+    );
+    assert_eq!(
+        info["owners"]["other"],
+        serde_json::json!([stranger.to_string()])
+    );
+
+    // Served from the registry cache the second time
+    let Json(cached) = get_safe_info(State(state)).await.unwrap();
+    assert_eq!(cached, info);
+    assert_eq!(rpc.calls("eth_call").len(), 3);
+}
+
+#[tokio::test]
+async fn safe_info_without_rpc_is_bad_gateway() {
+    let state = Arc::new(test_state().await);
+
+    assert_eq!(
+        get_safe_info(State(state)).await.err(),
+        Some(StatusCode::BAD_GATEWAY)
+    );
+}
+
+#[tokio::test]
+async fn owner_removed_after_signing_blocks_execution() {
+    let owners = Arc::new(std::sync::Mutex::new(
+        (1..=5).map(|n| key(n).address()).collect::<Vec<_>>(),
+    ));
+    let rpc = safe_rpc(owners.clone(), 4, 0).await;
+    let state = Arc::new(test_state_with_rpc(&rpc.url).await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    for n in 1..=4 {
+        let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+        assert!(sign_with(&state, "tx", key(n).address(), &signature)
+            .await
+            .is_ok());
+    }
+    let signed_status = state.store.get("tx").await.unwrap().unwrap().status;
+
+    // Owner 4 is swapped out on-chain before anyone executes
+    owners
+        .lock()
+        .unwrap()
+        .retain(|owner| *owner != key(4).address());
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body["rejected_signers"],
+        serde_json::json!([key(4).address().to_string()])
+    );
+    assert_eq!(body["owner_signatures"], 3);
+    assert!(rpc.calls("eth_sendRawTransaction").is_empty());
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert_eq!(stored.status, signed_status);
+}
+
+async fn resign_with(
+    state: &Arc<AppState>,
+    tx_id: &str,
+    signer: Address,
+    signature: &[u8],
+) -> Result<Json<serde_json::Value>, ApiError> {
+    resign_transaction(
+        State(state.clone()),
+        TxId(tx_id.to_string()),
+        Json(SignTransactionRequest {
+            signer_address: signer.to_string(),
+            signature: format!("0x{}", hex::encode(signature)),
+        }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn advanced_nonce_clears_collected_signatures() {
+    let state = Arc::new(test_state().await);
+    for (tx_id, nonce) in [("stale", 0), ("current", 2)] {
+        let tx_state = proposal(nonce);
+        state.store.insert(tx_id, &tx_state).await.unwrap();
+        let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+        for n in 1..=2 {
+            let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+            assert!(sign_with(&state, tx_id, key(n).address(), &signature)
+                .await
+                .is_ok());
+        }
+    }
+
+    // Another transaction used nonces 0 and 1
+    let invalidated = invalidate_consumed_nonces(&state, U256::from(2)).await;
+
+    assert_eq!(invalidated, vec!["stale".to_string()]);
+    let stale = state.store.get("stale").await.unwrap().unwrap();
+    assert_eq!(stale.status, TransactionStatus::Invalidated);
+    assert!(stale.signatures.is_empty());
+    assert!(stale
+        .invalidation_reason
+        .unwrap()
+        .contains("Safe nonce advanced to 2"));
+    let current = state.store.get("current").await.unwrap().unwrap();
+    assert_eq!(current.status, TransactionStatus::CollectingSignatures);
+    assert_eq!(current.signatures.len(), 2);
+}
+
+#[tokio::test]
+async fn signature_list_follows_the_execution_encoding() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    // Received in descending address order, the reverse of how they're packed
+    let mut signers: Vec<PrivateKeySigner> = (1..=3).map(key).collect();
+    signers.sort_by_key(|signer| std::cmp::Reverse(signer.address()));
+    for signer in &signers {
+        let signature = signer.sign_hash_sync(&hash).unwrap().as_bytes();
+        assert!(sign_with(&state, "tx", signer.address(), &signature)
+            .await
+            .is_ok());
+    }
+
+    let Json(details) = get_transaction_signatures(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    let encoded = EncodedSignatures::from_signatures(&stored.signatures).unwrap();
+    let packed: Vec<&[u8]> = encoded.as_bytes().chunks(65).collect();
+    assert_eq!(details.len(), 3);
+    for (position, detail) in details.iter().enumerate() {
+        assert_eq!(detail.position, position);
+        assert_eq!(detail.received_order, 2 - position);
+        assert_eq!(detail.signer, signers[2 - position].address().to_string());
+        assert_eq!(
+            Bytes::from_str(&detail.signature).unwrap().as_ref(),
+            packed[position]
+        );
+        assert_eq!(detail.recovered_signer.as_ref(), Some(&detail.signer));
+        assert!(detail.v_scheme.is_some());
+        assert_eq!(detail.low_s, Some(true));
+        assert!(detail.matches_signer);
+        assert!(detail.signed_at.is_some());
+        assert_eq!(detail.signed_hash, Some(hash));
+        assert_eq!(detail.matches_current_hash, Some(true));
+    }
+}
+
+#[tokio::test]
+async fn signature_details_expose_signers_that_signed_different_hashes() {
+    let state = Arc::new(test_state().await);
+    let mut tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let stale = B256::from_str(&tx_state.tx_hash).unwrap();
+    let signature = key(1).sign_hash_sync(&stale).unwrap().as_bytes();
+    assert!(sign_with(&state, "tx", key(1).address(), &signature)
+        .await
+        .is_ok());
+
+    // The proposal's hash moves on; the next signer uses eth_sign over the new one
+    let current = B256::repeat_byte(0x5e);
+    tx_state = state.store.get("tx").await.unwrap().unwrap();
+    tx_state.tx_hash = current.to_string();
+    state.store.update("tx", &tx_state).await.unwrap();
+    let mut signature = key(2)
+        .sign_message_sync(current.as_slice())
+        .unwrap()
+        .as_bytes();
+    signature[64] += 4;
+    assert!(sign_with(&state, "tx", key(2).address(), &signature)
+        .await
+        .is_ok());
+
+    let Json(details) = get_transaction_signatures(State(state), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    let by_signer = |n: u8| {
+        details
+            .iter()
+            .find(|d| d.signer == key(n).address().to_string())
+            .unwrap()
+    };
+    let (first, second) = (by_signer(1), by_signer(2));
+    assert_eq!(first.signed_hash, Some(stale));
+    assert_eq!(first.v_scheme, Some(VScheme::TypedData));
+    assert_eq!(first.signed_digest, Some(stale));
+    assert_eq!(first.matches_current_hash, Some(false));
+    assert_eq!(second.signed_hash, Some(current));
+    assert_eq!(second.v_scheme, Some(VScheme::EthSign));
+    assert_eq!(
+        second.signed_digest,
+        Some(alloy::primitives::eip191_hash_message(current))
+    );
+    assert_eq!(second.matches_current_hash, Some(true));
+}
+
+#[tokio::test]
+async fn content_hash_is_stable_until_the_transaction_is_altered() {
+    let state = Arc::new(test_state().await);
+    let mut tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let content_hash = || async {
+        let Json(info) = get_transaction(
+            State(state.clone()),
+            TxId("tx".to_string()),
+            Query(TransactionInfoQuery { refresh: None }),
+        )
+        .await
+        .unwrap();
+        info.content_hash
+    };
+
+    let first = content_hash().await;
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    let signature = key(1).sign_hash_sync(&hash).unwrap().as_bytes();
+    assert!(sign_with(&state, "tx", key(1).address(), &signature)
+        .await
+        .is_ok());
+    // Collected signatures aren't part of the transaction content
+    assert_eq!(content_hash().await, first);
+
+    tx_state.transaction.value = U256::from(1_000_000u64);
+    state.store.update("tx", &tx_state).await.unwrap();
+    assert_ne!(content_hash().await, first);
+}
+
+#[tokio::test]
+async fn near_threshold_deadline_is_extended_instead_of_expiring() {
+    let mut state = test_state().await;
+    state.auto_extend = Some(AutoExtendConfig {
+        window_secs: 60,
+        max_extensions: 1,
+    });
+    let state = Arc::new(state);
+    let now = Utc::now();
+    let deadline = now + chrono::Duration::seconds(30);
+    for (tx_id, nonce, signers) in [("near", 0, 3), ("far", 1, 2)] {
+        let mut tx_state = proposal(nonce);
+        tx_state.deadline = Some(deadline);
+        state.store.insert(tx_id, &tx_state).await.unwrap();
+        let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+        for n in 1..=signers {
+            let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+            assert!(sign_with(&state, tx_id, key(n).address(), &signature)
+                .await
+                .is_ok());
+        }
+    }
+
+    // Inside the extension window, then just past the original deadline
+    sweep_expired(&state, now).await;
+    sweep_expired(&state, deadline + chrono::Duration::seconds(1)).await;
+
+    let near = state.store.get("near").await.unwrap().unwrap();
+    assert_eq!(near.status, TransactionStatus::CollectingSignatures);
+    assert_eq!(
+        near.deadline,
+        Some(deadline + chrono::Duration::seconds(60))
+    );
+    assert_eq!(near.deadline_extensions, 1);
+    let extended = near.audit_log.last().unwrap();
+    assert_eq!(extended.event, "deadline_extended");
+    assert!(extended.detail.contains("(1/1)"));
+    let far = state.store.get("far").await.unwrap().unwrap();
+    assert_eq!(far.status, TransactionStatus::Failed);
+    assert_eq!(far.deadline_extensions, 0);
+
+    // Out of extensions, so it expires like any other
+    sweep_expired(&state, deadline + chrono::Duration::seconds(61)).await;
+    let near = state.store.get("near").await.unwrap().unwrap();
+    assert_eq!(near.status, TransactionStatus::Failed);
+    assert_eq!(near.deadline_extensions, 1);
+}
+
+#[tokio::test]
+async fn proposal_expires_at_its_own_timeout_and_stops_taking_signatures() {
+    let state = Arc::new(test_state().await);
+    let mut req = create_request(Address::repeat_byte(0x11), 1_000, &[]);
+    req.signature_timeout_secs = Some(600);
+    let created = create_proposal(&state, req, Operation::Call, Some(U256::ZERO))
+        .await
+        .unwrap();
+    let tx_state = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    let deadline = tx_state.deadline.unwrap();
+    assert_eq!(
+        deadline,
+        tx_state.created_at + chrono::Duration::seconds(600)
+    );
+    let sign = |n: u8| {
+        let state = state.clone();
+        let (tx_id, hash) = (
+            created.tx_id.clone(),
+            B256::from_str(&tx_state.tx_hash).unwrap(),
+        );
+        async move {
+            let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+            sign_with(&state, &tx_id, key(n).address(), &signature).await
+        }
+    };
+    assert!(sign(1).await.is_ok());
+
+    // Clock just short of the deadline, then at it
+    sweep_expired(&state, deadline - chrono::Duration::seconds(1)).await;
+    let tx_state = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    assert_eq!(tx_state.status, TransactionStatus::CollectingSignatures);
+    sweep_expired(&state, deadline).await;
+    let tx_state = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    assert_eq!(tx_state.status, TransactionStatus::Failed);
+    assert_eq!(tx_state.audit_log.last().unwrap().event, "expired");
+    assert!(tx_state
+        .invalidation_reason
+        .unwrap()
+        .contains("passed with 1/"));
+
+    assert_eq!(
+        sign(2).await.err().map(|(status, _)| status),
+        Some(StatusCode::CONFLICT)
+    );
+
+    // A proposal nobody swept is expired by the signature that arrives late
+    let mut overdue = proposal(1);
+    overdue.deadline = Some(Utc::now() - chrono::Duration::seconds(1));
+    state.store.insert("overdue", &overdue).await.unwrap();
+    let signature = key(1)
+        .sign_hash_sync(&B256::from_str(&overdue.tx_hash).unwrap())
+        .unwrap()
+        .as_bytes();
+    assert_eq!(
+        sign_with(&state, "overdue", key(1).address(), &signature)
+            .await
+            .err()
+            .map(|(status, _)| status),
+        Some(StatusCode::GONE)
+    );
+    let overdue = state.store.get("overdue").await.unwrap().unwrap();
+    assert_eq!(overdue.status, TransactionStatus::Failed);
+    assert!(overdue.signatures.is_empty());
+}
+
+#[tokio::test]
+async fn malformed_tx_id_is_a_bad_request_not_a_miss() {
+    use tower::ServiceExt;
+
+    let state = Arc::new(test_state().await);
+    let known = uuid::Uuid::new_v4().to_string();
+    state.store.insert(&known, &proposal(0)).await.unwrap();
+    let app = Router::new()
+        .route("/api/v1/transactions/{tx_id}", get(get_transaction))
+        .route(
+            "/api/v1/transactions/{tx_id}/signatures",
+            get(get_transaction_signatures),
+        )
+        .with_state(state);
+    let status = |path: String| {
+        let app = app.clone();
+        async move {
+            let request = axum::http::Request::builder()
+                .uri(path)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            app.oneshot(request).await.unwrap().status()
+        }
+    };
+    let unknown = uuid::Uuid::new_v4();
+
+    for suffix in ["", "/signatures"] {
+        assert_eq!(
+            status(format!("/api/v1/transactions/not-a-uuid{}", suffix)).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(format!("/api/v1/transactions/{}{}", unknown, suffix)).await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(format!("/api/v1/transactions/{}{}", known, suffix)).await,
+            StatusCode::OK
+        );
+    }
+}
+
+#[tokio::test]
+async fn resign_replaces_a_signature() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    let signature = key(1).sign_hash_sync(&hash).unwrap().as_bytes();
+    assert!(sign_with(&state, "tx", key(1).address(), &signature)
+        .await
+        .is_ok());
+
+    let Json(body) = resign_with(&state, "tx", key(1).address(), &signature)
+        .await
+        .unwrap();
+    assert_eq!(body["replaced"], true);
+}
+
+#[tokio::test]
+async fn resign_errors_carry_a_json_body() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    let own = key(1).sign_hash_sync(&hash).unwrap().as_bytes();
+    let other = key(2).sign_hash_sync(&hash).unwrap().as_bytes();
+
+    let (status, Json(body)) = resign_with(&state, "missing", key(1).address(), &own)
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "Transaction not found");
+
+    let (status, Json(body)) = resign_with(&state, "tx", key(1).address(), &own)
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("No signature from"));
+
+    assert!(sign_with(&state, "tx", key(1).address(), &own)
+        .await
+        .is_ok());
+    let (status, Json(body)) = resign_with(&state, "tx", key(1).address(), &other)
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body["error"],
+        "Signature does not recover to signer_address"
+    );
+}
+
+#[test]
+fn chain_id_must_parse_when_set() {
+    assert_eq!(parse_chain_id(None), Ok(KAIA_TESTNET_CHAIN_ID));
+    assert_eq!(parse_chain_id(Some("")), Ok(KAIA_TESTNET_CHAIN_ID));
+    assert_eq!(parse_chain_id(Some(" 8217 ")), Ok(8217));
+    assert!(parse_chain_id(Some("8217x")).is_err());
+    assert!(parse_chain_id(Some("-1")).is_err());
+}
+
+// State whose node answers Safe.getTransactionHash with `contract_hash`
+async fn hashing_state(contract_hash: B256) -> (MockRpc, Arc<AppState>) {
+    let rpc = MockRpc::start(move |method, params| {
+        if method == "eth_call"
+            && called_selector(params) == safe_contract_abi::ISafe::getTransactionHashCall::SELECTOR
+        {
+            abi_result(
+                safe_contract_abi::ISafe::getTransactionHashCall::abi_encode_returns(
+                    &contract_hash,
+                ),
+            )
+        } else {
+            serde_json::Value::Null
+        }
+    })
+    .await;
+    let mut state = test_state_with_rpc(&rpc.url).await;
+    state.verify_hash_onchain = true;
+    (rpc, Arc::new(state))
+}
+
+#[tokio::test]
+async fn typed_data_is_served_when_the_contract_agrees() {
+    let local_hash = B256::from_str(&proposal(0).tx_hash).unwrap();
+    let (_rpc, state) = hashing_state(local_hash).await;
+
+    let created = create_proposal(
+        &state,
+        create_request(Address::repeat_byte(0x11), 1000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(created.hash_source, HashSource::Contract));
+    assert!(created.hash_verified_onchain);
+    assert_eq!(created.safe_tx_hash, local_hash.to_string());
+    assert!(created.typed_data.is_some());
+}
+
+#[tokio::test]
+async fn typed_data_is_withheld_when_the_contract_disagrees() {
+    let (_rpc, state) = hashing_state(B256::repeat_byte(0xee)).await;
+
+    let created = create_proposal(
+        &state,
+        create_request(Address::repeat_byte(0x11), 1000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(created.hash_source, HashSource::Contract));
+    assert!(!created.hash_verified_onchain);
+    assert_eq!(created.safe_tx_hash, B256::repeat_byte(0xee).to_string());
+    assert!(created.typed_data.is_none());
+}
+
+#[tokio::test]
+async fn contract_hash_round_trips_the_proposal() {
+    use safe_contract_abi::ISafe;
+
+    // Hashes whatever getTransactionHash is asked about, as the Safe would
+    let rpc = MockRpc::start(|method, params| {
+        let input = params[0]["input"].as_str().or(params[0]["data"].as_str());
+        let input = Bytes::from_str(input.unwrap_or_default()).unwrap_or_default();
+        match ISafe::getTransactionHashCall::abi_decode(&input) {
+            Ok(call) if method == "eth_call" => {
+                let tx = SafeTransaction {
+                    to: call.to,
+                    value: call.value,
+                    data: call.data,
+                    operation: Operation::try_from(call.operation).unwrap(),
+                    safe_tx_gas: call.safeTxGas,
+                    base_gas: call.baseGas,
+                    gas_price: call.gasPrice,
+                    gas_token: call.gasToken,
+                    refund_receiver: call.refundReceiver,
+                    nonce: call._nonce,
+                };
+                abi_result(ISafe::getTransactionHashCall::abi_encode_returns(
+                    &tx.safe_tx_hash(KAIA_TESTNET_CHAIN_ID, SAFE),
+                ))
+            }
+            _ => serde_json::Value::Null,
+        }
+    })
+    .await;
+    let mut state = test_state_with_rpc(&rpc.url).await;
+    state.verify_hash_onchain = true;
+    let state = Arc::new(state);
+    let to = Address::repeat_byte(0x11);
+    let data = [0xde, 0xad, 0xbe, 0xef];
+
+    let created = create_proposal(
+        &state,
+        create_request(to, 1_000, &data),
+        Operation::Call,
+        Some(U256::from(7)),
+    )
+    .await
+    .unwrap();
+
+    let calls = rpc.calls("eth_call");
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0][0]["to"], serde_json::json!(SAFE));
+    let input = calls[0][0]["input"]
+        .as_str()
+        .or(calls[0][0]["data"].as_str());
+    let asked =
+        ISafe::getTransactionHashCall::abi_decode(&Bytes::from_str(input.unwrap()).unwrap())
+            .unwrap();
+    assert_eq!(asked.to, to);
+    assert_eq!(asked.value, U256::from(1_000u64));
+    assert_eq!(asked.data.as_ref(), &data);
+    assert_eq!(asked._nonce, U256::from(7));
+    assert!(matches!(created.hash_source, HashSource::Contract));
+    assert!(created.hash_verified_onchain);
+    let stored = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    assert_eq!(created.safe_tx_hash, stored.tx_hash);
+    assert_eq!(
+        created.safe_tx_hash,
+        stored
+            .transaction
+            .safe_tx_hash(KAIA_TESTNET_CHAIN_ID, SAFE)
+            .to_string()
+    );
+}
+
+#[tokio::test]
+async fn unreachable_contract_falls_back_to_the_local_hash() {
+    let rpc = MockRpc::start_fallible(|_, _| {
+        Err(serde_json::json!({ "code": -32000, "message": "node unavailable" }))
+    })
+    .await;
+    let mut state = test_state_with_rpc(&rpc.url).await;
+    state.verify_hash_onchain = true;
+    let state = Arc::new(state);
+
+    let created = create_proposal(
+        &state,
+        create_request(Address::repeat_byte(0x11), 1000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(created.hash_source, HashSource::Local));
+    assert!(!created.hash_verified_onchain);
+    assert_eq!(created.safe_tx_hash, proposal(0).tx_hash);
+    assert!(created.typed_data.is_some());
+}
+
+// Node with owners 1..=5 at threshold 4 (nonce 0) and a 2 gwei network price, whose next
+// base fee of 2 gwei has execution paying up to 5 gwei (doubled plus the 1 gwei tip)
+async fn priced_node() -> MockRpc {
+    use safe_contract_abi::ISafe;
+
+    MockRpc::start(|method, params| match method {
+        "eth_gasPrice" => serde_json::Value::from("0x77359400"),
+        "eth_feeHistory" => serde_json::json!({
+            "oldestBlock": "0x1",
+            "baseFeePerGas": ["0x77359400", "0x77359400"],
+            "gasUsedRatio": [0.5],
+        }),
+        "eth_call" if called_selector(params) == ISafe::getOwnersCall::SELECTOR => {
+            let owners: Vec<Address> = (1..=5).map(|n| key(n).address()).collect();
+            abi_result(ISafe::getOwnersCall::abi_encode_returns(&owners))
+        }
+        "eth_call" if called_selector(params) == ISafe::getThresholdCall::SELECTOR => {
+            abi_result(ISafe::getThresholdCall::abi_encode_returns(&U256::from(4)))
+        }
+        "eth_call" if called_selector(params) == ISafe::nonceCall::SELECTOR => {
+            abi_result(ISafe::nonceCall::abi_encode_returns(&U256::ZERO))
+        }
+        _ => serde_json::Value::Null,
+    })
+    .await
+}
+
+#[tokio::test]
+async fn gas_guard_compares_the_execution_price() {
+    let rpc = priced_node().await;
+    let mut state = test_state_with_rpc(&rpc.url).await;
+    // Above the raw network price, below what execution would pay
+    state.max_gas_price = Some(U256::from(3_000_000_000u64));
+    let state = Arc::new(state);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    for n in 1..=4 {
+        let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+        assert!(sign_with(&state, "tx", key(n).address(), &signature)
+            .await
+            .is_ok());
+    }
+
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["gas_price"], "5000000000");
+}
+
+#[tokio::test]
+async fn health_reuses_the_gas_price() {
+    let rpc = priced_node().await;
+    let state = Arc::new(test_state_with_rpc(&rpc.url).await);
+
+    let Json(first) = health(State(state.clone())).await;
+    let Json(second) = health(State(state)).await;
+
+    assert_eq!(first["gas_price"], "5000000000");
+    assert_eq!(second["gas_price"], "5000000000");
+    assert_eq!(rpc.calls("eth_feeHistory").len(), 1);
+}
+
+#[tokio::test]
+async fn balances_are_read_through_one_multicall() {
+    use alloy::providers::bindings::IMulticall3;
+    use safe_contract_abi::IERC20;
+
+    let (usdt, wkaia) = (Address::repeat_byte(0x7c), Address::repeat_byte(0x7d));
+    let rpc = chain_node(move |method, params| {
+        if method != "eth_call" {
+            return None;
+        }
+        let input = params[0]["input"].as_str().or(params[0]["data"].as_str());
+        let input = Bytes::from_str(input.unwrap_or_default()).unwrap();
+        let calls = IMulticall3::aggregate3Call::abi_decode(&input).ok()?.calls;
+        let results: Vec<IMulticall3::Result> = calls
+            .iter()
+            .map(|call| {
+                let selector: [u8; 4] = call.callData[..4].try_into().unwrap();
+                let balance = if call.target == usdt { 1_500_000u64 } else { 3 };
+                let returned = match selector {
+                    IERC20::balanceOfCall::SELECTOR => {
+                        IERC20::balanceOfCall::abi_encode_returns(&U256::from(balance))
+                    }
+                    IERC20::decimalsCall::SELECTOR => IERC20::decimalsCall::abi_encode_returns(&18),
+                    IERC20::symbolCall::SELECTOR => {
+                        IERC20::symbolCall::abi_encode_returns(&"WKAIA".to_string())
+                    }
+                    _ => {
+                        return IMulticall3::Result {
+                            success: false,
+                            returnData: Bytes::new(),
+                        }
+                    }
+                };
+                IMulticall3::Result {
+                    success: true,
+                    returnData: returned.into(),
+                }
+            })
+            .collect();
+        Some(Ok(abi_result(
+            IMulticall3::aggregate3Call::abi_encode_returns(&results),
+        )))
+    })
+    .await;
+    let mut state = test_state_with_rpc(&rpc.url).await;
+    state.tracked_tokens = vec![usdt, wkaia];
+    state.tokens = Arc::new(TokenRegistry::new(std::collections::HashMap::from([(
+        usdt,
+        shared::tokens::TokenInfo {
+            symbol: "USDT".to_string(),
+            decimals: 6,
+        },
+    )])));
+
+    let Json(balances) = get_safe_balances(State(Arc::new(state))).await.unwrap();
+
+    assert_eq!(
+        balances.native.balance,
+        U256::from(10u64).pow(U256::from(18))
+    );
+    assert_eq!(balances.native.decimals, 18);
+    assert_eq!(balances.tokens.len(), 2);
+    assert_eq!(balances.tokens[0].symbol.as_deref(), Some("USDT"));
+    assert_eq!(balances.tokens[0].formatted, "1.500000");
+    // Unknown to the registry, so its metadata came from the second batch
+    assert_eq!(balances.tokens[1].token, wkaia);
+    assert_eq!(balances.tokens[1].symbol.as_deref(), Some("WKAIA"));
+    assert_eq!(balances.tokens[1].balance, U256::from(3));
+    // One batch of balanceOf, one each of decimals and symbol
+    assert_eq!(rpc.calls("eth_call").len(), 3);
+}
+
+// Node at nonce 4 whose lookback window holds one execution per nonce, in order.
+// Nonces 1 and 2 went through a relay contract, so their calldata can't be decoded.
+async fn history_node() -> MockRpc {
+    use alloy::sol_types::SolEvent;
+    use safe_contract_abi::{ExecutionSuccess, ISafe};
+
+    let relay = Address::repeat_byte(0x77);
+    // (nonce, SafeTx, transaction hash, transaction target)
+    let executions: Vec<(u64, SafeTransaction, B256, Address)> = (0..4u8)
+        .map(|nonce| {
+            let mut tx = proposal(nonce.into()).transaction;
+            // Nonce 1 is the local proposal; the others were proposed elsewhere
+            if nonce != 1 {
+                tx.value = U256::from(7);
+            }
+            let target = if matches!(nonce, 1 | 2) { relay } else { SAFE };
+            (nonce.into(), tx, B256::repeat_byte(0xa0 + nonce), target)
+        })
+        .collect();
+
+    MockRpc::start(move |method, params| match method {
+        "eth_blockNumber" => serde_json::Value::from("0x64"),
+        "eth_call" if called_selector(params) == ISafe::nonceCall::SELECTOR => {
+            abi_result(ISafe::nonceCall::abi_encode_returns(&U256::from(4)))
+        }
+        "eth_getLogs" => executions
+            .iter()
+            .map(|(nonce, tx, tx_hash, _)| {
+                let event = ExecutionSuccess {
+                    txHash: tx.safe_tx_hash(KAIA_TESTNET_CHAIN_ID, SAFE),
+                    payment: U256::ZERO,
+                };
+                serde_json::json!({
+                    "address": SAFE,
+                    "topics": [ExecutionSuccess::SIGNATURE_HASH],
+                    "data": Bytes::from(event.encode_data()),
+                    "blockNumber": format!("{:#x}", 90 + nonce),
+                    "blockHash": B256::repeat_byte(0xbb),
+                    "transactionHash": tx_hash,
+                    "transactionIndex": "0x0",
+                    "logIndex": "0x0",
+                    "removed": false,
+                })
+            })
+            .collect(),
+        "eth_getTransactionByHash" => {
+            let Some((nonce, tx, tx_hash, target)) = executions
+                .iter()
+                .find(|(_, _, tx_hash, _)| params[0] == serde_json::json!(tx_hash))
+            else {
+                return serde_json::Value::Null;
+            };
+            let input = ISafe::execTransactionCall {
+                to: tx.to,
+                value: tx.value,
+                data: tx.data.clone(),
+                operation: tx.operation.into(),
+                safeTxGas: tx.safe_tx_gas,
+                baseGas: tx.base_gas,
+                gasPrice: tx.gas_price,
+                gasToken: tx.gas_token,
+                refundReceiver: tx.refund_receiver,
+                signatures: Bytes::new(),
+            }
+            .abi_encode();
+            serde_json::json!({
+                "type": "0x2",
+                "chainId": "0x3e9",
+                "nonce": "0x0",
+                "to": target,
+                "gas": "0x30d40",
+                "maxFeePerGas": "0x1",
+                "maxPriorityFeePerGas": "0x1",
+                "value": "0x0",
+                "input": Bytes::from(input),
+                "accessList": [],
+                "r": "0x1",
+                "s": "0x1",
+                "yParity": "0x0",
+                "v": "0x0",
+                "hash": tx_hash,
+                "blockHash": B256::repeat_byte(0xbb),
+                "blockNumber": format!("{:#x}", 90 + nonce),
+                "transactionIndex": "0x0",
+                "from": key(9).address(),
+            })
+        }
+        _ => serde_json::Value::Null,
+    })
+    .await
+}
+
+async fn nonce_history(state: &Arc<AppState>, query: NonceHistoryQuery) -> serde_json::Value {
+    let Json(body) = get_nonce_history(State(state.clone()), Path(SAFE.to_string()), Query(query))
+        .await
+        .unwrap();
+    body
+}
+
+#[tokio::test]
+async fn nonce_history_matches_events_by_their_safe_tx() {
+    let rpc = history_node().await;
+    let state = Arc::new(test_state_with_rpc(&rpc.url).await);
+    let mut local = proposal(1);
+    local.status = TransactionStatus::Executed;
+    state.store.insert("local", &local).await.unwrap();
+
+    let body = nonce_history(
+        &state,
+        NonceHistoryQuery {
+            before: None,
+            limit: None,
+        },
+    )
+    .await;
+
+    let history = &body["history"];
+    assert_eq!(body["current_nonce"], 4);
+    assert!(body["next_before"].is_null());
+    assert_eq!(history["0"]["source"], "external");
+    assert_eq!(history["0"]["tx_hash"], B256::repeat_byte(0xa0).to_string());
+    // Relayed, but its safeTxHash is known locally
+    assert_eq!(history["1"]["source"], "local");
+    assert_eq!(history["1"]["tx_id"], "local");
+    assert_eq!(history["1"]["tx_hash"], B256::repeat_byte(0xa1).to_string());
+    // Relayed and proposed elsewhere: consumed, but the event can't be tied to it
+    assert_eq!(history["2"]["source"], "external");
+    assert!(history["2"]["tx_hash"].is_null());
+    assert_eq!(history["3"]["tx_hash"], B256::repeat_byte(0xa3).to_string());
+}
+
+#[tokio::test]
+async fn nonce_history_is_paged_from_the_newest_nonce() {
+    let rpc = history_node().await;
+    let state = Arc::new(test_state_with_rpc(&rpc.url).await);
+
+    let newest = nonce_history(
+        &state,
+        NonceHistoryQuery {
+            before: None,
+            limit: Some(1),
+        },
+    )
+    .await;
+    let older = nonce_history(
+        &state,
+        NonceHistoryQuery {
+            before: newest["next_before"].as_u64(),
+            limit: Some(3),
+        },
+    )
+    .await;
+
+    assert_eq!(newest["next_before"], 3);
+    assert_eq!(
+        newest["history"]["3"]["tx_hash"],
+        B256::repeat_byte(0xa3).to_string()
+    );
+    let history = older["history"].as_object().unwrap();
+    assert_eq!(history.keys().collect::<Vec<_>>(), ["0", "1", "2"]);
+    assert!(older["next_before"].is_null());
+    assert_eq!(history["0"]["tx_hash"], B256::repeat_byte(0xa0).to_string());
+    // The newest event belongs to nonce 3, not to the newest nonce of this page
+    assert!(history["2"]["tx_hash"].is_null());
+}
+
+#[tokio::test]
+async fn execution_below_threshold_is_refused_with_violations() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    for n in 1..=3 {
+        let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+        assert!(sign_with(&state, "tx", key(n).address(), &signature)
+            .await
+            .is_ok());
+    }
+
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body["violations"],
+        serde_json::json!([{ "rule": "signatures", "have": 3, "need": 4 }])
+    );
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert_eq!(stored.status, TransactionStatus::CollectingSignatures);
+}
+
+#[tokio::test]
+async fn admin_operation_without_every_human_is_refused() {
+    let state = Arc::new(test_state().await);
+    let mut tx_state = proposal(0);
+    tx_state.metadata.admin_operation = Some(AdminOperation::ChangeThreshold {
+        threshold: U256::from(3),
+    });
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    // One human and all three AI agents: the threshold alone is met
+    for n in [1, 3, 4, 5] {
+        let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+        assert!(sign_with(&state, "tx", key(n).address(), &signature)
+            .await
+            .is_ok());
+    }
+
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body["violations"],
+        serde_json::json!([{ "rule": "human_signatures", "have": 1, "need": 2 }])
+    );
+    assert_eq!(
+        body["missing_human_approvals"],
+        serde_json::json!([key(2).address()])
+    );
+}
+
+// ai-agents stand-in: /api/v1/decision answers with one CFO verdict after
+// `delay`, and every request body is recorded
+pub(crate) struct MockAiAgents {
+    pub url: String,
+    requests: Arc<std::sync::Mutex<Vec<serde_json::Value>>>,
+}
+
+impl MockAiAgents {
+    pub async fn start(approved: bool, delay: Duration) -> Self {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let app = Router::new().route(
+            "/api/v1/decision",
+            post(move |Json(request): Json<serde_json::Value>| async move {
+                recorded.lock().unwrap().push(request);
+                tokio::time::sleep(delay).await;
+                Json(serde_json::json!({
+                    "approved": approved,
+                    "results": [{
+                        "agent": "CFO Agent",
+                        "approved": approved,
+                        "risk_score": if approved { 0.1 } else { 0.9 },
+                        "reasons": ["Within budget"],
+                        "reason_codes": []
+                    }]
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<serde_json::Value> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn client(&self) -> AiAgentsClient {
+        AiAgentsClient::new(self.url.clone(), Duration::from_secs(5)).unwrap()
+    }
+}
+
+fn verdict(agent: &str, approved: bool, reason_codes: &[&str]) -> ai_analysis::AgentVerdict {
+    ai_analysis::AgentVerdict {
+        agent: agent.to_string(),
+        approved,
+        risk_score: if approved { 0.1 } else { 0.9 },
+        reason: format!("{} verdict", agent),
+        reason_codes: reason_codes.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+#[tokio::test]
+async fn rejections_are_listed_per_agent() {
+    let state = Arc::new(test_state().await);
+    let mut blocked = pending_proposal();
+    blocked.ai_analysis = Some(AiAnalysis::from_decision(
+        false,
+        vec![
+            verdict("cfo_agent", true, &[]),
+            verdict("security_agent", false, &["BLACKLISTED_RECIPIENT"]),
+        ],
+    ));
+    state.store.insert("blocked", &blocked).await.unwrap();
+    let mut overspent = proposal(1);
+    overspent.ai_analysis = Some(AiAnalysis::from_decision(
+        false,
+        vec![
+            verdict("cfo_agent", false, &["DAILY_LIMIT"]),
+            verdict("security_agent", true, &[]),
+        ],
+    ));
+    state.store.insert("overspent", &overspent).await.unwrap();
+    let rejections =
+        |role: &str| get_agent_rejections(State(state.clone()), Path(role.to_string()));
+
+    let Json(security) = rejections("security").await.unwrap();
+    let Json(cfo) = rejections("cfo").await.unwrap();
+    let Json(analyst) = rejections("analyst").await.unwrap();
+
+    assert_eq!(security.len(), 1);
+    assert_eq!(security[0].tx_id, "blocked");
+    assert_eq!(security[0].reason_codes, vec!["BLACKLISTED_RECIPIENT"]);
+    assert_eq!(security[0].status, TransactionStatus::Pending);
+    assert_eq!(cfo.len(), 1);
+    assert_eq!(cfo[0].tx_id, "overspent");
+    assert!(analyst.is_empty());
+    assert_eq!(
+        rejections("treasurer").await.err(),
+        Some(StatusCode::NOT_FOUND)
+    );
+}
+
+fn pending_proposal() -> TransactionState {
+    let mut tx_state = proposal(0);
+    tx_state.status = TransactionStatus::Pending;
+    tx_state
+}
+
+#[tokio::test]
+async fn refresh_stores_the_verdict_and_returns_it() {
+    let agents = MockAiAgents::start(true, Duration::ZERO).await;
+    let mut state = test_state().await;
+    state.ai_agents = agents.client();
+    let state = Arc::new(state);
+    state.store.insert("tx", &pending_proposal()).await.unwrap();
+
+    let Json(refreshed) = get_transaction(
+        State(state.clone()),
+        TxId("tx".to_string()),
+        Query(TransactionInfoQuery {
+            refresh: Some(true),
+        }),
+    )
+    .await
+    .unwrap();
+    let Json(later) = get_transaction(
+        State(state.clone()),
+        TxId("tx".to_string()),
+        Query(TransactionInfoQuery { refresh: None }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(agents.requests().len(), 1);
+    for info in [&refreshed, &later] {
+        let analysis = info.ai_analysis.as_ref().unwrap();
+        assert!(analysis.approved);
+        assert_eq!(analysis.per_agent[0].agent, "cfo_agent");
+        assert_eq!(analysis.per_agent[0].reason, "Within budget");
+        assert_eq!(info.status, TransactionStatus::CollectingSignatures);
+    }
+}
+
+#[tokio::test]
+async fn refresh_does_not_hold_the_mutation_lock() {
+    let agents = MockAiAgents::start(true, Duration::from_millis(500)).await;
+    let mut state = test_state().await;
+    state.ai_agents = agents.client();
+    let state = Arc::new(state);
+    state.store.insert("tx", &pending_proposal()).await.unwrap();
+
+    let refresh = tokio::spawn({
+        let state = state.clone();
+        async move {
+            get_transaction(
+                State(state),
+                TxId("tx".to_string()),
+                Query(TransactionInfoQuery {
+                    refresh: Some(true),
+                }),
+            )
+            .await
+            .map(|_| ())
+        }
+    });
+    while agents.requests().is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Other writers get through while the agents are still thinking
+    let locked = tokio::time::timeout(Duration::from_millis(200), state.mutation_lock.lock()).await;
+    assert!(locked.is_ok());
+    drop(locked);
+    assert_eq!(refresh.await.unwrap(), Ok(()));
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert!(stored.ai_analysis.is_some());
+}
+
+#[tokio::test]
+async fn ai_analyze_relays_the_proposal_and_stores_the_verdict() {
+    let agents = MockAiAgents::start(false, Duration::ZERO).await;
+    let mut state = test_state().await;
+    state.ai_agents = agents.client();
+    let state = Arc::new(state);
+    state.store.insert("tx", &pending_proposal()).await.unwrap();
+
+    let Json(body) = ai_analyze_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    let requests = agents.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["safe"], SAFE.to_string());
+    assert_eq!(requests[0]["to"], Address::repeat_byte(0x11).to_string());
+    assert_eq!(requests[0]["value"], "1000");
+    assert_eq!(requests[0]["data"], "0x");
+    assert_eq!(requests[0]["existing_signers"], serde_json::json!([]));
+
+    assert_eq!(body["tx_id"], "tx");
+    assert_eq!(body["analysis"]["approved"], false);
+    assert_eq!(body["recommendation"], "Review required before signing");
+    // A rejection is recorded but doesn't open the proposal for signing
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert!(!stored.ai_analysis.unwrap().approved);
+    assert_eq!(stored.status, TransactionStatus::Pending);
+}
+
+#[tokio::test]
+async fn ai_analyze_without_the_agents_is_bad_gateway() {
+    let state = Arc::new(test_state().await);
+    state.store.insert("tx", &pending_proposal()).await.unwrap();
+
+    let result = ai_analyze_transaction(State(state.clone()), TxId("tx".to_string())).await;
+
+    assert_eq!(result.err(), Some(StatusCode::BAD_GATEWAY));
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert!(stored.ai_analysis.is_none());
+}
+
+// Receipt of `tx_hash` mined in block 2; `status` "0x0" means it reverted
+fn receipt(tx_hash: &serde_json::Value, status: &str) -> serde_json::Value {
+    serde_json::json!({
+        "transactionHash": tx_hash,
+        "transactionIndex": "0x0",
+        "blockHash": B256::repeat_byte(0xbb),
+        "blockNumber": "0x2",
+        "from": key(9).address(),
+        "to": SAFE,
+        "cumulativeGasUsed": "0x30d40",
+        "gasUsed": "0x30d40",
+        "effectiveGasPrice": "0x3b9aca00",
+        "contractAddress": null,
+        "logs": [],
+        "logsBloom": alloy::primitives::Bloom::ZERO,
+        "status": status,
+        "type": "0x2",
+    })
+}
+
+// Node that lets the Safe execute: owners 1..=5 at threshold 4 (nonce 0), a
+// simulation that succeeds, a funded relayer and receipts for whatever is
+// broadcast. `overrides` answers first; `None` falls through to these defaults.
+pub(crate) async fn chain_node(
+    overrides: impl Fn(&str, &serde_json::Value) -> Option<Result<serde_json::Value, serde_json::Value>>
+        + Send
+        + Sync
+        + 'static,
+) -> MockRpc {
+    use safe_contract_abi::ISafe;
+
+    MockRpc::start_fallible(move |method, params| {
+        if let Some(response) = overrides(method, params) {
+            return response;
+        }
+        Ok(match method {
+            "eth_chainId" => serde_json::Value::from("0x3e9"),
+            "eth_blockNumber" => serde_json::Value::from("0x2"),
+            "eth_getTransactionCount" => serde_json::Value::from("0x0"),
+            "eth_estimateGas" => serde_json::Value::from("0x30d40"),
+            "eth_gasPrice" => serde_json::Value::from("0x77359400"),
+            "eth_feeHistory" => serde_json::json!({
+                "oldestBlock": "0x1",
+                "baseFeePerGas": ["0x77359400", "0x77359400"],
+                "gasUsedRatio": [0.5],
+            }),
+            // 1 KAIA
+            "eth_getBalance" => serde_json::Value::from("0xde0b6b3a7640000"),
+            "eth_getStorageAt" => B256::ZERO.to_string().into(),
+            "eth_sendRawTransaction" => {
+                let raw = Bytes::from_str(params[0].as_str().unwrap()).unwrap();
+                alloy::primitives::keccak256(raw).to_string().into()
+            }
+            "eth_getTransactionReceipt" => receipt(&params[0], "0x1"),
+            "eth_call" => {
+                let selector = called_selector(params);
+                if selector == ISafe::getOwnersCall::SELECTOR {
+                    let owners: Vec<Address> = (1..=5).map(|n| key(n).address()).collect();
+                    abi_result(ISafe::getOwnersCall::abi_encode_returns(&owners))
+                } else if selector == ISafe::getThresholdCall::SELECTOR {
+                    abi_result(ISafe::getThresholdCall::abi_encode_returns(&U256::from(4)))
+                } else if selector == ISafe::nonceCall::SELECTOR {
+                    abi_result(ISafe::nonceCall::abi_encode_returns(&U256::ZERO))
+                } else if selector == ISafe::execTransactionCall::SELECTOR {
+                    abi_result(ISafe::execTransactionCall::abi_encode_returns(&true))
+                } else {
+                    serde_json::Value::Null
+                }
+            }
+            _ => serde_json::Value::Null,
+        })
+    })
+    .await
+}
+
+// Proposal `tx` at nonce 0 signed by owners 1..=4, on a state whose relayer is key 9
+async fn signed_for_execution(rpc: &MockRpc) -> Arc<AppState> {
+    sign_for_execution(test_state_with_relayer(&rpc.url, Some(key(9))).await).await
+}
+
+// Stores proposal `tx` at nonce 0 in `state` and signs it with owners 1..=4
+async fn sign_for_execution(state: AppState) -> Arc<AppState> {
+    let state = Arc::new(state);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    for n in 1..=4 {
+        let signature = key(n).sign_hash_sync(&hash).unwrap().as_bytes();
+        assert!(sign_with(&state, "tx", key(n).address(), &signature)
+            .await
+            .is_ok());
+    }
+    state
+}
+
+#[tokio::test]
+async fn exec_encoding_decodes_back_to_what_execution_submits() {
+    use safe_contract_abi::ISafe;
+
+    // Owner 1 signed, then was swapped out for owner 5 and the threshold lowered
+    let rpc = chain_node(|method, params| match method {
+        "eth_call" if called_selector(params) == ISafe::getOwnersCall::SELECTOR => {
+            let owners: Vec<Address> = (2..=5).map(|n| key(n).address()).collect();
+            Some(Ok(abi_result(ISafe::getOwnersCall::abi_encode_returns(
+                &owners,
+            ))))
+        }
+        "eth_call" if called_selector(params) == ISafe::getThresholdCall::SELECTOR => Some(Ok(
+            abi_result(ISafe::getThresholdCall::abi_encode_returns(&U256::from(3))),
+        )),
+        _ => None,
+    })
+    .await;
+    let state = signed_for_execution(&rpc).await;
+
+    let Json(encoding) = get_exec_encoding(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    let mut owners: Vec<Address> = (2..=4).map(|n| key(n).address()).collect();
+    owners.sort();
+    assert_eq!(encoding.signer_order, owners);
+    assert_eq!(encoding.signatures.len(), 3);
+    assert_eq!(encoding.signatures_blob.len(), 3 * 65);
+
+    let call = ISafe::execTransactionCall::abi_decode(&encoding.calldata).unwrap();
+    let tx = &stored.transaction;
+    assert_eq!(call.to, tx.to);
+    assert_eq!(call.value, tx.value);
+    assert_eq!(call.data, tx.data);
+    assert_eq!(call.operation, u8::from(tx.operation));
+    assert_eq!(call.safeTxGas, tx.safe_tx_gas);
+    assert_eq!(call.baseGas, tx.base_gas);
+    assert_eq!(call.gasPrice, tx.gas_price);
+    assert_eq!(call.gasToken, tx.gas_token);
+    assert_eq!(call.refundReceiver, tx.refund_receiver);
+    assert_eq!(call.signatures, encoding.signatures_blob);
+    let submitted = current_owner_signatures(&state, "tx", &stored.signatures)
+        .await
+        .unwrap();
+    assert_eq!(&call.signatures, submitted.as_bytes());
+}
+
+#[tokio::test]
+async fn execution_broadcasts_and_records_the_hash() {
+    let rpc = chain_node(|_, _| None).await;
+    let state = signed_for_execution(&rpc).await;
+
+    let Json(response) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    let sent = rpc.calls("eth_sendRawTransaction");
+    assert_eq!(sent.len(), 1);
+    let raw = Bytes::from_str(sent[0][0].as_str().unwrap()).unwrap();
+    let broadcast = alloy::primitives::keccak256(raw);
+    assert!(response.success);
+    assert_eq!(response.tx_hash, broadcast.to_string());
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert_eq!(stored.status, TransactionStatus::Executed);
+    assert_eq!(stored.executed_tx_hash, Some(broadcast));
+}
+
+#[tokio::test]
+async fn execution_waits_out_the_grace_period() {
+    let rpc = chain_node(|_, _| None).await;
+    let mut state = test_state_with_relayer(&rpc.url, Some(key(9))).await;
+    state.execution_grace_secs = 600;
+    let state = sign_for_execution(state).await;
+
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::TOO_EARLY);
+    let mut stored = state.store.get("tx").await.unwrap().unwrap();
+    let ready_at = stored.ready_at.unwrap();
+    assert_eq!(
+        body["executable_at"],
+        (ready_at + chrono::Duration::seconds(600)).to_rfc3339()
+    );
+    assert!(rpc.calls("eth_sendRawTransaction").is_empty());
+
+    // Threshold reached eleven minutes ago
+    stored.ready_at = Some(ready_at - chrono::Duration::seconds(660));
+    state.store.update("tx", &stored).await.unwrap();
+    let Json(response) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .unwrap();
+    assert!(response.success);
+    assert_eq!(rpc.calls("eth_sendRawTransaction").len(), 1);
+}
+
+#[tokio::test]
+async fn underfunded_relayer_is_refused_before_broadcast() {
+    // 0.001 KAIA against 200k gas, times the 1.2 multiplier, at 5 gwei
+    let rpc = chain_node(|method, _| {
+        (method == "eth_getBalance").then(|| Ok(serde_json::Value::from("0x38d7ea4c68000")))
+    })
+    .await;
+    let state = signed_for_execution(&rpc).await;
+
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    assert_eq!(body["gas_limit"], 240_000);
+    assert_eq!(body["estimated_cost"], "1200000000000000");
+    assert_eq!(body["relayer_balance"], "1000000000000000");
+    assert_eq!(body["shortfall"], "200000000000000");
+    assert!(rpc.calls("eth_sendRawTransaction").is_empty());
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert_eq!(stored.status, TransactionStatus::ReadyToExecute);
+}
+
+#[tokio::test]
+async fn reverted_execution_is_failed_with_bad_gateway() {
+    let rpc = chain_node(|method, params| {
+        (method == "eth_getTransactionReceipt").then(|| Ok(receipt(&params[0], "0x0")))
+    })
+    .await;
+    let state = signed_for_execution(&rpc).await;
+
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(body["success"], false);
+    assert!(body["error"].as_str().unwrap().contains("reverted"));
+    assert_eq!(rpc.calls("eth_sendRawTransaction").len(), 1);
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert_eq!(stored.status, TransactionStatus::Failed);
+    assert_eq!(stored.executed_tx_hash, None);
+}
+
+// Node whose Safe has `guard` installed and whose execTransaction reverts with `reason`
+async fn reverting_node(guard: Address, reason: &'static str) -> MockRpc {
+    use alloy::sol_types::{Revert, SolError};
+    use safe_contract_abi::ISafe;
+
+    chain_node(move |method, params| match method {
+        "eth_getStorageAt" => Some(Ok(guard.into_word().to_string().into())),
+        "eth_call" if called_selector(params) == ISafe::execTransactionCall::SELECTOR => {
+            Some(Err(serde_json::json!({
+                "code": 3,
+                "message": "execution reverted",
+                "data": Bytes::from(Revert::from(reason).abi_encode()),
+            })))
+        }
+        _ => None,
+    })
+    .await
+}
+
+#[tokio::test]
+async fn guard_revert_is_attributed_to_the_guard() {
+    let guard = Address::repeat_byte(0x9a);
+    let rpc = reverting_node(guard, "Guard: recipient not allowed").await;
+    let state = signed_for_execution(&rpc).await;
+
+    let Json(body) = simulate_transaction(State(state), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    let simulation = &body["simulation"];
+    assert_eq!(simulation["outcome"], "reverted");
+    assert_eq!(simulation["source"], "guard");
+    assert_eq!(simulation["reason"], "Guard: recipient not allowed");
+    assert_eq!(simulation["guard"], serde_json::json!(guard));
+}
+
+#[tokio::test]
+async fn safe_revert_is_not_blamed_on_the_guard() {
+    let rpc = reverting_node(Address::repeat_byte(0x9a), "GS026").await;
+    let state = signed_for_execution(&rpc).await;
+
+    let Json(body) = simulate_transaction(State(state), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(body["simulation"]["source"], "safe");
+    assert_eq!(body["simulation"]["reason"], "GS026");
+}
+
+#[tokio::test]
+async fn failing_transfer_is_caught_before_broadcast() {
+    // The Safe can't cover the 1000 wei transfer, so with safeTxGas 0 it reverts GS013
+    let rpc = reverting_node(Address::ZERO, "GS013").await;
+    let state = signed_for_execution(&rpc).await;
+    let before = state.store.get("tx").await.unwrap().unwrap().status;
+
+    let (status, Json(body)) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"], "Execution would revert");
+    assert_eq!(body["simulation"]["source"], "safe");
+    assert_eq!(body["simulation"]["reason"], "GS013");
+    assert_eq!(body["simulation"]["guard"], serde_json::Value::Null);
+    assert!(!rpc.calls("eth_call").is_empty());
+    assert!(rpc.calls("eth_sendRawTransaction").is_empty());
+    let stored = state.store.get("tx").await.unwrap().unwrap();
+    assert_eq!(stored.status, before);
+    assert_eq!(stored.executed_tx_hash, None);
+}
+
+#[tokio::test]
+async fn execution_report_needs_an_onchain_execution() {
+    let rpc = chain_node(|_, _| None).await;
+    let state = signed_for_execution(&rpc).await;
+
+    let (status, _) = get_execution_report(State(state), TxId("tx".to_string()))
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn execution_report_carries_signatures_and_receipt() {
+    let rpc = chain_node(|_, _| None).await;
+    let state = signed_for_execution(&rpc).await;
+    let Json(executed) = execute_transaction(State(state.clone()), TxId("tx".to_string()))
+        .await
+        .unwrap();
+
+    let Json(report) = get_execution_report(State(state), TxId("tx".to_string()))
+        .await
+        .unwrap();
+    let report = serde_json::to_value(report).unwrap();
+
+    assert_eq!(report["status"], "executed");
+    assert_eq!(report["receipt"]["transaction_hash"], executed.tx_hash);
+    assert_eq!(report["receipt"]["block_number"], 2);
+    assert_eq!(report["receipt"]["gas_used"], 200_000);
+    assert_eq!(report["receipt"]["success"], true);
+    let signatures = report["signatures"].as_array().unwrap();
+    assert_eq!(signatures.len(), 4);
+    for detail in signatures {
+        assert_eq!(detail["matches_signer"], true);
+        assert_eq!(detail["recovered_signer"], detail["signer"]);
+        assert!(detail["signed_at"].is_string());
+    }
+}
+
+// Starts a WebSocket handshake for `path_and_query` and returns its status line
+async fn open_transaction_socket(
+    server: std::net::SocketAddr,
+    path_and_query: &str,
+) -> (String, tokio::net::TcpStream) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut stream = tokio::net::TcpStream::connect(server).await.unwrap();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: Upgrade\r\n\
+         Upgrade: websocket\r\nSec-WebSocket-Version: 13\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        path_and_query, server
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte).await.unwrap();
+        response.push(byte[0]);
+    }
+    let status_line = String::from_utf8(response)
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .to_string();
+    (status_line, stream)
+}
+
+// Next server frame, which must be a text message (server frames are unmasked)
+async fn read_text_frame(stream: &mut tokio::net::TcpStream) -> serde_json::Value {
+    use tokio::io::AsyncReadExt;
+
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await.unwrap();
+    assert_eq!(header[0] & 0x0f, 0x1, "text frame");
+    let len = match header[1] & 0x7f {
+        126 => stream.read_u16().await.unwrap() as usize,
+        127 => stream.read_u64().await.unwrap() as usize,
+        len => len as usize,
+    };
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await.unwrap();
+    serde_json::from_slice(&payload).unwrap()
+}
+
+#[tokio::test]
+async fn subscriber_with_a_query_key_receives_signatures() {
+    let state = Arc::new(test_state().await);
+    let tx_id = uuid::Uuid::new_v4().to_string();
+    let tx_state = proposal(0);
+    state.store.insert(&tx_id, &tx_state).await.unwrap();
+    let path = format!("/api/v1/transactions/{}/ws", tx_id);
+    let app = Router::new()
+        .route(
+            "/api/v1/transactions/{tx_id}/ws",
+            get(subscribe_transaction),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            ApiKeys::new(HashSet::from(["k1".to_string()])),
+            api_auth::require_api_key,
+        ))
+        .with_state(state.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let (refused, _) = open_transaction_socket(server, &path).await;
+    assert!(refused.contains("401"), "{}", refused);
+
+    let (status_line, mut socket) =
+        open_transaction_socket(server, &format!("{}?api_key=k1", path)).await;
+    assert!(status_line.contains("101"), "{}", status_line);
+    let snapshot = read_text_frame(&mut socket).await;
+    assert_eq!(snapshot["event"], "snapshot");
+    assert_eq!(snapshot["signers"], serde_json::json!([]));
+
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    let signature = key(1).sign_hash_sync(&hash).unwrap().as_bytes();
+    assert!(sign_with(&state, &tx_id, key(1).address(), &signature)
+        .await
+        .is_ok());
+
+    let update = tokio::time::timeout(Duration::from_secs(5), read_text_frame(&mut socket))
+        .await
+        .unwrap();
+    assert_eq!(update["event"], "signature_added");
+    assert_eq!(update["tx_id"], tx_id);
+    assert_eq!(update["signers"], serde_json::json!([key(1).address()]));
+}
+
+// State that requires AI approval while the agents are unreachable
+async fn unreachable_agents(policy: AiFailurePolicy) -> Arc<AppState> {
+    let mut state = test_state().await;
+    state.require_ai_approval = true;
+    state.ai_failure_policy = policy;
+    Arc::new(state)
+}
+
+#[tokio::test]
+async fn unreachable_agents_fail_open_without_review() {
+    let state = unreachable_agents(AiFailurePolicy::Open).await;
+
+    let created = create_proposal(
+        &state,
+        create_request(Address::repeat_byte(0x11), 1000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(created.status, TransactionStatus::CollectingSignatures);
+    let stored = state.store.get(&created.tx_id).await.unwrap().unwrap();
+    assert!(stored.metadata.ai_unavailable);
+    assert!(stored.ai_analysis.is_none());
+    assert!(stored
+        .audit_log
+        .iter()
+        .any(|entry| entry.event == "ai_unavailable"));
+}
+
+#[tokio::test]
+async fn unreachable_agents_fail_closed_without_a_proposal() {
+    let state = unreachable_agents(AiFailurePolicy::Closed).await;
+
+    let refused = create_proposal(
+        &state,
+        create_request(Address::repeat_byte(0x11), 1000, &[]),
+        Operation::Call,
+        Some(U256::ZERO),
+    )
+    .await;
+
+    assert_eq!(refused.err(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(state.store.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reads_use_the_signer_recovered_on_submission() {
+    let state = Arc::new(test_state().await);
+    let tx_state = proposal(0);
+    state.store.insert("tx", &tx_state).await.unwrap();
+    let hash = B256::from_str(&tx_state.tx_hash).unwrap();
+    let signature = key(1).sign_hash_sync(&hash).unwrap().as_bytes();
+    assert!(sign_with(&state, "tx", key(1).address(), &signature)
+        .await
+        .is_ok());
+
+    // Zeroed bytes can't be recovered from, so any read that re-ran ecrecover
+    // would lose the signer
+    let mut stored = state.store.get("tx").await.unwrap().unwrap();
+    stored.signatures[0].signature = Bytes::from(vec![0u8; 65]);
+    state.store.update("tx", &stored).await.unwrap();
+
+    for _ in 0..3 {
+        let stored = state.store.get("tx").await.unwrap().unwrap();
+        assert_eq!(stored.signatures[0].verified_signer(), Ok(key(1).address()));
+        let details = signature_details(&state, &stored);
+        assert_eq!(details[0].signer_type, "Human");
+        assert_eq!(
+            details[0].recovered_signer,
+            Some(key(1).address().to_string())
+        );
+        assert!(details[0].matches_signer);
+    }
+}
+
+#[tokio::test]
+async fn queue_metrics_report_the_oldest_pending_age() {
+    let state = Arc::new(test_state().await);
+    let now = Utc::now();
+    for (tx_id, nonce, age_mins, status) in [
+        ("fresh", 0, 1, TransactionStatus::CollectingSignatures),
+        ("stale", 1, 30, TransactionStatus::CollectingSignatures),
+        // Older still, but no longer waiting on signatures
+        ("done", 2, 120, TransactionStatus::Executed),
+    ] {
+        let mut tx_state = proposal(nonce);
+        tx_state.created_at = now - chrono::Duration::minutes(age_mins);
+        tx_state.status = status;
+        state.store.insert(tx_id, &tx_state).await.unwrap();
+    }
+
+    let Json(metrics) = get_queue_metrics(
+        State(state),
+        Query(QueueMetricsQuery {
+            stale_after_secs: Some(600),
+        }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(metrics.pending_count, 2);
+    assert_eq!(metrics.stale_count, 1);
+    let oldest = metrics.oldest_pending_age_secs.unwrap();
+    assert!((1800..1805).contains(&oldest), "{}", oldest);
+}
+
+#[tokio::test]
+async fn queue_metrics_without_pending_proposals_have_no_age() {
+    let state = Arc::new(test_state().await);
+
+    let Json(metrics) = get_queue_metrics(
+        State(state),
+        Query(QueueMetricsQuery {
+            stale_after_secs: None,
+        }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(metrics.pending_count, 0);
+    assert_eq!(metrics.stale_after_secs, 900);
+    assert_eq!(metrics.oldest_pending_age_secs, None);
+}
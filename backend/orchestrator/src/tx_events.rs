@@ -0,0 +1,188 @@
+use alloy_primitives::{Address, B256};
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::extract::ws::{Message, WebSocket};
+use serde::Serialize;
+use shared::types::TransactionStatus;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::{safe_contract::Signature, store::Store, TransactionState};
+
+// Buffered events per subscriber before it starts lagging
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+// Proposal state after a write, as pushed to /ws subscribers
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TxSnapshot {
+    pub tx_id: String,
+    pub status: TransactionStatus,
+    pub signers: Vec<Address>,
+    pub executed_tx_hash: Option<B256>,
+}
+
+impl TxSnapshot {
+    pub fn of(tx_id: &str, tx_state: &TransactionState) -> Self {
+        Self {
+            tx_id: tx_id.to_string(),
+            status: tx_state.status,
+            signers: tx_state.signatures.iter().map(|s| s.signer).collect(),
+            executed_tx_hash: tx_state.executed_tx_hash,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TxEventKind {
+    Snapshot, // Sent once on connect
+    SignatureAdded,
+    StatusChanged,
+    Executed,
+}
+
+#[derive(Debug, Serialize)]
+struct TxEvent<'a> {
+    event: TxEventKind,
+    #[serde(flatten)]
+    snapshot: &'a TxSnapshot,
+}
+
+// Wraps the configured store and publishes a snapshot after every successful
+// write, so no handler has to remember to announce its changes
+pub struct EventingStore {
+    inner: Arc<dyn Store>,
+    events: broadcast::Sender<TxSnapshot>,
+}
+
+impl EventingStore {
+    pub fn new(inner: Arc<dyn Store>) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { inner, events }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TxSnapshot> {
+        self.events.subscribe()
+    }
+
+    fn publish(&self, tx_id: &str, tx_state: &TransactionState) {
+        // No subscribers is the common case, not an error
+        let _ = self.events.send(TxSnapshot::of(tx_id, tx_state));
+    }
+
+    async fn publish_current(&self, tx_id: &str) {
+        match self.inner.get(tx_id).await {
+            Ok(Some(tx_state)) => self.publish(tx_id, &tx_state),
+            Ok(None) => {}
+            Err(e) => warn!("Could not publish update for {}: {}", tx_id, e),
+        }
+    }
+}
+
+#[async_trait]
+impl Store for EventingStore {
+    async fn insert(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()> {
+        self.inner.insert(tx_id, tx_state).await?;
+        self.publish(tx_id, tx_state);
+        Ok(())
+    }
+
+    async fn get(&self, tx_id: &str) -> Result<Option<TransactionState>> {
+        self.inner.get(tx_id).await
+    }
+
+    async fn update(&self, tx_id: &str, tx_state: &TransactionState) -> Result<()> {
+        self.inner.update(tx_id, tx_state).await?;
+        self.publish(tx_id, tx_state);
+        Ok(())
+    }
+
+    async fn add_signature(&self, tx_id: &str, signature: Signature) -> Result<()> {
+        self.inner.add_signature(tx_id, signature).await?;
+        self.publish_current(tx_id).await;
+        Ok(())
+    }
+
+    async fn transition_status(
+        &self,
+        tx_id: &str,
+        from_expected: TransactionStatus,
+        to: TransactionStatus,
+    ) -> Result<()> {
+        self.inner
+            .transition_status(tx_id, from_expected, to)
+            .await?;
+        self.publish_current(tx_id).await;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<(String, TransactionState)>> {
+        self.inner.list().await
+    }
+
+    async fn find_in_flight(
+        &self,
+        content_hash: B256,
+    ) -> Result<Option<(String, TransactionState)>> {
+        self.inner.find_in_flight(content_hash).await
+    }
+}
+
+// Initial snapshot, then one message per change to this proposal. Closes once
+// it's executed or cancelled, or when the client goes away.
+pub async fn stream_transaction(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<TxSnapshot>,
+    mut last: TxSnapshot,
+) {
+    if !send(&mut socket, TxEventKind::Snapshot, &last).await {
+        return;
+    }
+
+    loop {
+        let snapshot = match events.recv().await {
+            Ok(snapshot) if snapshot.tx_id == last.tx_id => snapshot,
+            Ok(_) => continue,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("WebSocket for {} lagged by {} events", last.tx_id, skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        let kind = if snapshot.status == TransactionStatus::Executed
+            && last.status != TransactionStatus::Executed
+        {
+            TxEventKind::Executed
+        } else if snapshot.status != last.status {
+            TxEventKind::StatusChanged
+        } else if snapshot.signers.len() > last.signers.len() {
+            TxEventKind::SignatureAdded
+        } else {
+            // Rewrites that don't change anything a subscriber sees
+            continue;
+        };
+
+        if !send(&mut socket, kind, &snapshot).await {
+            return;
+        }
+        last = snapshot;
+        if matches!(
+            last.status,
+            TransactionStatus::Executed | TransactionStatus::Cancelled
+        ) {
+            break;
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+// False once the client is gone
+async fn send(socket: &mut WebSocket, event: TxEventKind, snapshot: &TxSnapshot) -> bool {
+    let Ok(payload) = serde_json::to_string(&TxEvent { event, snapshot }) else {
+        return false;
+    };
+    socket.send(Message::Text(payload.into())).await.is_ok()
+}
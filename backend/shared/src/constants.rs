@@ -6,7 +6,12 @@ pub const AI_SIGNERS_COUNT: u8 = 3;
 pub const KAIA_TESTNET_RPC: &str = "https://public-en-kairos.node.kaia.io";
 pub const KAIA_MAINNET_RPC: &str = "https://public-en-rpc.klaytn.net";
 
+pub const KAIA_TESTNET_CHAIN_ID: u64 = 1001;
+pub const KAIA_MAINNET_CHAIN_ID: u64 = 8217;
+
 pub const DEFAULT_GAS_LIMIT: u64 = 3_000_000;
 pub const DEFAULT_GAS_PRICE: u64 = 25_000_000_000;
 
 pub const SIGNATURE_COLLECTION_TIMEOUT_SECS: u64 = 3600;
+
+pub const DEFAULT_MIN_CONFIRMATIONS: u64 = 3;
@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Result};
+use axum::http::HeaderValue;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::{error, warn};
+
+// `DEPLOYMENT_MODE=demo` (default) keeps the mock paths and permissive
+// defaults; `production` turns them off and refuses to start on anything
+// still left at a demo value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeploymentMode {
+    Demo,
+    Production,
+}
+
+impl DeploymentMode {
+    pub fn from_env() -> Self {
+        match std::env::var("DEPLOYMENT_MODE").as_deref() {
+            Ok("production") => Self::Production,
+            Ok("demo") | Err(_) => Self::Demo,
+            Ok(other) => {
+                warn!("Unknown DEPLOYMENT_MODE '{}', using demo", other);
+                Self::Demo
+            }
+        }
+    }
+
+    pub fn is_production(self) -> bool {
+        self == Self::Production
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Demo => "demo",
+            Self::Production => "production",
+        }
+    }
+
+    // Fails listing every problem at once so they can be fixed in one pass.
+    // `unsafe_defaults` describes each setting still at a demo value.
+    pub fn ensure_production_ready(self, service: &str, unsafe_defaults: &[String]) -> Result<()> {
+        if !self.is_production() || unsafe_defaults.is_empty() {
+            return Ok(());
+        }
+        for problem in unsafe_defaults {
+            error!("{}: {}", service, problem);
+        }
+        Err(anyhow!(
+            "{} refuses to start in production mode: {}",
+            service,
+            unsafe_defaults.join("; ")
+        ))
+    }
+
+    // Any origin in demo; production only allows `CORS_ALLOWED_ORIGINS`.
+    pub fn cors_layer(self) -> Result<CorsLayer> {
+        if !self.is_production() {
+            return Ok(CorsLayer::permissive());
+        }
+
+        let origins = std::env::var("CORS_ALLOWED_ORIGINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .map(|o| HeaderValue::from_str(o).map_err(|_| anyhow!("Invalid CORS origin '{}'", o)))
+            .collect::<Result<Vec<_>>>()?;
+        if origins.is_empty() {
+            return Err(anyhow!(
+                "CORS_ALLOWED_ORIGINS is required in production mode"
+            ));
+        }
+
+        Ok(CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods(Any)
+            .allow_headers(Any))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn production_refuses_to_start_on_unsafe_defaults() {
+        let unsafe_defaults = vec![
+            "EXECUTOR_PRIVATE_KEY must be set".to_string(),
+            "API_KEYS must be set and API_AUTH_DISABLED unset".to_string(),
+        ];
+
+        let err = DeploymentMode::Production
+            .ensure_production_ready("Orchestrator", &unsafe_defaults)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.starts_with("Orchestrator refuses to start in production mode"));
+        assert!(err.contains("EXECUTOR_PRIVATE_KEY must be set; API_KEYS"));
+        assert!(DeploymentMode::Demo
+            .ensure_production_ready("Orchestrator", &unsafe_defaults)
+            .is_ok());
+        assert!(DeploymentMode::Production
+            .ensure_production_ready("Orchestrator", &[])
+            .is_ok());
+    }
+}
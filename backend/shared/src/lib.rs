@@ -1,6 +1,12 @@
 use thiserror::Error;
 
 pub mod constants;
+pub mod deployment;
+pub mod observability;
+pub mod policy;
+pub mod shutdown;
+pub mod signature;
+pub mod tokens;
 pub mod types;
 pub mod utils;
 
@@ -0,0 +1,158 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+    routing::get,
+    Router,
+};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::{
+    future::Future,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+// How often histogram buckets are compacted between scrapes.
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+// Installs the process-wide Prometheus recorder on first use.
+pub fn prometheus_handle() -> PrometheusHandle {
+    static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+    HANDLE
+        .get_or_init(|| {
+            let handle = PrometheusBuilder::new()
+                .install_recorder()
+                .expect("Failed to install Prometheus recorder");
+            let upkeep = handle.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(UPKEEP_INTERVAL).await;
+                    upkeep.run_upkeep();
+                }
+            });
+            handle
+        })
+        .clone()
+}
+
+// Counts every request to the routes registered so far by method, route
+// template and status, then adds `GET /metrics` in Prometheus text format.
+// Call it after the last `.route` so every endpoint is counted.
+pub fn with_metrics<S>(router: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let handle = prometheus_handle();
+    router
+        .route_layer(axum::middleware::from_fn(count_request))
+        .route("/metrics", get(move || async move { handle.render() }))
+}
+
+async fn count_request(request: Request, next: Next) -> Response {
+    // The route template, so /transactions/{tx_id} is one series and not one per id
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_default();
+    let method = request.method().to_string();
+
+    let response = next.run(request).await;
+    metrics::counter!(
+        "http_requests_total",
+        "method" => method,
+        "path" => path,
+        "status" => response.status().as_u16().to_string(),
+    )
+    .increment(1);
+    response
+}
+
+// Records how long an RPC call took, labelled by `method` and whether it succeeded.
+pub async fn time_rpc<T, E>(
+    method: &'static str,
+    call: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let result = call.await;
+    record_rpc(method, started.elapsed(), result.is_ok());
+    result
+}
+
+pub fn record_rpc(method: &'static str, elapsed: Duration, success: bool) {
+    metrics::histogram!(
+        "rpc_call_duration_seconds",
+        "method" => method,
+        "outcome" => if success { "ok" } else { "error" },
+    )
+    .record(elapsed.as_secs_f64());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    async fn get_text(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            path
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    // The sample line for `name` whose labels include every one of `labels`
+    fn sample<'a>(scrape: &'a str, name: &str, labels: &[&str]) -> Option<&'a str> {
+        scrape
+            .lines()
+            .find(|line| line.starts_with(name) && labels.iter().all(|l| line.contains(l)))
+    }
+
+    #[tokio::test]
+    async fn scrape_counts_requests_by_route_template() {
+        let app = with_metrics(Router::new().route(
+            "/items/{id}",
+            get(|| async {
+                let _ = time_rpc("eth_test", async { Ok::<_, ()>(()) }).await;
+                "ok"
+            }),
+        ));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        for id in ["1", "2"] {
+            assert!(get_text(addr, &format!("/items/{}", id))
+                .await
+                .starts_with("HTTP/1.1 200"));
+        }
+        let scrape = get_text(addr, "/metrics").await;
+
+        let requests = sample(
+            &scrape,
+            "http_requests_total",
+            &[
+                r#"method="GET""#,
+                r#"path="/items/{id}""#,
+                r#"status="200""#,
+            ],
+        )
+        .unwrap_or_else(|| panic!("no request counter in:\n{}", scrape));
+        assert!(requests.ends_with(" 2"), "{}", requests);
+        assert!(sample(
+            &scrape,
+            "rpc_call_duration_seconds_count",
+            &[r#"method="eth_test""#, r#"outcome="ok""#],
+        )
+        .is_some_and(|line| line.ends_with(" 2")));
+        // The scrape itself isn't counted
+        assert!(sample(&scrape, "http_requests_total", &[r#"path="/metrics""#]).is_none());
+    }
+}
@@ -0,0 +1,216 @@
+use alloy_primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{SafeConfig, SignerType};
+
+// Off-chain execution rules applied on top of the Safe's own threshold.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRules {
+    pub min_signatures: usize,
+    pub min_human_signatures: usize,
+    // An AI agent only signs what it approved, so its signature is its approval.
+    pub min_ai_approvals: usize,
+    pub max_value: Option<U256>,
+}
+
+impl PolicyRules {
+    // Just the Safe's threshold, with no extra human/AI or value requirements.
+    pub fn from_config(config: &SafeConfig) -> Self {
+        Self {
+            min_signatures: config.required_signatures as usize,
+            ..Self::default()
+        }
+    }
+}
+
+// One unmet rule, carrying what was required and what was found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum PolicyViolation {
+    Signatures { have: usize, need: usize },
+    HumanSignatures { have: usize, need: usize },
+    AiApprovals { have: usize, need: usize },
+    ValueCap { value: U256, cap: U256 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", content = "violations", rename_all = "snake_case")]
+pub enum PolicyDecision {
+    Executable,
+    Blocked(Vec<PolicyViolation>),
+}
+
+impl PolicyDecision {
+    pub fn is_executable(&self) -> bool {
+        matches!(self, Self::Executable)
+    }
+}
+
+// Decides whether a transaction may execute given who has signed it.
+#[derive(Debug, Clone)]
+pub struct PolicyEngine {
+    rules: PolicyRules,
+}
+
+impl PolicyEngine {
+    pub fn new(rules: PolicyRules) -> Self {
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &PolicyRules {
+        &self.rules
+    }
+
+    // `signers` holds the type of each collected signature, one per signer.
+    // Every rule is checked so a blocked decision lists all that's missing.
+    pub fn evaluate(&self, value: U256, signers: &[SignerType]) -> PolicyDecision {
+        let humans = signers
+            .iter()
+            .filter(|s| matches!(s, SignerType::Human))
+            .count();
+        let ai = signers.len() - humans;
+
+        let mut violations = Vec::new();
+        if signers.len() < self.rules.min_signatures {
+            violations.push(PolicyViolation::Signatures {
+                have: signers.len(),
+                need: self.rules.min_signatures,
+            });
+        }
+        if humans < self.rules.min_human_signatures {
+            violations.push(PolicyViolation::HumanSignatures {
+                have: humans,
+                need: self.rules.min_human_signatures,
+            });
+        }
+        if ai < self.rules.min_ai_approvals {
+            violations.push(PolicyViolation::AiApprovals {
+                have: ai,
+                need: self.rules.min_ai_approvals,
+            });
+        }
+        if let Some(cap) = self.rules.max_value.filter(|cap| value > *cap) {
+            violations.push(PolicyViolation::ValueCap { value, cap });
+        }
+
+        if violations.is_empty() {
+            PolicyDecision::Executable
+        } else {
+            PolicyDecision::Blocked(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SignerType::*;
+
+    fn engine(min_signatures: usize, humans: usize, ai: usize, cap: Option<u64>) -> PolicyEngine {
+        PolicyEngine::new(PolicyRules {
+            min_signatures,
+            min_human_signatures: humans,
+            min_ai_approvals: ai,
+            max_value: cap.map(U256::from),
+        })
+    }
+
+    #[test]
+    fn threshold_met_is_executable() {
+        let decision =
+            engine(4, 0, 0, None).evaluate(U256::ZERO, &[Human, Human, AiCfo, AiSecurity]);
+        assert_eq!(decision, PolicyDecision::Executable);
+    }
+
+    #[test]
+    fn short_of_threshold_is_blocked() {
+        let decision = engine(4, 0, 0, None).evaluate(U256::ZERO, &[Human, AiCfo, AiSecurity]);
+        assert_eq!(
+            decision,
+            PolicyDecision::Blocked(vec![PolicyViolation::Signatures { have: 3, need: 4 }])
+        );
+    }
+
+    #[test]
+    fn human_and_ai_minimums_count_by_signer_type() {
+        let rules = engine(3, 2, 2, None);
+
+        assert!(rules
+            .evaluate(U256::ZERO, &[Human, Human, AiCfo, AiAnalyst])
+            .is_executable());
+        // Enough signatures overall, but from the wrong mix of signers
+        assert_eq!(
+            rules.evaluate(U256::ZERO, &[Human, AiCfo, AiSecurity, AiAnalyst]),
+            PolicyDecision::Blocked(vec![PolicyViolation::HumanSignatures { have: 1, need: 2 }])
+        );
+        assert_eq!(
+            rules.evaluate(U256::ZERO, &[Human, Human, Human, AiCfo]),
+            PolicyDecision::Blocked(vec![PolicyViolation::AiApprovals { have: 1, need: 2 }])
+        );
+    }
+
+    #[test]
+    fn value_cap_is_inclusive() {
+        let rules = engine(0, 0, 0, Some(1_000));
+
+        assert!(rules.evaluate(U256::from(1_000), &[]).is_executable());
+        assert_eq!(
+            rules.evaluate(U256::from(1_001), &[]),
+            PolicyDecision::Blocked(vec![PolicyViolation::ValueCap {
+                value: U256::from(1_001),
+                cap: U256::from(1_000),
+            }])
+        );
+    }
+
+    #[test]
+    fn blocked_decision_lists_every_violation() {
+        let decision = engine(4, 2, 2, Some(10)).evaluate(U256::from(11), &[Human]);
+
+        assert_eq!(
+            decision,
+            PolicyDecision::Blocked(vec![
+                PolicyViolation::Signatures { have: 1, need: 4 },
+                PolicyViolation::HumanSignatures { have: 1, need: 2 },
+                PolicyViolation::AiApprovals { have: 0, need: 2 },
+                PolicyViolation::ValueCap {
+                    value: U256::from(11),
+                    cap: U256::from(10),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn rules_from_config_only_carry_the_threshold() {
+        let config = SafeConfig {
+            safe_address: String::new(),
+            required_signatures: 4,
+            total_signers: 5,
+            human_signers: Vec::new(),
+            ai_signers: Vec::new(),
+        };
+
+        assert_eq!(
+            PolicyRules::from_config(&config),
+            PolicyRules {
+                min_signatures: 4,
+                ..PolicyRules::default()
+            }
+        );
+    }
+
+    #[test]
+    fn decision_serializes_with_its_violations() {
+        let json =
+            serde_json::to_value(PolicyDecision::Blocked(vec![PolicyViolation::Signatures {
+                have: 3,
+                need: 4,
+            }]))
+            .unwrap();
+
+        assert_eq!(json["decision"], "blocked");
+        assert_eq!(json["violations"][0]["rule"], "signatures");
+        assert_eq!(json["violations"][0]["need"], 4);
+    }
+}
@@ -0,0 +1,198 @@
+use axum::{extract::Request, middleware::Next, Router};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{net::TcpListener, sync::Notify};
+use tracing::{info, warn};
+
+pub const DEFAULT_SHUTDOWN_DRAIN_SECS: u64 = 30;
+
+// How long in-flight requests get to finish once a shutdown signal arrives
+pub fn drain_timeout_from_env() -> Duration {
+    let secs = std::env::var("SHUTDOWN_DRAIN_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_SHUTDOWN_DRAIN_SECS);
+    Duration::from_secs(secs)
+}
+
+// Decrements on drop so cancelled requests don't leave the count inflated
+struct InFlightGuard(Arc<AtomicUsize>);
+
+impl InFlightGuard {
+    fn new(counter: Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+// Serves `app` until SIGINT/SIGTERM, then lets in-flight requests drain for up
+// to `drain`; anything still running after that is abandoned.
+pub async fn serve(listener: TcpListener, app: Router, drain: Duration) -> std::io::Result<()> {
+    serve_until(listener, app, drain, shutdown_signal()).await
+}
+
+// `serve`, with shutdown triggered by `shutdown` completing instead of a signal
+async fn serve_until(
+    listener: TcpListener,
+    app: Router,
+    drain: Duration,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let counter = in_flight.clone();
+    let app = app.layer(axum::middleware::from_fn(
+        move |req: Request, next: Next| {
+            let guard = InFlightGuard::new(counter.clone());
+            async move {
+                let response = next.run(req).await;
+                drop(guard);
+                response
+            }
+        },
+    ));
+
+    let shutdown_started = Arc::new(Notify::new());
+    let notify = shutdown_started.clone();
+    // Connect info lets middleware key on the client address (e.g. rate limiting)
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        shutdown.await;
+        info!("Shutdown signal received, draining for up to {:?}", drain);
+        notify.notify_one();
+    });
+
+    tokio::select! {
+        result = server => result,
+        _ = async {
+            shutdown_started.notified().await;
+            tokio::time::sleep(drain).await;
+        } => {
+            warn!(
+                "Drain timeout of {:?} elapsed, abandoning {} in-flight requests",
+                drain,
+                in_flight.load(Ordering::SeqCst)
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+        sync::oneshot,
+        task::JoinHandle,
+    };
+
+    // Server whose `/work` takes `work` to answer, and a trigger for its shutdown
+    async fn server(
+        work: Duration,
+        drain: Duration,
+    ) -> (
+        SocketAddr,
+        oneshot::Sender<()>,
+        JoinHandle<std::io::Result<()>>,
+    ) {
+        let app = Router::new().route(
+            "/work",
+            get(move || async move {
+                tokio::time::sleep(work).await;
+                "done"
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = oneshot::channel::<()>();
+        let handle = tokio::spawn(serve_until(listener, app, drain, async {
+            let _ = stopped.await;
+        }));
+        (addr, stop, handle)
+    }
+
+    async fn start_request(addr: SocketAddr) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /work HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        // Let the request reach the handler before shutdown starts
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        stream
+    }
+
+    #[tokio::test]
+    async fn in_flight_requests_finish_within_the_drain() {
+        let (addr, stop, handle) =
+            server(Duration::from_millis(300), Duration::from_secs(10)).await;
+        let mut stream = start_request(addr).await;
+
+        stop.send(()).unwrap();
+
+        let mut response = String::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with("done"));
+        let served = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        assert!(served.unwrap().unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn drain_timeout_abandons_stuck_requests() {
+        let (addr, stop, handle) =
+            server(Duration::from_secs(60), Duration::from_millis(200)).await;
+        let _stream = start_request(addr).await;
+
+        stop.send(()).unwrap();
+
+        // Returns once the drain runs out, not when the 60s request would finish
+        let served = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        assert!(served.unwrap().unwrap().is_ok());
+    }
+}
@@ -0,0 +1,174 @@
+use alloy_primitives::{eip191_hash_message, Address, Signature as EcdsaSignature, B256};
+use serde::{Deserialize, Serialize};
+
+pub const SIGNATURE_LENGTH: usize = 65;
+
+// How an owner produced an ECDSA signature, as encoded in the Safe's `v` byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VScheme {
+    // v = 27/28: the Safe tx hash was signed directly (eth_signTypedData).
+    TypedData,
+    // v = 31/32: signed via eth_sign, so the hash carries the EIP-191 prefix.
+    EthSign,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveredSignature {
+    pub signer: Address,
+    pub v_scheme: VScheme,
+    pub low_s: bool,
+}
+
+// Hardware wallets (Ledger/Trezor) may return `v` as a raw recovery id (0/1),
+// or 4/5 once the eth_sign marker is added to it; the Safe reads those values
+// as contract/approved-hash signatures, so they have to be lifted to 27/28 and
+// 31/32 before being stored or submitted.
+pub fn normalize_v(signature: &mut [u8]) {
+    if signature.len() == SIGNATURE_LENGTH && matches!(signature[64], 0 | 1 | 4 | 5) {
+        signature[64] += 27;
+    }
+}
+
+// The digest a signature of `scheme` actually covers: the Safe tx hash itself,
+// or its EIP-191 prefixed hash for eth_sign.
+pub fn signed_digest(hash: B256, scheme: VScheme) -> B256 {
+    match scheme {
+        VScheme::TypedData => hash,
+        VScheme::EthSign => eip191_hash_message(hash),
+    }
+}
+
+// Recovers the signer of a 65-byte `r || s || v` signature over a Safe tx hash,
+// following the same `v` conventions as `Safe.checkSignatures`.
+pub fn recover_signer(hash: B256, signature: &[u8]) -> Result<RecoveredSignature, String> {
+    if signature.len() != SIGNATURE_LENGTH {
+        return Err(format!(
+            "Invalid signature length: expected {} bytes, got {}",
+            SIGNATURE_LENGTH,
+            signature.len()
+        ));
+    }
+
+    let mut signature = signature.to_vec();
+    normalize_v(&mut signature);
+
+    let v = signature[64];
+    let (v_scheme, parity) = match v {
+        27 | 28 => (VScheme::TypedData, v == 28),
+        31 | 32 => (VScheme::EthSign, v == 32),
+        _ => return Err(format!("Unsupported signature v value: {}", v)),
+    };
+    let prehash = signed_digest(hash, v_scheme);
+
+    let sig = EcdsaSignature::from_bytes_and_parity(&signature[..64], parity);
+    let signer = sig
+        .recover_address_from_prehash(&prehash)
+        .map_err(|e| format!("Signature recovery failed: {}", e))?;
+
+    Ok(RecoveredSignature {
+        signer,
+        v_scheme,
+        low_s: sig.normalize_s().is_none(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::{local::PrivateKeySigner, SignerSync};
+
+    fn owner() -> PrivateKeySigner {
+        PrivateKeySigner::from_bytes(&B256::with_last_byte(1)).unwrap()
+    }
+
+    const HASH: B256 = B256::repeat_byte(0xab);
+
+    #[test]
+    fn typed_data_signature_recovers_over_the_hash() {
+        let signature = owner().sign_hash_sync(&HASH).unwrap().as_bytes();
+
+        let recovered = recover_signer(HASH, &signature).unwrap();
+
+        assert_eq!(recovered.signer, owner().address());
+        assert_eq!(recovered.v_scheme, VScheme::TypedData);
+        assert!(recovered.low_s);
+    }
+
+    #[test]
+    fn eth_sign_signature_recovers_over_the_prefixed_hash() {
+        let mut signature = owner()
+            .sign_message_sync(HASH.as_slice())
+            .unwrap()
+            .as_bytes();
+        signature[64] += 4;
+
+        let recovered = recover_signer(HASH, &signature).unwrap();
+
+        assert_eq!(recovered.signer, owner().address());
+        assert_eq!(recovered.v_scheme, VScheme::EthSign);
+    }
+
+    #[test]
+    fn eth_sign_signature_without_the_marker_recovers_someone_else() {
+        // Signed with the EIP-191 prefix but labelled as typed data
+        let signature = owner()
+            .sign_message_sync(HASH.as_slice())
+            .unwrap()
+            .as_bytes();
+
+        let recovered = recover_signer(HASH, &signature).unwrap();
+
+        assert_ne!(recovered.signer, owner().address());
+    }
+
+    #[test]
+    fn raw_recovery_id_is_lifted_to_27_28() {
+        let mut signature = owner().sign_hash_sync(&HASH).unwrap().as_bytes();
+        signature[64] -= 27;
+
+        let recovered = recover_signer(HASH, &signature).unwrap();
+
+        assert_eq!(recovered.signer, owner().address());
+        assert_eq!(recovered.v_scheme, VScheme::TypedData);
+    }
+
+    #[test]
+    fn raw_recovery_id_with_eth_sign_marker_is_lifted_to_31_32() {
+        let mut signature = owner()
+            .sign_message_sync(HASH.as_slice())
+            .unwrap()
+            .as_bytes();
+        signature[64] -= 27 - 4;
+
+        let mut normalized = signature;
+        normalize_v(&mut normalized);
+        assert!(matches!(normalized[64], 31 | 32));
+
+        let recovered = recover_signer(HASH, &signature).unwrap();
+
+        assert_eq!(recovered.signer, owner().address());
+        assert_eq!(recovered.v_scheme, VScheme::EthSign);
+    }
+
+    #[test]
+    fn approved_hash_signature_is_not_an_owner_signature() {
+        // Safe's v = 1 form: r = owner, s = 0. It needs an on-chain approveHash,
+        // so it must never recover to the owner off-chain.
+        let mut signature = [0u8; SIGNATURE_LENGTH];
+        signature[12..32].copy_from_slice(owner().address().as_slice());
+        signature[64] = 1;
+
+        assert!(recover_signer(HASH, &signature).is_err());
+    }
+
+    #[test]
+    fn unknown_v_and_bad_lengths_are_rejected() {
+        let mut unknown_v = owner().sign_hash_sync(&HASH).unwrap().as_bytes();
+        unknown_v[64] = 29;
+
+        assert!(recover_signer(HASH, &unknown_v).is_err());
+        assert!(recover_signer(HASH, &[0u8; 64]).is_err());
+        assert!(recover_signer(HASH, &[0u8; 66]).is_err());
+    }
+}
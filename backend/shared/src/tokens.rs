@@ -0,0 +1,229 @@
+use alloy::{providers::Provider, sol};
+use alloy_primitives::{utils::format_units, Address, U256};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::RwLock};
+
+use crate::{Result, SafeWalletError};
+
+sol! {
+    #[sol(rpc)]
+    interface IERC20Metadata {
+        function decimals() external view returns (uint8);
+        function symbol() external view returns (string);
+    }
+}
+
+// Display metadata for an ERC20 token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+// Known tokens shared by balance reporting, calldata decoding and budgets.
+// Configured tokens are loaded once; anything else is read from the token
+// contract on first use and cached.
+pub struct TokenRegistry {
+    configured: Vec<Address>,
+    tokens: RwLock<HashMap<Address, TokenInfo>>,
+}
+
+impl TokenRegistry {
+    pub fn new(configured: HashMap<Address, TokenInfo>) -> Self {
+        let mut addresses: Vec<Address> = configured.keys().copied().collect();
+        addresses.sort();
+        Self {
+            configured: addresses,
+            tokens: RwLock::new(configured),
+        }
+    }
+
+    // Reads `KNOWN_TOKENS`, e.g. `0xabc..:USDT:6,0xdef..:WKAIA:18`.
+    pub fn from_env() -> Result<Self> {
+        let spec = std::env::var("KNOWN_TOKENS").unwrap_or_default();
+        Ok(Self::new(Self::parse(&spec)?))
+    }
+
+    // Parses comma-separated `address:symbol:decimals` entries.
+    pub fn parse(spec: &str) -> Result<HashMap<Address, TokenInfo>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let invalid = || {
+                    SafeWalletError::ValidationError(format!(
+                        "Invalid token entry '{}', expected address:symbol:decimals",
+                        entry
+                    ))
+                };
+                let mut parts = entry.split(':');
+                let address = parts
+                    .next()
+                    .and_then(|a| a.parse::<Address>().ok())
+                    .ok_or_else(invalid)?;
+                let symbol = parts.next().filter(|s| !s.is_empty()).ok_or_else(invalid)?;
+                let decimals = parts
+                    .next()
+                    .and_then(|d| d.parse::<u8>().ok())
+                    .ok_or_else(invalid)?;
+                if parts.next().is_some() {
+                    return Err(invalid());
+                }
+                Ok((
+                    address,
+                    TokenInfo {
+                        symbol: symbol.to_string(),
+                        decimals,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    // Tokens listed in configuration, as opposed to ones cached from chain.
+    pub fn configured(&self) -> &[Address] {
+        &self.configured
+    }
+
+    // Configured or previously resolved metadata, without touching the chain.
+    pub fn get(&self, token: Address) -> Option<TokenInfo> {
+        self.tokens
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&token)
+            .cloned()
+    }
+
+    pub fn insert(&self, token: Address, info: TokenInfo) {
+        self.tokens
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(token, info);
+    }
+
+    // Returns cached metadata, falling back to the token's `decimals()` and
+    // `symbol()`. A token without `symbol()` is still usable and is shown by address.
+    pub async fn resolve<P: Provider>(&self, provider: &P, token: Address) -> Result<TokenInfo> {
+        if let Some(info) = self.get(token) {
+            return Ok(info);
+        }
+
+        let contract = IERC20Metadata::new(token, provider);
+        let decimals = contract.decimals().call().await.map_err(|e| {
+            SafeWalletError::BlockchainError(format!("decimals() failed for {}: {}", token, e))
+        })?;
+        let symbol = contract
+            .symbol()
+            .call()
+            .await
+            .unwrap_or_else(|_| token.to_string());
+
+        let info = TokenInfo { symbol, decimals };
+        self.insert(token, info.clone());
+        Ok(info)
+    }
+
+    // `amount` in whole-token units, if the token's decimals are known.
+    pub fn format_amount(&self, token: Address, amount: U256) -> Option<String> {
+        let info = self.get(token)?;
+        format_units(amount, info.decimals).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{primitives::Bytes, providers::ProviderBuilder, sol_types::SolCall};
+    use axum::{routing::post, Json, Router};
+    use serde_json::Value;
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    const USDT: Address = Address::repeat_byte(0x7c);
+    const WKAIA: Address = Address::repeat_byte(0x7d);
+
+    fn registry() -> TokenRegistry {
+        TokenRegistry::new(HashMap::from([(
+            USDT,
+            TokenInfo {
+                symbol: "USDT".to_string(),
+                decimals: 6,
+            },
+        )]))
+    }
+
+    // Node where every token reports 18 decimals and symbol "WKAIA"; returns its
+    // URL and the eth_calls it received
+    async fn token_node() -> (String, Arc<Mutex<Vec<Value>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(call): Json<Value>| async move {
+                let params = &call["params"];
+                let input = params[0]["input"].as_str().or(params[0]["data"].as_str());
+                let input = Bytes::from_str(input.unwrap_or("0x")).unwrap_or_default();
+                let result = match input.get(..4) {
+                    Some(s) if s == IERC20Metadata::decimalsCall::SELECTOR => {
+                        IERC20Metadata::decimalsCall::abi_encode_returns(&18)
+                    }
+                    Some(s) if s == IERC20Metadata::symbolCall::SELECTOR => {
+                        IERC20Metadata::symbolCall::abi_encode_returns(&"WKAIA".to_string())
+                    }
+                    _ => Vec::new(),
+                };
+                recorded.lock().unwrap().push(call.clone());
+                Json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": call["id"],
+                    "result": Bytes::from(result),
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, calls)
+    }
+
+    #[tokio::test]
+    async fn known_token_is_resolved_without_the_chain() {
+        // Nothing listens here, so any chain read would fail
+        let provider = ProviderBuilder::new().connect_http("http://127.0.0.1:9".parse().unwrap());
+        let registry = registry();
+
+        let info = registry.resolve(&provider, USDT).await.unwrap();
+
+        assert_eq!(info.symbol, "USDT");
+        assert_eq!(info.decimals, 6);
+        assert_eq!(
+            registry
+                .format_amount(USDT, U256::from(1_500_000u64))
+                .as_deref(),
+            Some("1.500000")
+        );
+        assert!(registry.resolve(&provider, WKAIA).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_token_is_read_from_chain_once() {
+        let (url, calls) = token_node().await;
+        let provider = ProviderBuilder::new().connect_http(url.parse().unwrap());
+        let registry = registry();
+        assert_eq!(registry.get(WKAIA), None);
+
+        let info = registry.resolve(&provider, WKAIA).await.unwrap();
+        let again = registry.resolve(&provider, WKAIA).await.unwrap();
+
+        assert_eq!(info.symbol, "WKAIA");
+        assert_eq!(info.decimals, 18);
+        assert_eq!(again, info);
+        // decimals() and symbol(), then served from the cache
+        assert_eq!(calls.lock().unwrap().len(), 2);
+        assert_eq!(registry.get(WKAIA), Some(info));
+        // Cached from chain, not configured
+        assert_eq!(registry.configured(), &[USDT]);
+    }
+}
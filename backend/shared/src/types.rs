@@ -1,5 +1,7 @@
+use alloy_primitives::{Address, U256};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
@@ -14,15 +16,88 @@ pub struct Transaction {
     pub status: TransactionStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Canonical status shared by all services. Serializes as snake_case; the aliases
+// accept the older short names and the orchestrator's former PascalCase output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TransactionStatus {
+    #[serde(alias = "Pending")]
     Pending,
-    Collecting,
-    Ready,
+    #[serde(
+        alias = "collecting",
+        alias = "Collecting",
+        alias = "CollectingSignatures"
+    )]
+    CollectingSignatures,
+    #[serde(alias = "ready", alias = "Ready", alias = "ReadyToExecute")]
+    ReadyToExecute,
+    // execTransaction has been submitted and the outcome isn't known yet
+    #[serde(alias = "Executing")]
+    Executing,
+    #[serde(alias = "Executed")]
     Executed,
+    #[serde(alias = "Failed")]
     Failed,
+    #[serde(alias = "Cancelled")]
     Cancelled,
+    #[serde(alias = "Invalidated")]
+    Invalidated,
+}
+
+impl TransactionStatus {
+    // Still heading for execution, i.e. holding on to its nonce.
+    pub fn is_in_flight(self) -> bool {
+        matches!(
+            self,
+            Self::Pending | Self::CollectingSignatures | Self::ReadyToExecute | Self::Executing
+        )
+    }
+
+    // Whether the state machine allows moving from `self` to `next`. Executed
+    // and Cancelled are final; Failed and Invalidated only give way to an
+    // execution observed on-chain.
+    pub fn can_transition_to(self, next: Self) -> bool {
+        use TransactionStatus::*;
+        matches!(
+            (self, next),
+            (Pending, CollectingSignatures | Failed | Cancelled | Invalidated)
+                | (
+                    CollectingSignatures,
+                    ReadyToExecute | Executed | Failed | Cancelled | Invalidated
+                )
+                | (
+                    ReadyToExecute,
+                    CollectingSignatures | Executing | Executed | Failed | Cancelled | Invalidated
+                )
+                // Back to ReadyToExecute/CollectingSignatures when nothing was submitted
+                | (
+                    Executing,
+                    ReadyToExecute | CollectingSignatures | Executed | Failed
+                )
+                | (Failed | Invalidated, Executed)
+        )
+    }
+
+    // Compare-and-set: moves to `to` only if the status is still `from_expected`
+    // and the move is allowed, so a stale writer can't regress a newer status.
+    pub fn transition(self, from_expected: Self, to: Self) -> Result<Self, InvalidTransition> {
+        if self != from_expected || !self.can_transition_to(to) {
+            return Err(InvalidTransition {
+                from: self,
+                expected: from_expected,
+                to,
+            });
+        }
+        Ok(to)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Invalid status transition {from:?} -> {to:?} (expected from {expected:?})")]
+pub struct InvalidTransition {
+    pub from: TransactionStatus,
+    pub expected: TransactionStatus,
+    pub to: TransactionStatus,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,7 +108,7 @@ pub struct Signature {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SignerType {
     Human,
@@ -51,10 +126,196 @@ pub struct SafeConfig {
     pub ai_signers: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Off-chain context for a proposal; never part of the signed hash
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProposalMetadata {
-    pub proposer: String,
-    pub description: Option<String>,
-    pub deadline: Option<DateTime<Utc>>,
+    pub reference: Option<String>, // External memo, e.g. an invoice number
+    #[serde(default)]
     pub tags: Vec<String>,
+    pub proposer: Option<Address>, // Receives the execution-result callback
+    #[serde(default)]
+    pub proposer_verified: bool, // Proposer proved authorship with an EIP-712 Proposal signature
+    #[serde(default)]
+    pub admin_operation: Option<AdminOperation>, // Decoded owner/threshold/guard change, if any
+    #[serde(default)]
+    pub ai_unavailable: bool, // Required AI review was skipped because the service failed
+}
+
+// A proposal that changes the Safe's own owners, threshold, guard, modules or
+// fallback handler, or delegatecalls code that could do any of that. These can
+// hand over or lock the wallet, so they need every human signer on top of the
+// usual threshold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AdminOperation {
+    AddOwner {
+        owner: Address,
+        threshold: U256,
+    },
+    RemoveOwner {
+        prev_owner: Address,
+        owner: Address,
+        threshold: U256,
+    },
+    SwapOwner {
+        prev_owner: Address,
+        old_owner: Address,
+        new_owner: Address,
+    },
+    ChangeThreshold {
+        threshold: U256,
+    },
+    SetGuard {
+        guard: Address,
+    },
+    EnableModule {
+        module: Address,
+    },
+    DisableModule {
+        prev_module: Address,
+        module: Address,
+    },
+    SetFallbackHandler {
+        handler: Address,
+    },
+    // Runs `target`'s code with the Safe's storage, so it can rewrite anything
+    DelegateCall {
+        target: Address,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TransactionStatus::*;
+
+    const ALL: [TransactionStatus; 8] = [
+        Pending,
+        CollectingSignatures,
+        ReadyToExecute,
+        Executing,
+        Executed,
+        Failed,
+        Cancelled,
+        Invalidated,
+    ];
+
+    #[test]
+    fn final_statuses_never_move() {
+        for to in ALL {
+            assert!(!Executed.can_transition_to(to), "Executed -> {:?}", to);
+            assert!(!Cancelled.can_transition_to(to), "Cancelled -> {:?}", to);
+        }
+    }
+
+    #[test]
+    fn failed_and_invalidated_only_give_way_to_execution() {
+        for from in [Failed, Invalidated] {
+            for to in ALL {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    to == Executed,
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn execution_paths_are_allowed() {
+        assert!(Pending.can_transition_to(CollectingSignatures));
+        assert!(CollectingSignatures.can_transition_to(ReadyToExecute));
+        assert!(ReadyToExecute.can_transition_to(Executing));
+        assert!(Executing.can_transition_to(Executed));
+        assert!(Executing.can_transition_to(ReadyToExecute));
+        assert!(!Pending.can_transition_to(ReadyToExecute));
+        assert!(!CollectingSignatures.can_transition_to(Executing));
+    }
+
+    #[test]
+    fn transition_is_compare_and_set() {
+        assert_eq!(
+            CollectingSignatures.transition(CollectingSignatures, ReadyToExecute),
+            Ok(ReadyToExecute)
+        );
+        // A writer that read ReadyToExecute can't act on a proposal that has moved on
+        assert_eq!(
+            Executed.transition(ReadyToExecute, Executing),
+            Err(InvalidTransition {
+                from: Executed,
+                expected: ReadyToExecute,
+                to: Executing,
+            })
+        );
+        // Nor can a matching status make a forbidden move
+        assert!(Cancelled
+            .transition(Cancelled, CollectingSignatures)
+            .is_err());
+    }
+
+    #[test]
+    fn statuses_round_trip_as_snake_case() {
+        for status in ALL {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(json, json.to_lowercase());
+            assert_eq!(
+                serde_json::from_str::<TransactionStatus>(&json).unwrap(),
+                status
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&CollectingSignatures).unwrap(),
+            "\"collecting_signatures\""
+        );
+    }
+
+    #[test]
+    fn old_status_names_still_parse() {
+        for (old, status) in [
+            ("Pending", Pending),
+            ("CollectingSignatures", CollectingSignatures),
+            ("collecting", CollectingSignatures),
+            ("Collecting", CollectingSignatures),
+            ("ReadyToExecute", ReadyToExecute),
+            ("ready", ReadyToExecute),
+            ("Ready", ReadyToExecute),
+            ("Executing", Executing),
+            ("Executed", Executed),
+            ("Failed", Failed),
+            ("Cancelled", Cancelled),
+            ("Invalidated", Invalidated),
+        ] {
+            assert_eq!(
+                serde_json::from_value::<TransactionStatus>(old.into()).unwrap(),
+                status,
+                "{}",
+                old
+            );
+        }
+    }
+
+    #[test]
+    fn proposal_metadata_reads_older_records() {
+        // Stored before the flags and admin_operation existed
+        let metadata: ProposalMetadata =
+            serde_json::from_str(r#"{"reference":"INV-7","proposer":null}"#).unwrap();
+
+        assert_eq!(metadata.reference.as_deref(), Some("INV-7"));
+        assert!(metadata.tags.is_empty());
+        assert!(!metadata.proposer_verified);
+        assert_eq!(metadata.admin_operation, None);
+    }
+
+    #[test]
+    fn admin_operation_is_tagged_by_kind() {
+        let op = AdminOperation::ChangeThreshold {
+            threshold: U256::from(2),
+        };
+        let json = serde_json::to_value(&op).unwrap();
+
+        assert_eq!(json["kind"], "change_threshold");
+        assert_eq!(serde_json::from_value::<AdminOperation>(json).unwrap(), op);
+    }
 }
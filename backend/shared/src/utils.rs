@@ -1,25 +1,309 @@
-use alloy_primitives::{Address, B256, U256};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::{sol, Eip712Domain, SolStruct};
+
+use crate::signature::SIGNATURE_LENGTH;
+
+sol! {
+    // The struct Safe signs over (EIP-712 `SafeTx`).
+    #[derive(Debug)]
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+}
+
+// Safe >= 1.3 domain: `EIP712Domain(uint256 chainId,address verifyingContract)`.
+pub fn safe_domain(chain_id: u64, safe_address: Address) -> Eip712Domain {
+    Eip712Domain::new(
+        None,
+        None,
+        Some(U256::from(chain_id)),
+        Some(safe_address),
+        None,
+    )
+}
+
+// `keccak256(0x1901 || domainSeparator || hashStruct(SafeTx))`, the value
+// `Safe.getTransactionHash` returns.
+pub fn safe_tx_hash(tx: &SafeTx, chain_id: u64, safe_address: Address) -> B256 {
+    let mut data = Vec::with_capacity(66);
+    data.extend_from_slice(&[0x19, 0x01]);
+    data.extend_from_slice(safe_domain(chain_id, safe_address).separator().as_slice());
+    data.extend_from_slice(tx.eip712_hash_struct().as_slice());
+    keccak256(data)
+}
 
 pub fn parse_address(addr: &str) -> Result<Address, String> {
     addr.parse::<Address>()
         .map_err(|e| format!("Invalid address: {}", e))
 }
 
+// EIP-55 mixed-case form, e.g. for display and logs.
+pub fn to_checksummed(addr: &Address) -> String {
+    addr.to_checksum(None)
+}
+
+// Like `parse_address`, but a mixed-case input must carry a valid EIP-55
+// checksum; all-lowercase or all-uppercase input has none to check.
+pub fn parse_and_checksum(addr: &str) -> Result<Address, String> {
+    let hex_part = addr.trim_start_matches("0x");
+    let mixed_case = hex_part.chars().any(|c| c.is_ascii_lowercase())
+        && hex_part.chars().any(|c| c.is_ascii_uppercase());
+    if !mixed_case {
+        return parse_address(addr);
+    }
+    Address::parse_checksummed(addr, None)
+        .map_err(|e| format!("Invalid checksummed address {}: {}", addr, e))
+}
+
 pub fn parse_u256(value: &str) -> Result<U256, String> {
     value
         .parse::<U256>()
         .map_err(|e| format!("Invalid U256 value: {}", e))
 }
 
+// Decimals of KAIA: 1 KAIA = 10^18 wei (kei).
+pub const KAIA_DECIMALS: usize = 18;
+
+// Decimal KAIA such as `"1.5"` to wei, exactly. More than 18 fractional
+// digits can't be represented and is an error rather than being rounded.
+pub fn parse_kaia(amount: &str) -> Result<U256, String> {
+    let amount = amount.trim();
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(format!("Invalid KAIA amount: '{}'", amount));
+    }
+    if fraction.len() > KAIA_DECIMALS {
+        return Err(format!(
+            "KAIA amount '{}' has more than {} decimal places",
+            amount, KAIA_DECIMALS
+        ));
+    }
+
+    let digits = format!("{}{:0<width$}", whole, fraction, width = KAIA_DECIMALS);
+    digits
+        .parse::<U256>()
+        .map_err(|e| format!("Invalid KAIA amount '{}': {}", amount, e))
+}
+
+// Wei as a KAIA string without trailing zeros, e.g. `1500000000000000000` -> `"1.5"`.
+pub fn format_wei(value: U256) -> String {
+    let unit = U256::from(10u64).pow(U256::from(KAIA_DECIMALS));
+    let (whole, fraction) = value.div_rem(unit);
+    if fraction.is_zero() {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0>width$}", fraction.to_string(), width = KAIA_DECIMALS);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
 pub fn parse_b256(hash: &str) -> Result<B256, String> {
     hash.parse::<B256>()
         .map_err(|e| format!("Invalid B256 hash: {}", e))
 }
 
-pub fn validate_signature(signature: &str) -> bool {
-    signature.starts_with("0x") && signature.len() == 132
+// Checks a hex ECDSA signature is well-formed: 65-byte `r || s || v` with
+// `v` of 27/28 (or a raw 0/1 recovery id), or a 64-byte EIP-2098 compact one.
+pub fn validate_signature(signature: &str) -> Result<(), String> {
+    let hex_part = signature
+        .strip_prefix("0x")
+        .ok_or_else(|| "Signature must be 0x-prefixed hex".to_string())?;
+    let bytes = hex::decode(hex_part).map_err(|e| format!("Signature is not valid hex: {}", e))?;
+
+    match bytes.len() {
+        64 => Ok(()),
+        SIGNATURE_LENGTH => match bytes[64] {
+            0 | 1 | 27 | 28 => Ok(()),
+            v => Err(format!(
+                "Unsupported signature v value {}: expected 27/28 or 0/1",
+                v
+            )),
+        },
+        len => Err(format!(
+            "Invalid signature length: expected 65 bytes (or 64 compact), got {}",
+            len
+        )),
+    }
 }
 
-pub fn calculate_safe_hash(_to: &str, _value: &str, _data: &str, nonce: u64) -> String {
-    format!("0x{:064x}", nonce)
+// Safe tx hash of a plain call (operation 0, no gas refund) from string inputs,
+// as `0x`-prefixed hex.
+pub fn calculate_safe_hash(
+    safe_address: &str,
+    chain_id: u64,
+    to: &str,
+    value: &str,
+    data: &str,
+    nonce: u64,
+) -> Result<String, String> {
+    let data = if data.is_empty() { "0x" } else { data };
+    let tx = SafeTx {
+        to: parse_address(to)?,
+        value: parse_u256(value)?,
+        data: data
+            .parse::<Bytes>()
+            .map_err(|e| format!("Invalid calldata: {}", e))?,
+        operation: 0,
+        safeTxGas: U256::ZERO,
+        baseGas: U256::ZERO,
+        gasPrice: U256::ZERO,
+        gasToken: Address::ZERO,
+        refundReceiver: Address::ZERO,
+        nonce: U256::from(nonce),
+    };
+    Ok(safe_tx_hash(&tx, chain_id, parse_address(safe_address)?).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAFE: &str = "0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a";
+    const TO: &str = "0x1111111111111111111111111111111111111111";
+
+    #[test]
+    fn safe_typehashes_match_the_contract() {
+        assert_eq!(
+            keccak256(SafeTx::eip712_encode_type().as_bytes()).to_string(),
+            "0xbb8310d486368db6bd6f849402fdd73ad53d316b5a4b2644ad6efe0f941286d8"
+        );
+        assert_eq!(
+            keccak256("EIP712Domain(uint256 chainId,address verifyingContract)").to_string(),
+            "0x47e79534a245952e8b16893a336b85a3d9ea9fa8c573f3d803afb92a79469218"
+        );
+    }
+
+    #[test]
+    fn calculate_safe_hash_matches_fixed_vectors() {
+        assert_eq!(
+            calculate_safe_hash(SAFE, 1001, TO, "1000000000000000000", "", 0).unwrap(),
+            "0x162cb9c3cea1d4173bdfa9c963cc50d58ac3ce63590d14c4e8f92946c21fcb14"
+        );
+        assert_eq!(
+            calculate_safe_hash(SAFE, 8217, TO, "0", "0xdeadbeef", 7).unwrap(),
+            "0xbba1dfeb351c728ab63a6a0c1a102f067a0cc01ea97cf520dd6d0a393c3a2896"
+        );
+    }
+
+    #[test]
+    fn calculate_safe_hash_depends_on_chain_and_safe() {
+        let base = calculate_safe_hash(SAFE, 1001, TO, "1", "0x", 0).unwrap();
+
+        assert_ne!(
+            calculate_safe_hash(SAFE, 8217, TO, "1", "0x", 0).unwrap(),
+            base
+        );
+        assert_ne!(
+            calculate_safe_hash(TO, 1001, TO, "1", "0x", 0).unwrap(),
+            base
+        );
+    }
+
+    #[test]
+    fn calculate_safe_hash_rejects_bad_input() {
+        assert!(calculate_safe_hash("0x1234", 1001, TO, "1", "0x", 0).is_err());
+        assert!(calculate_safe_hash(SAFE, 1001, "not-an-address", "1", "0x", 0).is_err());
+        assert!(calculate_safe_hash(SAFE, 1001, TO, "-1", "0x", 0).is_err());
+        assert!(calculate_safe_hash(SAFE, 1001, TO, "1", "0xzz", 0).is_err());
+    }
+
+    // EIP-55 test vector
+    const CHECKSUMMED: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn parse_and_checksum_accepts_checksummed_and_single_case() {
+        let address = parse_and_checksum(CHECKSUMMED).unwrap();
+
+        assert_eq!(to_checksummed(&address), CHECKSUMMED);
+        assert_eq!(parse_and_checksum(&CHECKSUMMED.to_lowercase()), Ok(address));
+        assert_eq!(
+            parse_and_checksum(&format!("0x{}", CHECKSUMMED[2..].to_uppercase())),
+            Ok(address)
+        );
+    }
+
+    #[test]
+    fn parse_and_checksum_rejects_a_bad_checksum() {
+        // Second character lowered: still mixed case, no longer EIP-55
+        let bad = "0x5aaeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+        let err = parse_and_checksum(bad).unwrap_err();
+
+        assert!(err.contains("Invalid checksummed address"), "{}", err);
+        // The plain parser doesn't look at the checksum
+        assert!(parse_address(bad).is_ok());
+        assert!(parse_and_checksum("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+    }
+
+    #[test]
+    fn parse_kaia_is_exact_to_the_wei() {
+        let kaia = U256::from(10u64).pow(U256::from(18));
+
+        assert_eq!(parse_kaia("1"), Ok(kaia));
+        assert_eq!(
+            parse_kaia(" 1.5 "),
+            Ok(kaia * U256::from(3) / U256::from(2))
+        );
+        assert_eq!(parse_kaia(".5"), parse_kaia("0.5"));
+        assert_eq!(parse_kaia("2."), parse_kaia("2"));
+        assert_eq!(parse_kaia("0.000000000000000001"), Ok(U256::from(1)));
+        assert_eq!(
+            format_wei(parse_kaia("12.345").unwrap()),
+            "12.345".to_string()
+        );
+    }
+
+    #[test]
+    fn parse_kaia_rejects_what_it_cant_represent() {
+        let too_precise = parse_kaia("0.0000000000000000001").unwrap_err();
+        assert!(
+            too_precise.contains("more than 18 decimal places"),
+            "{}",
+            too_precise
+        );
+        for bad in ["", ".", "-1", "1e18", "1,5", "1.2.3", "0x10", "abc"] {
+            assert!(parse_kaia(bad).is_err(), "{:?} parsed", bad);
+        }
+        // Overflows U256 rather than wrapping
+        assert!(parse_kaia(&"9".repeat(80)).is_err());
+    }
+
+    fn hex_signature(v: u8, len: usize) -> String {
+        let mut bytes = vec![0x11; len];
+        if len == 65 {
+            bytes[64] = v;
+        }
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    #[test]
+    fn validate_signature_accepts_canonical_and_compact() {
+        assert_eq!(validate_signature(&hex_signature(27, 65)), Ok(()));
+        assert_eq!(validate_signature(&hex_signature(28, 65)), Ok(()));
+        assert_eq!(validate_signature(&hex_signature(1, 65)), Ok(()));
+        assert_eq!(validate_signature(&hex_signature(0, 64)), Ok(()));
+    }
+
+    #[test]
+    fn validate_signature_rejects_malformed_input() {
+        // Wrong length
+        assert!(validate_signature(&hex_signature(27, 66)).is_err());
+        assert!(validate_signature(&hex_signature(27, 63)).is_err());
+        // Non-hex characters at the right length
+        assert!(validate_signature(&format!("0x{}", "zz".repeat(65))).is_err());
+        // Missing prefix
+        assert!(validate_signature(&hex_signature(27, 65)[2..]).is_err());
+        // v outside 0/1 and 27/28
+        let bad_v = validate_signature(&hex_signature(29, 65)).unwrap_err();
+        assert!(bad_v.contains("v value 29"), "{}", bad_v);
+    }
 }